@@ -26,6 +26,7 @@ fn prepare_appimage_files() {
     let resources_path: std::path::PathBuf = crate_dir().join("resources");
     let assets_path: std::path::PathBuf = resources_path.join("assets");
     let locales_path: std::path::PathBuf = resources_path.join("locales");
+    let licenses_path: std::path::PathBuf = resources_path.join("licenses");
 
     let new_resources_path = target_dir().join("resources");
     let options: fs_extra::dir::CopyOptions = fs_extra::dir::CopyOptions::new();
@@ -61,6 +62,16 @@ fn prepare_appimage_files() {
             new_resources_path.display()
         );
     }
+
+    // Copy License Texts to New Resource Directory
+    let copy_licenses_result = fs_extra::dir::copy(&licenses_path, &new_resources_path, &options);
+    if let Err(error) = copy_licenses_result {
+        println!(
+            "cargo:warning=failed to copy licenses ({}) to new resource directory ({}): {error}",
+            licenses_path.display(),
+            new_resources_path.display()
+        );
+    }
 }
 
 /// Sets environment variables for building
@@ -14,6 +14,10 @@ use winit::platform::web::EventLoopExtWebSys;
 /// Allows sending custom events to the event loop from the outside
 static EVENT_LOOP_PROXY: OnceLock<EventLoopProxy<()>> = OnceLock::new();
 
+/// How often the event loop wakes up to exchange packets with the server
+#[cfg(not(target_family = "wasm"))]
+const NETWORK_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
+
 // http://gameprogrammingpatterns.com/game-loop.html
 // https://zdgeier.com/wgpuintro.html
 // https://sotrh.github.io/learn-wgpu/beginner/tutorial5-textures/#loading-an-image-from-a-file
@@ -43,6 +47,10 @@ pub fn client_game_loop() -> Result<(), String> {
     // This'll be useful for triggering the event loop from the outside when in wait mode
     let _ = EVENT_LOOP_PROXY.set(event_loop.create_proxy());
 
+    // Connection to the listen server or a remote server, if any
+    #[cfg(not(target_family = "wasm"))]
+    let mut network: Option<super::network::ClientNetwork> = super::connect_to_server();
+
     /// Holds the window state in a way that's compatible with async
     #[allow(clippy::items_after_statements)]
     static WINDOW_STATE: Mutex<Option<WindowState>> = Mutex::new(None);
@@ -67,7 +75,12 @@ pub fn client_game_loop() -> Result<(), String> {
         //   and will use [`winit::event_loop::ControlFlow::Poll`] when in game
         window_target.set_control_flow(winit::event_loop::ControlFlow::Wait);
 
-        // Starts exit process when exit bool is set
+        // Nothing asks the player whether they're sure yet, so pending exits are confirmed straight away
+        if utils::exit::is_exit_pending() {
+            utils::exit::confirm_exit();
+        }
+
+        // Stops the event loop once the exit is confirmed
         if utils::exit::is_exiting() {
             // window_target.set_control_flow(winit::event_loop::ControlFlow::Poll);
             window_target.exit();
@@ -79,7 +92,7 @@ pub fn client_game_loop() -> Result<(), String> {
                 event: WindowEvent::CloseRequested,
                 ..
             } => {
-                crate::window::events::close_requested(window_target);
+                crate::window::events::close_requested();
             }
 
             Event::Resumed => {
@@ -127,7 +140,32 @@ pub fn client_game_loop() -> Result<(), String> {
                 event: WindowEvent::KeyboardInput { event, .. },
                 ..
             } => {
-                crate::window::events::pressed_key(event, window_target);
+                if crate::window::events::pressed_key(event) {
+                    request_redraw(&WINDOW_STATE);
+                }
+            }
+
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                if crate::window::events::scrolled_mouse(delta) {
+                    request_redraw(&WINDOW_STATE);
+                }
+            }
+
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                ..
+            } => {
+                crate::window::events::moved_cursor(position);
+            }
+
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                ..
+            } => {
+                crate::window::events::left_cursor();
             }
 
             Event::WindowEvent {
@@ -151,12 +189,24 @@ pub fn client_game_loop() -> Result<(), String> {
                 // Technically, this can fail if another window was resized before this one was created
                 if WINDOW_STATE.lock().unwrap().is_some() {
                     crate::window::events::resized_window(
-                        WINDOW_STATE.lock().unwrap().as_ref().unwrap(),
+                        WINDOW_STATE.lock().unwrap().as_mut().unwrap(),
                         size,
                     );
                 }
             }
 
+            Event::WindowEvent {
+                event: WindowEvent::ScaleFactorChanged { scale_factor, .. },
+                ..
+            } => {
+                if WINDOW_STATE.lock().unwrap().is_some() {
+                    crate::window::events::changed_scale_factor(
+                        WINDOW_STATE.lock().unwrap().as_ref().unwrap(),
+                        scale_factor,
+                    );
+                }
+            }
+
             // Can be used to pause the game
             Event::WindowEvent {
                 event: WindowEvent::Focused(focused),
@@ -172,13 +222,18 @@ pub fn client_game_loop() -> Result<(), String> {
             } => {
                 if WINDOW_STATE.lock().unwrap().is_some() {
                     crate::window::events::requested_redraw(
-                        WINDOW_STATE.lock().unwrap().as_ref().unwrap(),
+                        WINDOW_STATE.lock().unwrap().as_mut().unwrap(),
                     );
                 }
             }
 
             // Last event to ever be executed on shutdown
             Event::LoopExiting => {
+                #[cfg(not(target_family = "wasm"))]
+                if let Some(network) = network.as_mut() {
+                    network.disconnect();
+                }
+
                 crate::window::events::exiting_loop();
             }
 
@@ -190,6 +245,15 @@ pub fn client_game_loop() -> Result<(), String> {
             // New events are incoming
             Event::AboutToWait => {
                 crate::window::events::about_to_wait_event();
+
+                // Wake up regularly to exchange packets with the server
+                #[cfg(not(target_family = "wasm"))]
+                if let Some(network) = network.as_mut() {
+                    network.update();
+                    window_target.set_control_flow(winit::event_loop::ControlFlow::wait_duration(
+                        NETWORK_UPDATE_INTERVAL,
+                    ));
+                }
             }
 
             // Last event to ever be executed on shutdown
@@ -214,6 +278,17 @@ pub fn client_game_loop() -> Result<(), String> {
     Ok(())
 }
 
+/// Asks the window to be redrawn, if it exists
+///
+/// # Panics
+///
+/// May panic if the window state's lock is poisoned
+fn request_redraw(window_state: &Mutex<Option<WindowState>>) {
+    if let Some(window_state) = window_state.lock().unwrap().as_ref() {
+        window_state.window.request_redraw();
+    }
+}
+
 /// Advances game loop by one cycle
 #[must_use]
 pub fn advance_event_loop() -> bool {
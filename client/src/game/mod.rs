@@ -1,7 +1,16 @@
 /// Handles client side game loop
 mod game_loop;
 
-use std::{path::PathBuf, sync::OnceLock};
+/// Handles the connection to the server
+mod network;
+
+use std::{
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+use utils::network::MemorySocket;
 
 #[cfg(target_os = "android")]
 use winit::platform::android::activity::AndroidApp;
@@ -16,6 +25,12 @@ static ANDROID_APP: OnceLock<AndroidApp> = OnceLock::new();
 /// Holds a path to the resources directory
 static RESOURCES_PATH: OnceLock<PathBuf> = OnceLock::new();
 
+/// Holds the socket connected to a listen server running in this process
+static LISTEN_SERVER_SOCKET: Mutex<Option<MemorySocket>> = Mutex::new(None);
+
+/// Holds the address of a remote server to connect to
+static SERVER_ADDRESS: OnceLock<SocketAddr> = OnceLock::new();
+
 #[cfg(target_os = "android")]
 /// Stores a reference to the winit AndroidApp activity
 pub fn store_android_app(app: AndroidApp) {
@@ -49,3 +64,40 @@ pub fn get_resources_path() -> PathBuf {
         PathBuf::from("resources")
     }
 }
+
+/// Stores the socket connected to a listen server running in this process
+///
+/// # Panics
+///
+/// May panic if the socket's lock is poisoned
+pub fn store_listen_server_socket(socket: MemorySocket) {
+    *LISTEN_SERVER_SOCKET.lock().unwrap() = Some(socket);
+}
+
+/// Stores the address of a remote server to connect to
+pub fn store_server_address(address: SocketAddr) {
+    let _address: &SocketAddr = SERVER_ADDRESS.get_or_init(|| address);
+}
+
+/// Connects to the listen server if one was started, otherwise to the stored remote server
+///
+/// # Panics
+///
+/// May panic if the listen server socket's lock is poisoned
+#[cfg(not(target_family = "wasm"))]
+fn connect_to_server() -> Option<network::ClientNetwork> {
+    if let Some(socket) = LISTEN_SERVER_SOCKET.lock().unwrap().take() {
+        debug!("Connecting to listen server...");
+        return Some(network::ClientNetwork::connect_to_listen_server(socket));
+    }
+
+    let address: SocketAddr = *SERVER_ADDRESS.get()?;
+    debug!("Connecting to remote server {address}...");
+    match network::ClientNetwork::connect_to_remote_server(address) {
+        Ok(network) => Some(network),
+        Err(error) => {
+            error!("Could not connect to {address}: {error}");
+            None
+        }
+    }
+}
@@ -0,0 +1,171 @@
+#![cfg(not(target_family = "wasm"))]
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::Instant,
+};
+
+use common::protocol::{
+    Chat, ClientMessage, Disconnect, EntityId, EntitySnapshot, Handshake, ProtocolMessage,
+    ServerMessage, PROTOCOL_VERSION,
+};
+use utils::network::{
+    Channel, DisconnectReason, Endpoint, EndpointConfig, MemorySocket, NetworkEvent, Socket,
+};
+
+/// Socket used by the client, boxed so both memory and UDP sockets can be used
+type ClientSocket = Box<dyn Socket + Send>;
+
+/// Handles the connection to the server and the messages exchanged with it
+pub(crate) struct ClientNetwork {
+    /// Endpoint connected to the server
+    endpoint: Endpoint<ClientSocket>,
+
+    /// Address of the server
+    server: SocketAddr,
+
+    /// Entity controlled by this client once the server welcomed it
+    player: Option<EntityId>,
+
+    /// Most recent snapshot received from the server
+    snapshot: Option<EntitySnapshot>,
+}
+
+impl ClientNetwork {
+    /// Connects to a listen server running in this process
+    pub(crate) fn connect_to_listen_server(socket: MemorySocket) -> Self {
+        let server: SocketAddr = socket.peer_address();
+
+        Self::connect(Box::new(socket), server)
+    }
+
+    /// Connects to a remote server over UDP
+    ///
+    /// # Errors
+    ///
+    /// Errors if a UDP socket cannot be opened
+    pub(crate) fn connect_to_remote_server(server: SocketAddr) -> Result<Self, String> {
+        let socket: UdpSocket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .map_err(|error| format!("Could not bind socket: {error}"))?;
+
+        socket
+            .set_nonblocking(true)
+            .map_err(|error| format!("Could not make socket non-blocking: {error}"))?;
+
+        Ok(Self::connect(Box::new(socket), server))
+    }
+
+    /// Starts connecting to the server over the socket
+    fn connect(socket: ClientSocket, server: SocketAddr) -> Self {
+        let mut endpoint: Endpoint<ClientSocket> = Endpoint::new(socket, EndpointConfig::default());
+        endpoint.connect(server, Instant::now());
+
+        Self {
+            endpoint,
+            server,
+            player: None,
+            snapshot: None,
+        }
+    }
+
+    /// Exchanges packets with the server and handles every received message
+    pub(crate) fn update(&mut self) {
+        self.endpoint.update(Instant::now());
+
+        while let Some(event) = self.endpoint.poll_event() {
+            match event {
+                NetworkEvent::Connected(address) => {
+                    info!("Connected to server {address}...");
+                    self.send(&ClientMessage::Handshake(Handshake {
+                        protocol_version: PROTOCOL_VERSION,
+                        engine_version: crate::build::build_info().crate_info.version.to_string(),
+                        player_name: player_name(),
+                    }));
+                }
+                NetworkEvent::Disconnected(address, reason) => {
+                    warn!("Disconnected from server {address} ({reason})...");
+
+                    if let Some(player) = self.player.take() {
+                        debug!("No longer controlling player {player:?}...");
+                    }
+                }
+                NetworkEvent::Message { payload, .. } => match ServerMessage::decode(&payload) {
+                    Ok(message) => self.handle_message(message),
+                    Err(error) => warn!("Could not decode message from server: {error}"),
+                },
+            }
+        }
+    }
+
+    /// Handles a single message received from the server
+    fn handle_message(&mut self, message: ServerMessage) {
+        match message {
+            ServerMessage::Welcome(welcome) => {
+                debug!(
+                    "Server welcomed player {:?} at tick {} ({} ticks per second)...",
+                    welcome.player, welcome.tick, welcome.tick_rate
+                );
+                self.player = Some(welcome.player);
+            }
+            ServerMessage::Snapshot(snapshot) => match &mut self.snapshot {
+                // Large snapshots are split into several messages for the same tick
+                Some(current) if snapshot.tick == current.tick => {
+                    current.entities.extend(snapshot.entities);
+                }
+                // Unreliable snapshots may arrive out of order
+                Some(current) if snapshot.tick < current.tick => {}
+                _ => self.snapshot = Some(snapshot),
+            },
+            ServerMessage::Chat(Chat { sender, text }) => {
+                if let Some(sender) = sender {
+                    info!("<{sender}> {text}");
+                } else {
+                    info!("{text}");
+                }
+            }
+            ServerMessage::Disconnect(Disconnect { reason }) => {
+                info!("Server is disconnecting us: {reason}");
+            }
+        }
+    }
+
+    /// Sends a message to the server
+    pub(crate) fn send(&mut self, message: &ClientMessage) {
+        let channel: Channel = match message {
+            // Input is sent every tick, so a lost one is quickly replaced
+            ClientMessage::Input(_) => Channel::Unreliable,
+            _ => Channel::ReliableOrdered,
+        };
+
+        let result: Result<(), String> = message
+            .encode()
+            .and_then(|bytes| self.endpoint.send(self.server, channel, bytes));
+
+        if let Err(error) = result {
+            trace!("Could not send message to server: {error}");
+        }
+    }
+
+    /// Tells the server the client is leaving
+    pub(crate) fn disconnect(&mut self) {
+        if !self.endpoint.is_connected(self.server) {
+            return;
+        }
+
+        self.send(&ClientMessage::Disconnect(Disconnect {
+            reason: "Client closed".to_string(),
+        }));
+
+        // Flush the disconnect message before closing the connection
+        self.endpoint.update(Instant::now());
+        self.endpoint
+            .disconnect(self.server, DisconnectReason::Requested);
+    }
+}
+
+/// Name the player is known by on the server
+fn player_name() -> String {
+    utils::environment::get_environment_var("USER")
+        .or_else(|| utils::environment::get_environment_var("USERNAME"))
+        .unwrap_or_else(|| "Player".to_string())
+}
@@ -16,19 +16,22 @@ pub mod game;
 mod window;
 
 /// Handles the rendering code
-mod render;
+pub mod render;
+
+/// Handles the screens drawn over the game
+mod screen;
 
 /// Module for storing and using build data
 pub mod build;
 
-use std::{env, fs, path::PathBuf};
+use std::{borrow::Cow, env, fs, path::PathBuf};
 
 /// Retrieve the engine's icon as raw bytes
 ///
 /// # Panics
 ///
 /// May panic if the bytes to load cannot be unwrapped
-fn get_icon_bytes() -> Option<Vec<u8>> {
+fn get_icon_bytes() -> Option<Cow<'static, [u8]>> {
     let resource_path: PathBuf = PathBuf::from("resources");
     let logo_path: PathBuf = resource_path
         .join("assets")
@@ -36,7 +39,7 @@ fn get_icon_bytes() -> Option<Vec<u8>> {
         .join("texture")
         .join("logo")
         .join("logo-1024x1024.png");
-    let bytes: Result<Vec<u8>, String> = utils::resources::get_resource_bytes(&logo_path);
+    let bytes: Result<Cow<'static, [u8]>, String> = utils::vfs::read(&logo_path);
 
     if bytes.is_err() {
         warn!("{}", bytes.err().unwrap());
@@ -56,10 +59,10 @@ fn get_icon_bytes() -> Option<Vec<u8>> {
 #[must_use]
 #[cfg(not(target_family = "wasm"))]
 fn get_icon() -> Option<winit::window::Icon> {
-    let image_bytes_option: Option<Vec<u8>> = get_icon_bytes();
+    let image_bytes_option: Option<Cow<'static, [u8]>> = get_icon_bytes();
     image_bytes_option.as_ref()?;
 
-    let image_bytes: Vec<u8> = image_bytes_option.unwrap();
+    let image_bytes: Cow<'static, [u8]> = image_bytes_option.unwrap();
     let image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>> = image::load_from_memory(&image_bytes)
         .expect("Could not get asset from memory...")
         .into_rgba8();
@@ -68,6 +71,12 @@ fn get_icon() -> Option<winit::window::Icon> {
     Some(winit::window::Icon::from_rgba(image.into_raw(), width, height).unwrap())
 }
 
+/// Get the notices of the third party assets bundled into the client, such as its font
+#[must_use]
+pub fn get_notices() -> Vec<utils::licenses::Notice> {
+    vec![render::font::notice()]
+}
+
 /// Install Linux desktop files
 ///
 /// # Panics
@@ -84,8 +93,8 @@ pub fn install_desktop_files() -> Result<(), String> {
         .join("install")
         .join("game-engine.desktop");
     let desktop_file_contents_results: Result<String, String> =
-        utils::resources::get_resource_string(&desktop_file_path);
-    let icon_bytes_option: Option<Vec<u8>> = get_icon_bytes();
+        utils::vfs::read_string(&desktop_file_path);
+    let icon_bytes_option: Option<Cow<'static, [u8]>> = get_icon_bytes();
 
     if desktop_file_contents_results.is_err() {
         warn!("{}", desktop_file_contents_results.err().unwrap());
@@ -0,0 +1,259 @@
+use std::sync::{Mutex, RwLock, RwLockWriteGuard};
+
+/// Camera the sprites are drawn through
+static CAMERA: RwLock<Camera> = RwLock::new(Camera::new());
+
+/// Last position of the mouse cursor in physical pixels from the top left of the window
+static CURSOR_POSITION: Mutex<Option<[f32; 2]>> = Mutex::new(None);
+
+/// Orthographic 2D camera looking at the world
+///
+/// The world's y axis points down like the screen's,
+/// at a zoom of 1 a world unit is as big as a logical pixel so the view stays the same size on high DPI screens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// Point of the world shown in the center of the viewport
+    pub position: [f32; 2],
+
+    /// How much bigger the world is shown, 2 shows everything twice as big
+    pub zoom: f32,
+
+    /// Clockwise rotation of the camera in radians, the world is shown turned the other way
+    pub rotation: f32,
+
+    /// Width and height of the viewport in physical pixels
+    viewport: [f32; 2],
+
+    /// Physical pixels per logical pixel of the window
+    scale_factor: f32,
+}
+
+impl Camera {
+    /// Creates a camera looking at the world's origin through a single pixel viewport
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            position: [0.0, 0.0],
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport: [1.0, 1.0],
+            scale_factor: 1.0,
+        }
+    }
+
+    /// Width and height of the viewport in physical pixels
+    #[must_use]
+    pub const fn viewport(&self) -> [f32; 2] {
+        self.viewport
+    }
+
+    /// Physical pixels per logical pixel of the window
+    #[must_use]
+    pub const fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Changes the size and DPI scale of the viewport, ignoring empty sizes (e.g. minimised windows)
+    pub(crate) fn set_viewport(&mut self, width: u32, height: u32, scale_factor: f64) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        {
+            self.viewport = [width as f32, height as f32];
+            self.scale_factor = scale_factor as f32;
+        }
+    }
+
+    /// Physical pixels per world unit
+    fn pixels_per_unit(&self) -> f32 {
+        self.zoom * self.scale_factor
+    }
+
+    /// Converts a point of the world into physical pixels from the top left of the viewport
+    #[must_use]
+    pub fn world_to_screen(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        let (sin, cos): (f32, f32) = self.rotation.sin_cos();
+        let scale: f32 = self.pixels_per_unit();
+        let [dx, dy] = [x - self.position[0], y - self.position[1]];
+
+        [
+            (cos * dx + sin * dy).mul_add(scale, self.viewport[0] / 2.0),
+            (cos * dy - sin * dx).mul_add(scale, self.viewport[1] / 2.0),
+        ]
+    }
+
+    /// Converts physical pixels from the top left of the viewport into a point of the world
+    ///
+    /// Used to find what's under the mouse or a finger
+    #[must_use]
+    pub fn screen_to_world(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        let (sin, cos): (f32, f32) = self.rotation.sin_cos();
+        let scale: f32 = self.pixels_per_unit();
+        let [dx, dy] = [
+            (x - self.viewport[0] / 2.0) / scale,
+            (y - self.viewport[1] / 2.0) / scale,
+        ];
+
+        [
+            cos.mul_add(dx, -sin * dy) + self.position[0],
+            sin.mul_add(dx, cos * dy) + self.position[1],
+        ]
+    }
+
+    /// Column major matrix turning points of the world into clip space
+    #[must_use]
+    pub fn view_projection(&self) -> [f32; 16] {
+        let (sin, cos): (f32, f32) = self.rotation.sin_cos();
+        let scale_x: f32 = 2.0 * self.pixels_per_unit() / self.viewport[0];
+        let scale_y: f32 = 2.0 * self.pixels_per_unit() / self.viewport[1];
+        let [x, y] = self.position;
+
+        [
+            scale_x * cos,
+            scale_y * sin,
+            0.0,
+            0.0,
+            scale_x * sin,
+            -scale_y * cos,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+            -scale_x * cos.mul_add(x, sin * y),
+            -scale_y * sin.mul_add(x, -cos * y),
+            0.0,
+            1.0,
+        ]
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Retrieves the camera the sprites are drawn through
+///
+/// # Panics
+///
+/// May panic if the camera's lock is poisoned
+#[must_use]
+pub fn get_camera() -> Camera {
+    *CAMERA.read().unwrap()
+}
+
+/// Moves, zooms or rotates the camera
+///
+/// # Panics
+///
+/// May panic if the camera's lock is poisoned
+pub fn update_camera(update: impl FnOnce(&mut Camera)) {
+    let mut camera: RwLockWriteGuard<Camera> = CAMERA.write().unwrap();
+    let viewport: [f32; 2] = camera.viewport;
+    let scale_factor: f32 = camera.scale_factor;
+
+    update(&mut camera);
+
+    // The viewport follows the window
+    camera.viewport = viewport;
+    camera.scale_factor = scale_factor;
+}
+
+/// Changes the camera's viewport to the size and DPI scale of the window
+///
+/// # Panics
+///
+/// May panic if the camera's lock is poisoned
+pub(crate) fn set_viewport(width: u32, height: u32, scale_factor: f64) {
+    CAMERA
+        .write()
+        .unwrap()
+        .set_viewport(width, height, scale_factor);
+}
+
+/// Stores where the mouse cursor is in physical pixels from the top left of the window
+///
+/// # Panics
+///
+/// May panic if the cursor's lock is poisoned
+pub(crate) fn store_cursor_position(position: [f32; 2]) {
+    *CURSOR_POSITION.lock().unwrap() = Some(position);
+}
+
+/// Retrieves the point of the world under the mouse cursor, if it is over the window
+///
+/// # Panics
+///
+/// May panic if the cursor's lock is poisoned
+#[must_use]
+pub fn get_cursor_world_position() -> Option<[f32; 2]> {
+    let position: [f32; 2] = (*CURSOR_POSITION.lock().unwrap())?;
+
+    Some(get_camera().screen_to_world(position))
+}
+
+/// Forgets the mouse cursor's position once it leaves the window
+///
+/// # Panics
+///
+/// May panic if the cursor's lock is poisoned
+pub(crate) fn clear_cursor_position() {
+    *CURSOR_POSITION.lock().unwrap() = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Camera;
+
+    /// Applies a column major matrix to a point
+    fn transform(matrix: &[f32; 16], [x, y]: [f32; 2]) -> [f32; 2] {
+        [
+            matrix[0] * x + matrix[4] * y + matrix[12],
+            matrix[1] * x + matrix[5] * y + matrix[13],
+        ]
+    }
+
+    /// Asserts two points are equal, allowing for rounding
+    fn assert_near(actual: [f32; 2], expected: [f32; 2]) {
+        assert!(
+            (actual[0] - expected[0]).abs() < 1e-3 && (actual[1] - expected[1]).abs() < 1e-3,
+            "{actual:?} is not {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_camera() {
+        let mut camera: Camera = Camera {
+            position: [100.0, 50.0],
+            zoom: 2.0,
+            ..Camera::new()
+        };
+        camera.set_viewport(800, 600, 1.5);
+
+        // The camera's position is shown in the center, a world unit is zoom times DPI scale pixels
+        assert_near(camera.world_to_screen([100.0, 50.0]), [400.0, 300.0]);
+        assert_near(camera.world_to_screen([110.0, 40.0]), [430.0, 270.0]);
+
+        // Turning the camera a quarter clockwise shows what was to its right above it
+        camera.rotation = std::f32::consts::FRAC_PI_2;
+        assert_near(camera.world_to_screen([110.0, 50.0]), [400.0, 270.0]);
+
+        for point in [[0.0, 0.0], [123.0, -45.0], [100.0, 50.0]] {
+            let screen: [f32; 2] = camera.world_to_screen(point);
+            assert_near(camera.screen_to_world(screen), point);
+
+            // The shader puts points where the screen conversion says they are
+            let clip: [f32; 2] = transform(&camera.view_projection(), point);
+            assert_near(clip, [screen[0] / 400.0 - 1.0, 1.0 - screen[1] / 300.0]);
+        }
+
+        // Minimised windows keep the last viewport
+        camera.set_viewport(0, 0, 1.0);
+        assert_near(camera.viewport(), [800.0, 600.0]);
+    }
+}
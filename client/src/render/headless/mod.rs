@@ -0,0 +1,327 @@
+use std::io::Cursor;
+
+use image::RgbaImage;
+use wgpu::{
+    Adapter, Buffer, BufferSlice, CommandEncoder, Device, Instance, Queue, Texture, TextureFormat,
+    TextureView,
+};
+
+use super::{
+    camera::Camera,
+    sprite::{DrawList, SpriteRenderer},
+    texture::TextureManager,
+};
+
+/// Format headless frames are drawn in, which can be read back as an image without converting it
+const HEADLESS_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+/// A texture drawn onto instead of a window, which can be read back into an image
+#[derive(Debug)]
+pub(crate) struct OffscreenTarget {
+    /// Texture the frame is drawn onto
+    texture: Texture,
+
+    /// View of the texture for render passes
+    view: TextureView,
+}
+
+impl OffscreenTarget {
+    /// Creates a target `width` by `height` pixels big
+    pub(crate) fn new(device: &Device, width: u32, height: u32, format: TextureFormat) -> Self {
+        let texture: Texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view: TextureView = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self { texture, view }
+    }
+
+    /// View to draw onto the target with
+    pub(crate) fn view(&self) -> &TextureView {
+        &self.view
+    }
+
+    /// Copies what was drawn onto the target back into an image, waiting for the graphics device to finish
+    ///
+    /// # Errors
+    ///
+    /// Errors if the target's format isn't 8 bit RGBA or BGRA, or the copy cannot be read
+    pub(crate) fn read_back(&self, device: &Device, queue: &Queue) -> Result<RgbaImage, String> {
+        let format: TextureFormat = self.texture.format();
+        let swap_red_blue: bool = match format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            _ => return Err(format!("Cannot read back frames drawn in {format:?}")),
+        };
+
+        let width: u32 = self.texture.width();
+        let height: u32 = self.texture.height();
+
+        // Rows of a copy have to be aligned, so the padding is removed afterwards
+        let row_size: u32 = 4 * width;
+        let padded_row_size: u32 = row_size.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer: Buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Offscreen Read Back Buffer"),
+            size: u64::from(padded_row_size) * u64::from(height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder: CommandEncoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_size),
+                    rows_per_image: Some(height),
+                },
+            },
+            self.texture.size(),
+        );
+        queue.submit(core::iter::once(encoder.finish()));
+
+        let slice: BufferSlice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        receiver
+            .recv()
+            .map_err(|error| format!("Frame was never read back: {error}"))?
+            .map_err(|error| format!("Could not read back the frame: {error}"))?;
+
+        let mut pixels: Vec<u8> = Vec::with_capacity(row_size as usize * height as usize);
+        for row in slice
+            .get_mapped_range()
+            .chunks_exact(padded_row_size as usize)
+        {
+            pixels.extend_from_slice(&row[..row_size as usize]);
+        }
+        buffer.unmap();
+
+        if swap_red_blue {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| "Frame read back has the wrong size".to_string())
+    }
+}
+
+/// Renders frames without a window or display server, falling back to a software adapter without a GPU
+///
+/// Used for golden image tests and anything else which needs frames as images
+#[derive(Debug)]
+pub struct HeadlessRenderer {
+    /// Connection to the graphics device
+    device: Device,
+
+    /// Queue in which to send commands to the graphics device
+    queue: Queue,
+
+    /// Uploads the textures the frames are drawn with
+    textures: TextureManager,
+
+    /// Draws the sprites of the frames
+    sprites: SpriteRenderer,
+}
+
+impl HeadlessRenderer {
+    /// Opens a connection to a graphics device, using a software adapter if there's no GPU
+    ///
+    /// # Errors
+    ///
+    /// Errors if there's no adapter at all or the device cannot be opened
+    pub async fn new() -> Result<Self, String> {
+        let instance: Instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+
+        let mut adapter: Option<Adapter> = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await;
+        if adapter.is_none() {
+            debug!("No graphics device found, trying the software adapter...");
+            adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    force_fallback_adapter: true,
+                    ..Default::default()
+                })
+                .await;
+        }
+
+        let adapter: Adapter = adapter.ok_or_else(|| "No graphics adapter found".to_string())?;
+        debug!("Rendering headless with {:?}...", adapter.get_info());
+
+        // Software adapters only support the lower limits
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Headless Device"),
+                    required_limits: wgpu::Limits::downlevel_webgl2_defaults()
+                        .using_resolution(adapter.limits()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .map_err(|error| format!("Could not open the graphics device: {error}"))?;
+
+        let textures: TextureManager = TextureManager::new(&device, &queue);
+        let sprites: SpriteRenderer = SpriteRenderer::new(&device, &textures, HEADLESS_FORMAT);
+
+        Ok(Self {
+            device,
+            queue,
+            textures,
+            sprites,
+        })
+    }
+
+    /// Draws a frame `width` by `height` pixels big as seen through a camera and reads it back
+    ///
+    /// The camera's viewport is replaced by the size of the frame
+    ///
+    /// # Errors
+    ///
+    /// Errors if the frame cannot be read back
+    pub fn render(
+        &mut self,
+        width: u32,
+        height: u32,
+        camera: &Camera,
+        draw_list: &mut DrawList,
+    ) -> Result<RgbaImage, String> {
+        self.textures.sync(&self.device, &self.queue);
+
+        let mut camera: Camera = *camera;
+        camera.set_viewport(width, height, f64::from(camera.scale_factor()));
+
+        let target: OffscreenTarget =
+            OffscreenTarget::new(&self.device, width, height, HEADLESS_FORMAT);
+        let mut encoder: CommandEncoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+        super::draw_scene(
+            &self.device,
+            &self.queue,
+            &mut encoder,
+            target.view(),
+            &self.textures,
+            &mut self.sprites,
+            &camera,
+            draw_list,
+        );
+        self.queue.submit(core::iter::once(encoder.finish()));
+
+        target.read_back(&self.device, &self.queue)
+    }
+}
+
+/// Encodes an image as a PNG file
+///
+/// # Errors
+///
+/// Errors if the image cannot be encoded
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|error| format!("Could not encode the image as a PNG: {error}"))?;
+
+    Ok(png.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use image::{Rgba, RgbaImage};
+
+    use super::HeadlessRenderer;
+    use crate::render::{
+        camera::Camera,
+        sprite::{DrawList, Sprite},
+        texture::{TextureHandle, TextureId},
+    };
+
+    /// Asserts a pixel is a color, allowing for rounding while converting to sRGB
+    fn assert_pixel(image: &RgbaImage, (x, y): (u32, u32), expected: [u8; 4]) {
+        let Rgba(actual) = *image.get_pixel(x, y);
+
+        assert!(
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(actual, expected)| actual.abs_diff(expected) <= 1),
+            "Pixel ({x}, {y}) is {actual:?} rather than {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_headless_render() {
+        let Ok(mut renderer) = futures::executor::block_on(HeadlessRenderer::new()) else {
+            // CI machines without even a software adapter can't draw anything
+            eprintln!("Skipping the headless render test, there's no graphics adapter");
+            return;
+        };
+
+        let mut draw_list: DrawList = DrawList::default();
+        draw_list.push(Sprite {
+            tint: [1.0, 0.0, 0.0, 1.0],
+            ..Sprite::new(TextureId::WHITE, [0.0, 0.0], [8.0, 8.0])
+        });
+
+        let image: RgbaImage = renderer
+            .render(32, 32, &Camera::new(), &mut draw_list)
+            .unwrap();
+        assert_eq!(image.dimensions(), (32, 32));
+
+        // A red square in the middle of the royal purple background
+        assert_pixel(&image, (16, 16), [255, 0, 0, 255]);
+        assert_pixel(&image, (12, 12), [255, 0, 0, 255]);
+        assert_pixel(&image, (11, 16), [104, 71, 141, 255]);
+        assert_pixel(&image, (0, 0), [104, 71, 141, 255]);
+
+        let png: Vec<u8> = super::encode_png(&image).unwrap();
+        assert_eq!(image::load_from_memory(&png).unwrap().into_rgba8(), image);
+
+        // Textures bigger than the device supports are skipped rather than crashing
+        let max: u32 = renderer.device.limits().max_texture_dimension_2d;
+        let oversized: TextureHandle = crate::render::texture::insert_texture(
+            Path::new("oversized"),
+            RgbaImage::new(max + 1, 1),
+            false,
+        );
+        let mut draw_list: DrawList = DrawList::default();
+        draw_list.push(Sprite::new(oversized.id(), [0.0, 0.0], [8.0, 8.0]));
+
+        let image: RgbaImage = renderer
+            .render(32, 32, &Camera::new(), &mut draw_list)
+            .unwrap();
+        assert_pixel(&image, (16, 16), [104, 71, 141, 255]);
+    }
+}
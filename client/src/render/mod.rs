@@ -1,5 +1,36 @@
+/// Handles the bitmap font text is drawn with
+pub(crate) mod font;
+
+/// Handles drawing text onto images
+pub(crate) mod text;
+
+/// Handles drawing images over the rendered frame
+pub(crate) mod overlay;
+
+/// Handles the view of the world sprites are drawn through
+pub mod camera;
+
+/// Handles drawing batches of sprites
+pub mod sprite;
+
+/// Handles loading textures and uploading them to the graphics device
+pub mod texture;
+
+/// Handles rendering without a window, such as for tests
+#[cfg(not(target_family = "wasm"))]
+pub mod headless;
+
+/// Handles saving what's on the screen to an image
+#[cfg(not(target_family = "wasm"))]
+pub(crate) mod screenshot;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use wgpu::{CommandEncoder, Device, Queue, RenderPass, RenderPassDescriptor, TextureView};
+
+use camera::Camera;
+use sprite::{DrawList, SpriteRenderer};
+use texture::TextureManager;
 
 /// Struct used for storing colors (usually in linear srgb)
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -74,6 +105,62 @@ pub(crate) fn get_wgpu_color_from_ce_color(color: Color) -> wgpu::Color {
     }
 }
 
+/// Color the frame is cleared to before anything is drawn
+pub(crate) fn clear_color() -> wgpu::Color {
+    // Royal Purple - 104, 71, 141
+    if true {
+        // Renders correctly on all machines I've tested except Arm Mac
+        let ce_color: Color = srgb_to_linear_srgb(104, 71, 141);
+
+        get_wgpu_color_from_ce_color(ce_color)
+    } else {
+        // Renders correctly only on Arm Mac during my tests
+        wgpu::Color {
+            r: 104.0 / 255.0,
+            g: 71.0 / 255.0,
+            b: 141.0 / 255.0,
+            a: 1.0,
+        }
+    }
+}
+
+/// Clears a target and draws the sprites of a frame onto it as seen through a camera
+///
+/// Shared by the window and offscreen targets so both show the same frame
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_scene(
+    device: &Device,
+    queue: &Queue,
+    encoder: &mut CommandEncoder,
+    target: &TextureView,
+    textures: &TextureManager,
+    sprites: &mut SpriteRenderer,
+    camera: &Camera,
+    draw_list: &mut DrawList,
+) {
+    // Command to render
+    // https://docs.rs/wgpu/latest/wgpu/struct.RenderPassDescriptor.html
+    let render_pass_descriptor: RenderPassDescriptor = wgpu::RenderPassDescriptor {
+        label: Some("Render Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(clear_color()),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    };
+
+    // Render command
+    // https://docs.rs/wgpu/latest/wgpu/struct.RenderPass.html
+    let mut render_pass: RenderPass = encoder.begin_render_pass(&render_pass_descriptor);
+    sprites.draw(device, queue, textures, &mut render_pass, camera, draw_list);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::render::{Color, ColorSpace};
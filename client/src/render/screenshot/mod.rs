@@ -0,0 +1,63 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use image::RgbaImage;
+
+/// Directory inside the engine's data directory holding the screenshots
+const SCREENSHOT_DIRECTORY: &str = "screenshots";
+
+/// Whether a screenshot should be taken while drawing the next frame
+static SCREENSHOT_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Asks for a screenshot to be taken while drawing the next frame
+pub(crate) fn request() {
+    debug!("Taking a screenshot...");
+    SCREENSHOT_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Whether a screenshot was asked for, so it is only taken once
+pub(crate) fn take_request() -> bool {
+    SCREENSHOT_REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+/// Directory screenshots are saved to, if the platform has a data directory
+pub(crate) fn screenshot_directory() -> Option<PathBuf> {
+    Some(utils::settings::file::engine_data_directory()?.join(SCREENSHOT_DIRECTORY))
+}
+
+/// Saves a screenshot as a PNG file named after when it was taken
+///
+/// Returns the path of the screenshot
+///
+/// # Errors
+///
+/// Errors if the platform has no data directory or the screenshot cannot be written
+pub(crate) fn save(image: &RgbaImage) -> Result<PathBuf, String> {
+    let directory: PathBuf = screenshot_directory()
+        .ok_or_else(|| "There is no directory to save screenshots to".to_string())?;
+
+    save_to(&directory, image)
+}
+
+/// Saves a screenshot as a PNG file in a directory
+///
+/// # Errors
+///
+/// Errors if the directory or the screenshot cannot be written
+fn save_to(directory: &Path, image: &RgbaImage) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|error| format!("Could not create {}: {error}", directory.display()))?;
+
+    let timestamp: u128 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    let path: PathBuf = directory.join(format!("screenshot-{timestamp}.png"));
+
+    std::fs::write(&path, super::headless::encode_png(image)?)
+        .map_err(|error| format!("Could not write {}: {error}", path.display()))?;
+
+    Ok(path)
+}
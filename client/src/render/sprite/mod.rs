@@ -0,0 +1,410 @@
+use std::{ops::Range, sync::Mutex};
+
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer, Device, PipelineLayout, Queue, RenderPass, RenderPipeline,
+    ShaderModule, TextureFormat,
+};
+
+use super::{
+    camera::Camera,
+    texture::{TextureId, TextureManager},
+};
+
+/// Sprites queued to be drawn on the next frame
+static DRAW_LIST: Mutex<DrawList> = Mutex::new(DrawList {
+    sprites: Vec::new(),
+});
+
+/// Number of floats in the instance data of a sprite
+const INSTANCE_FLOATS: usize = 13;
+
+/// Size of the instance data of a sprite in bytes
+const INSTANCE_SIZE: wgpu::BufferAddress = (INSTANCE_FLOATS * size_of::<f32>()) as u64;
+
+/// Number of sprites the instance buffer starts with room for
+const INITIAL_CAPACITY: usize = 256;
+
+/// A textured quad to draw
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    /// Texture (usually an atlas) the sprite is drawn from
+    pub texture: TextureId,
+
+    /// Position of the sprite's center in the world
+    pub position: [f32; 2],
+
+    /// Width and height of the sprite in world units
+    pub size: [f32; 2],
+
+    /// Clockwise rotation around the sprite's center in radians
+    pub rotation: f32,
+
+    /// Region of the texture to draw as x, y, width and height from 0 to 1
+    pub uv: [f32; 4],
+
+    /// Linear color the texture is multiplied by, including the transparency
+    pub tint: [f32; 4],
+
+    /// Layer the sprite is drawn on, higher layers are drawn over lower ones
+    pub layer: i32,
+
+    /// Order of the sprite within its layer, higher depths are drawn over lower ones
+    pub depth: f32,
+}
+
+impl Sprite {
+    /// Creates an untinted sprite showing a whole texture
+    #[must_use]
+    pub const fn new(texture: TextureId, position: [f32; 2], size: [f32; 2]) -> Self {
+        Self {
+            texture,
+            position,
+            size,
+            rotation: 0.0,
+            uv: [0.0, 0.0, 1.0, 1.0],
+            tint: [1.0, 1.0, 1.0, 1.0],
+            layer: 0,
+            depth: 0.0,
+        }
+    }
+
+    /// Instance data read by the sprite shader
+    fn instance(&self) -> [f32; INSTANCE_FLOATS] {
+        let [x, y] = self.position;
+        let [width, height] = self.size;
+        let [u, v, uv_width, uv_height] = self.uv;
+        let [red, green, blue, alpha] = self.tint;
+
+        [
+            x,
+            y,
+            width,
+            height,
+            self.rotation,
+            u,
+            v,
+            uv_width,
+            uv_height,
+            red,
+            green,
+            blue,
+            alpha,
+        ]
+    }
+}
+
+/// Sprites drawn with the same texture in a single draw call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Batch {
+    /// Texture the sprites are drawn from
+    texture: TextureId,
+
+    /// Instances of the sorted draw list in the batch
+    instances: Range<u32>,
+}
+
+/// Sprites to draw on a frame
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DrawList {
+    /// Sprites in the order they were queued
+    sprites: Vec<Sprite>,
+}
+
+impl DrawList {
+    /// Queues a sprite to be drawn
+    pub fn push(&mut self, sprite: Sprite) {
+        self.sprites.push(sprite);
+    }
+
+    /// Number of sprites queued
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Whether no sprites are queued
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Sorts the sprites into drawing order and groups them into batches
+    ///
+    /// Sprites are ordered by layer, then depth, then texture, so sprites at the same depth
+    /// share draw calls while sprites which overlap are still drawn in order
+    pub(crate) fn sort_into_batches(&mut self) -> Vec<Batch> {
+        // Stable, so sprites queued in the same place keep their order
+        self.sprites.sort_by(|a, b| {
+            a.layer
+                .cmp(&b.layer)
+                .then(a.depth.total_cmp(&b.depth))
+                .then(a.texture.cmp(&b.texture))
+        });
+
+        let mut batches: Vec<Batch> = Vec::new();
+        for (index, sprite) in self.sprites.iter().enumerate() {
+            #[allow(clippy::cast_possible_truncation)]
+            let index: u32 = index as u32;
+
+            match batches.last_mut() {
+                Some(batch) if batch.texture == sprite.texture => {
+                    batch.instances.end = index + 1;
+                }
+                _ => batches.push(Batch {
+                    texture: sprite.texture,
+                    instances: index..index + 1,
+                }),
+            }
+        }
+
+        batches
+    }
+}
+
+/// Queues a sprite to be drawn on the next frame
+///
+/// # Panics
+///
+/// May panic if the draw list's lock is poisoned
+pub fn draw_sprite(sprite: Sprite) {
+    DRAW_LIST.lock().unwrap().push(sprite);
+}
+
+/// Takes the sprites queued for the frame, leaving the draw list empty for the next one
+///
+/// # Panics
+///
+/// May panic if the draw list's lock is poisoned
+pub(crate) fn take_draw_list() -> DrawList {
+    std::mem::take(&mut *DRAW_LIST.lock().unwrap())
+}
+
+/// Draws the sprites of a frame with one instanced draw call per batch
+#[derive(Debug)]
+pub(crate) struct SpriteRenderer {
+    /// Pipeline drawing instanced quads
+    pipeline: RenderPipeline,
+
+    /// Holds the matrix turning points of the world into clip space
+    projection_buffer: Buffer,
+
+    /// Binds the projection buffer
+    projection_bind_group: BindGroup,
+
+    /// Holds the instance data of the sprites
+    instance_buffer: Buffer,
+
+    /// Number of sprites the instance buffer has room for
+    capacity: usize,
+}
+
+impl SpriteRenderer {
+    /// Creates the pipeline for drawing onto targets of the given format
+    pub(crate) fn new(device: &Device, textures: &TextureManager, format: TextureFormat) -> Self {
+        let shader: ShaderModule = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Sprite Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("sprite.wgsl").into()),
+        });
+
+        let projection_bind_group_layout: BindGroupLayout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Sprite Projection Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let layout: PipelineLayout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Sprite Pipeline Layout"),
+                bind_group_layouts: &[&projection_bind_group_layout, textures.bind_group_layout()],
+                push_constant_ranges: &[],
+            });
+
+        let pipeline: RenderPipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Sprite Pipeline"),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: INSTANCE_SIZE,
+                        step_mode: wgpu::VertexStepMode::Instance,
+                        attributes: &wgpu::vertex_attr_array![
+                            0 => Float32x2,
+                            1 => Float32x2,
+                            2 => Float32,
+                            3 => Float32x4,
+                            4 => Float32x4
+                        ],
+                    }],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            });
+
+        let projection_buffer: Buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Sprite Projection Buffer"),
+            size: 16 * size_of::<f32>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let projection_bind_group: BindGroup =
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Sprite Projection Bind Group"),
+                layout: &projection_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: projection_buffer.as_entire_binding(),
+                }],
+            });
+
+        Self {
+            pipeline,
+            projection_buffer,
+            projection_bind_group,
+            instance_buffer: create_instance_buffer(device, INITIAL_CAPACITY),
+            capacity: INITIAL_CAPACITY,
+        }
+    }
+
+    /// Draws the sprites of a frame as seen through a camera
+    ///
+    /// Sprites using a texture which isn't uploaded are skipped
+    pub(crate) fn draw(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        textures: &TextureManager,
+        render_pass: &mut RenderPass,
+        camera: &Camera,
+        draw_list: &mut DrawList,
+    ) {
+        if draw_list.is_empty() {
+            return;
+        }
+
+        let batches: Vec<Batch> = draw_list.sort_into_batches();
+
+        if draw_list.len() > self.capacity {
+            self.capacity = draw_list.len().next_power_of_two();
+            self.instance_buffer = create_instance_buffer(device, self.capacity);
+        }
+
+        let instances: Vec<u8> = draw_list
+            .sprites
+            .iter()
+            .flat_map(Sprite::instance)
+            .flat_map(f32::to_ne_bytes)
+            .collect();
+        queue.write_buffer(&self.instance_buffer, 0, &instances);
+
+        let projection: Vec<u8> = camera
+            .view_projection()
+            .into_iter()
+            .flat_map(f32::to_ne_bytes)
+            .collect();
+        queue.write_buffer(&self.projection_buffer, 0, &projection);
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.projection_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+
+        for batch in batches {
+            let Some(texture) = textures.get_bind_group(batch.texture) else {
+                warn!("Texture {} is not uploaded...", batch.texture.0);
+                continue;
+            };
+
+            render_pass.set_bind_group(1, texture, &[]);
+            render_pass.draw(0..4, batch.instances);
+        }
+    }
+}
+
+/// Creates a buffer with room for the instance data of `capacity` sprites
+fn create_instance_buffer(device: &Device, capacity: usize) -> Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Sprite Instance Buffer"),
+        size: INSTANCE_SIZE * capacity as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Batch, DrawList, Sprite};
+    use crate::render::texture::TextureId;
+
+    #[test]
+    fn test_sort_into_batches() {
+        let tiles: TextureId = TextureId(1);
+        let characters: TextureId = TextureId(2);
+
+        let mut draw_list: DrawList = DrawList::default();
+        for (texture, layer, depth) in [
+            (characters, 1, 0.0),
+            (tiles, 0, 0.0),
+            (characters, 0, 0.0),
+            (tiles, 0, 0.0),
+            (TextureId::WHITE, 2, 0.0),
+            (tiles, 1, 1.0),
+        ] {
+            draw_list.push(Sprite {
+                layer,
+                depth,
+                ..Sprite::new(texture, [0.0, 0.0], [16.0, 16.0])
+            });
+        }
+
+        // Layers are drawn in order, sprites of a layer at the same depth are grouped by texture
+        assert_eq!(
+            draw_list.sort_into_batches(),
+            vec![
+                Batch {
+                    texture: tiles,
+                    instances: 0..2
+                },
+                Batch {
+                    texture: characters,
+                    instances: 2..4
+                },
+                Batch {
+                    texture: tiles,
+                    instances: 4..5
+                },
+                Batch {
+                    texture: TextureId::WHITE,
+                    instances: 5..6
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// Vertex shader
+
+struct Projection {
+    matrix: mat4x4<f32>,
+};
+
+struct SpriteInstance {
+    @location(0) position: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) rotation: f32,
+    @location(3) uv_rect: vec4<f32>,
+    @location(4) tint: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) tint: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> projection: Projection;
+
+// Draws a quad as a triangle strip, rotated around the sprite's center
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+    instance: SpriteInstance,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let corner = vec2<f32>(f32(in_vertex_index & 1u), f32((in_vertex_index >> 1u) & 1u));
+    let offset = (corner - vec2<f32>(0.5, 0.5)) * instance.size;
+
+    let cos_rotation = cos(instance.rotation);
+    let sin_rotation = sin(instance.rotation);
+    let rotated = vec2<f32>(
+        offset.x * cos_rotation - offset.y * sin_rotation,
+        offset.x * sin_rotation + offset.y * cos_rotation,
+    );
+
+    out.clip_position = projection.matrix * vec4<f32>(instance.position + rotated, 0.0, 1.0);
+    out.uv = instance.uv_rect.xy + corner * instance.uv_rect.zw;
+    out.tint = instance.tint;
+    return out;
+}
+
+// Fragment shader
+
+@group(1) @binding(0)
+var sprite_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var sprite_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(sprite_texture, sprite_sampler, in.uv) * in.tint;
+}
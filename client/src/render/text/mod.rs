@@ -0,0 +1,83 @@
+use image::{Rgba, RgbaImage};
+
+use crate::render::font::{GLYPH_HEIGHT, GLYPH_WIDTH};
+
+/// Breaks the lines which are longer than `columns` characters into several lines
+pub(crate) fn wrap(lines: &[String], columns: usize) -> Vec<String> {
+    let columns: usize = columns.max(1);
+    let mut wrapped: Vec<String> = Vec::with_capacity(lines.len());
+
+    for line in lines {
+        let characters: Vec<char> = line.chars().collect();
+
+        if characters.is_empty() {
+            wrapped.push(String::new());
+        }
+
+        for chunk in characters.chunks(columns) {
+            wrapped.push(chunk.iter().collect());
+        }
+    }
+
+    wrapped
+}
+
+/// Draws lines of text onto an image from the cell at `column` and `row`
+///
+/// Every pixel of the font covers `scale` by `scale` pixels of the image, text outside the image is cut off
+pub(crate) fn draw_text(
+    image: &mut RgbaImage,
+    lines: &[String],
+    (column, row): (u32, u32),
+    scale: u32,
+    color: Rgba<u8>,
+) {
+    let scale: u32 = scale.max(1);
+    let (width, height): (u32, u32) = image.dimensions();
+
+    for (line_index, line) in (row..).zip(lines) {
+        let top: u32 = line_index * GLYPH_HEIGHT * scale;
+
+        for (character_index, character) in (column..).zip(line.chars()) {
+            let left: u32 = character_index * GLYPH_WIDTH * scale;
+            let rows: &[u8; GLYPH_HEIGHT as usize] = crate::render::font::glyph(character);
+
+            for (y, pixels) in (0..).zip(rows) {
+                for x in (0..GLYPH_WIDTH).filter(|x| pixels & (0x80 >> x) != 0) {
+                    // Fill the block of image pixels the font pixel covers
+                    for pixel_y in
+                        (top + y * scale..top + (y + 1) * scale).take_while(|&py| py < height)
+                    {
+                        for pixel_x in
+                            (left + x * scale..left + (x + 1) * scale).take_while(|&px| px < width)
+                        {
+                            image.put_pixel(pixel_x, pixel_y, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::{draw_text, wrap};
+
+    #[test]
+    fn test_text() {
+        let lines: Vec<String> = vec!["abcdefg".to_string(), String::new(), "hi".to_string()];
+        assert_eq!(wrap(&lines, 3), vec!["abc", "def", "g", "", "hi"]);
+
+        let white: Rgba<u8> = Rgba([255, 255, 255, 255]);
+        let mut image: RgbaImage = RgbaImage::new(40, 40);
+        draw_text(&mut image, &["|".to_string()], (1, 0), 2, white);
+
+        // The bar of the pipe is two pixels wide at columns 3 and 4 of its glyph
+        assert_eq!(image.get_pixel(8 * 2 + 3 * 2, 10), &white);
+        assert_eq!(image.get_pixel(8 * 2 + 5 * 2, 10)[3], 0);
+        assert_eq!(image.get_pixel(3 * 2, 10)[3], 0);
+    }
+}
@@ -0,0 +1,467 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use image::{imageops::FilterType, Rgba, RgbaImage};
+use wgpu::{
+    BindGroup, BindGroupLayout, Device, Queue, Sampler, Texture, TextureFormat, TextureView,
+};
+
+/// Textures loaded by the game, uploaded to the graphics device by the [`TextureManager`]
+static TEXTURE_CACHE: Mutex<TextureCache> = Mutex::new(TextureCache {
+    entries: BTreeMap::new(),
+    paths: BTreeMap::new(),
+    next_id: 1,
+});
+
+/// Identifies a texture on the graphics device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextureId(pub(super) u32);
+
+impl TextureId {
+    /// A single white pixel, for drawing sprites of a solid color with the tint
+    pub const WHITE: Self = Self(0);
+}
+
+/// Keeps a cached texture loaded, the texture is freed once every clone of its handle is dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextureHandle(Arc<TextureId>);
+
+impl TextureHandle {
+    /// Id of the texture, used to draw it
+    #[must_use]
+    pub fn id(&self) -> TextureId {
+        *self.0
+    }
+}
+
+/// A decoded texture and how to upload it
+#[derive(Debug)]
+struct CachedTexture {
+    /// Resource path or name the texture is cached under
+    key: PathBuf,
+
+    /// Reference held by the cache, the texture is unused when it's the only one left
+    handle: Arc<TextureId>,
+
+    /// Pixels of the texture, kept to upload them again if the graphics are reset
+    image: RgbaImage,
+
+    /// Whether smaller copies of the texture are generated for drawing it shrunk
+    mipmaps: bool,
+}
+
+/// Decoded textures keyed by their resource path
+#[derive(Debug)]
+struct TextureCache {
+    /// Textures by their id
+    entries: BTreeMap<TextureId, CachedTexture>,
+
+    /// Ids of the textures by their key
+    paths: BTreeMap<PathBuf, TextureId>,
+
+    /// Id given to the next cached texture
+    next_id: u32,
+}
+
+impl TextureCache {
+    /// Retrieves the handle of a cached texture
+    fn get(&self, key: &Path) -> Option<TextureHandle> {
+        let id: &TextureId = self.paths.get(key)?;
+
+        Some(TextureHandle(self.entries.get(id)?.handle.clone()))
+    }
+
+    /// Caches a texture, unless a texture was already cached under the key
+    fn insert(&mut self, key: &Path, image: RgbaImage, mipmaps: bool) -> TextureHandle {
+        if let Some(handle) = self.get(key) {
+            return handle;
+        }
+
+        let id: TextureId = TextureId(self.next_id);
+        self.next_id += 1;
+
+        let handle: Arc<TextureId> = Arc::new(id);
+        self.paths.insert(key.to_path_buf(), id);
+        self.entries.insert(
+            id,
+            CachedTexture {
+                key: key.to_path_buf(),
+                handle: handle.clone(),
+                image,
+                mipmaps,
+            },
+        );
+
+        TextureHandle(handle)
+    }
+
+    /// Removes the textures which no handles refer to anymore, returning their ids
+    fn release_unused(&mut self) -> Vec<TextureId> {
+        let unused: Vec<TextureId> = self
+            .entries
+            .iter()
+            .filter(|(_, texture)| Arc::strong_count(&texture.handle) == 1)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in &unused {
+            if let Some(texture) = self.entries.remove(id) {
+                trace!("Releasing texture {}...", texture.key.display());
+                self.paths.remove(&texture.key);
+            }
+        }
+
+        unused
+    }
+}
+
+/// Loads an image from the resources as a texture, or retrieves it if it's already loaded
+///
+/// The image is decoded once and uploaded before the next frame is drawn
+///
+/// # Errors
+///
+/// Errors if the resource cannot be read or isn't a supported image
+///
+/// # Panics
+///
+/// May panic if the texture cache's lock is poisoned
+pub fn load_texture(path: &Path, mipmaps: bool) -> Result<TextureHandle, String> {
+    if let Some(handle) = TEXTURE_CACHE.lock().unwrap().get(path) {
+        return Ok(handle);
+    }
+
+    let bytes: Vec<u8> = utils::resources::get_resource_bytes(path)?;
+    let image: RgbaImage = image::load_from_memory(&bytes)
+        .map_err(|error| format!("Could not decode texture {}: {error}", path.display()))?
+        .into_rgba8();
+
+    debug!(
+        "Loaded {}x{} texture {}...",
+        image.width(),
+        image.height(),
+        path.display()
+    );
+    Ok(TEXTURE_CACHE.lock().unwrap().insert(path, image, mipmaps))
+}
+
+/// Caches an image made by the game as a texture (e.g. a generated atlas) under a name
+///
+/// If a texture was already cached under the name, its handle is returned instead
+///
+/// # Panics
+///
+/// May panic if the texture cache's lock is poisoned
+pub fn insert_texture(name: &Path, image: RgbaImage, mipmaps: bool) -> TextureHandle {
+    TEXTURE_CACHE.lock().unwrap().insert(name, image, mipmaps)
+}
+
+/// Whether a texture fits within the largest texture the graphics device supports
+pub(crate) fn fits_device(device: &Device, width: u32, height: u32) -> bool {
+    let max: u32 = device.limits().max_texture_dimension_2d;
+
+    width > 0 && height > 0 && width <= max && height <= max
+}
+
+/// Shrinks an image by half until it's a single pixel, starting with the image itself
+fn mipmap_chain(image: &RgbaImage) -> Vec<RgbaImage> {
+    let mut levels: Vec<RgbaImage> = vec![image.clone()];
+
+    while let Some(previous) = levels.last() {
+        if previous.width() == 1 && previous.height() == 1 {
+            break;
+        }
+
+        let level: RgbaImage = image::imageops::resize(
+            previous,
+            (previous.width() / 2).max(1),
+            (previous.height() / 2).max(1),
+            FilterType::Triangle,
+        );
+        levels.push(level);
+    }
+
+    levels
+}
+
+/// A texture uploaded to the graphics device
+#[derive(Debug)]
+struct GpuTexture {
+    /// The texture, kept alive for as long as it can be drawn
+    _texture: Texture,
+
+    /// Binds the texture and its sampler for drawing
+    bind_group: BindGroup,
+}
+
+/// Uploads the cached textures to the graphics device and frees the ones which are no longer used
+#[derive(Debug)]
+pub(crate) struct TextureManager {
+    /// Layout of the texture and sampler shaders read a texture from
+    bind_group_layout: BindGroupLayout,
+
+    /// Samples textures without smoothing so pixel art stays sharp
+    sampler: Sampler,
+
+    /// Blends between the mipmaps of textures which have them when they are drawn shrunk
+    mipmap_sampler: Sampler,
+
+    /// Textures on the graphics device by their id
+    uploaded: HashMap<TextureId, GpuTexture>,
+
+    /// Textures too big for the graphics device, kept so they are only warned about once
+    oversized: HashSet<TextureId>,
+}
+
+impl TextureManager {
+    /// Creates the manager, uploading the white texture
+    pub(crate) fn new(device: &Device, queue: &Queue) -> Self {
+        let bind_group_layout: BindGroupLayout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Texture Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let sampler: Sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Texture Sampler"),
+            ..Default::default()
+        });
+
+        let mipmap_sampler: Sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Texture Mipmap Sampler"),
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let mut manager: Self = Self {
+            bind_group_layout,
+            sampler,
+            mipmap_sampler,
+            uploaded: HashMap::new(),
+            oversized: HashSet::new(),
+        };
+        manager.upload_white(device, queue);
+
+        manager
+    }
+
+    /// Layout of the bind groups textures are drawn with
+    pub(crate) fn bind_group_layout(&self) -> &BindGroupLayout {
+        &self.bind_group_layout
+    }
+
+    /// Retrieves the bind group of an uploaded texture
+    pub(crate) fn get_bind_group(&self, id: TextureId) -> Option<&BindGroup> {
+        self.uploaded.get(&id).map(|texture| &texture.bind_group)
+    }
+
+    /// Frees the textures which are no longer used and uploads the ones which were loaded since the last call
+    ///
+    /// # Panics
+    ///
+    /// May panic if the texture cache's lock is poisoned
+    pub(crate) fn sync(&mut self, device: &Device, queue: &Queue) {
+        let mut cache: MutexGuard<TextureCache> = TEXTURE_CACHE.lock().unwrap();
+
+        cache.release_unused();
+
+        // Textures may also have been released from the cache by another manager (e.g. a headless renderer)
+        self.uploaded
+            .retain(|id, _| *id == TextureId::WHITE || cache.entries.contains_key(id));
+
+        self.oversized.retain(|id| cache.entries.contains_key(id));
+
+        for (id, texture) in &cache.entries {
+            if self.uploaded.contains_key(id) || self.oversized.contains(id) {
+                continue;
+            }
+
+            // Creating a texture bigger than the device supports is a validation error, which panics
+            if !fits_device(device, texture.image.width(), texture.image.height()) {
+                warn!(
+                    "Texture {} is {}x{}, larger than the {} pixels the graphics device supports, skipping it...",
+                    texture.key.display(),
+                    texture.image.width(),
+                    texture.image.height(),
+                    device.limits().max_texture_dimension_2d
+                );
+                self.oversized.insert(*id);
+                continue;
+            }
+
+            trace!("Uploading texture {}...", texture.key.display());
+            let uploaded: GpuTexture = self.upload(device, queue, &texture.image, texture.mipmaps);
+            self.uploaded.insert(*id, uploaded);
+        }
+    }
+
+    /// Uploads every texture again (e.g. after the graphics were reset on Android)
+    pub(crate) fn reupload(&mut self, device: &Device, queue: &Queue) {
+        debug!("Uploading {} textures again...", self.uploaded.len());
+
+        self.uploaded.clear();
+        self.oversized.clear();
+        self.upload_white(device, queue);
+        self.sync(device, queue);
+    }
+
+    /// Uploads the white texture used for drawing solid colors
+    fn upload_white(&mut self, device: &Device, queue: &Queue) {
+        let white: RgbaImage = RgbaImage::from_pixel(1, 1, Rgba([255, 255, 255, 255]));
+        let uploaded: GpuTexture = self.upload(device, queue, &white, false);
+
+        self.uploaded.insert(TextureId::WHITE, uploaded);
+    }
+
+    /// Uploads an image along with its mipmaps if it has them
+    fn upload(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        image: &RgbaImage,
+        mipmaps: bool,
+    ) -> GpuTexture {
+        let levels: Vec<RgbaImage> = if mipmaps {
+            mipmap_chain(image)
+        } else {
+            vec![image.clone()]
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let texture: Texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Texture"),
+            size: wgpu::Extent3d {
+                width: image.width(),
+                height: image.height(),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: levels.len() as u32,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        for (mip_level, level) in (0..).zip(&levels) {
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                level.as_raw(),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * level.width()),
+                    rows_per_image: Some(level.height()),
+                },
+                wgpu::Extent3d {
+                    width: level.width(),
+                    height: level.height(),
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let view: TextureView = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler: &Sampler = if mipmaps {
+            &self.mipmap_sampler
+        } else {
+            &self.sampler
+        };
+
+        let bind_group: BindGroup = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Texture Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+
+        GpuTexture {
+            _texture: texture,
+            bind_group,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        path::{Path, PathBuf},
+    };
+
+    use image::RgbaImage;
+
+    use super::{TextureCache, TextureHandle};
+
+    #[test]
+    fn test_texture_cache() {
+        let mut cache: TextureCache = TextureCache {
+            entries: BTreeMap::new(),
+            paths: BTreeMap::new(),
+            next_id: 1,
+        };
+        let path: PathBuf = PathBuf::from("resources/assets/vanilla/texture/logo/logo.png");
+
+        let handle: TextureHandle = cache.insert(&path, RgbaImage::new(2, 2), false);
+        let other: TextureHandle = cache.insert(Path::new("generated"), RgbaImage::new(1, 1), true);
+        assert_ne!(handle.id(), other.id());
+
+        // Cached textures aren't loaded again
+        let clone: TextureHandle = cache.insert(&path, RgbaImage::new(4, 4), false);
+        assert_eq!(clone, handle);
+        assert_eq!(cache.entries[&handle.id()].image.width(), 2);
+
+        // Textures are only released once every handle is dropped
+        drop(handle);
+        drop(other);
+        assert_eq!(cache.release_unused().len(), 1);
+        assert!(cache.get(&path).is_some());
+
+        drop(clone);
+        assert_eq!(cache.release_unused().len(), 1);
+        assert!(cache.get(&path).is_none());
+    }
+
+    #[test]
+    fn test_mipmap_chain() {
+        let sizes: Vec<(u32, u32)> = super::mipmap_chain(&RgbaImage::new(8, 2))
+            .iter()
+            .map(RgbaImage::dimensions)
+            .collect();
+
+        assert_eq!(sizes, vec![(8, 2), (4, 1), (2, 1), (1, 1)]);
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::{Mutex, MutexGuard};
+
+use image::{Rgba, RgbaImage};
+use utils::licenses::ThirdPartyNotices;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::render::font::{GLYPH_HEIGHT, GLYPH_WIDTH};
+
+/// The licenses screen, if it is open
+static LICENSES_SCREEN: Mutex<Option<LicensesScreen>> = Mutex::new(None);
+
+/// Color of the background drawn over the game
+const BACKGROUND: Rgba<u8> = Rgba([16, 12, 24, 232]);
+
+/// Color of the notices
+const FOREGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// Scrollable view of the third party notices
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct LicensesScreen {
+    /// Lines of the notices document
+    lines: Vec<String>,
+
+    /// Index of the first wrapped line shown
+    scroll: usize,
+
+    /// Number of lines which fit on the screen when it was last drawn
+    rows: usize,
+}
+
+impl LicensesScreen {
+    /// Creates a screen showing a document from its start
+    pub(crate) fn new(document: &str) -> Self {
+        Self {
+            lines: document
+                .lines()
+                .map(|line| line.replace('\t', "    "))
+                .collect(),
+            scroll: 0,
+            rows: 0,
+        }
+    }
+
+    /// Moves the view by a number of lines, towards the end if positive
+    pub(crate) fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self.scroll.saturating_add_signed(lines);
+    }
+
+    /// Lines shown on a screen which fits `columns` by `rows` characters
+    ///
+    /// The view is kept from scrolling past the last line
+    pub(crate) fn page(&mut self, columns: usize, rows: usize) -> Vec<String> {
+        let wrapped: Vec<String> = crate::render::text::wrap(&self.lines, columns);
+
+        self.rows = rows;
+        self.scroll = self.scroll.min(wrapped.len().saturating_sub(rows));
+
+        wrapped.into_iter().skip(self.scroll).take(rows).collect()
+    }
+
+    /// Handles a key, returning whether it was used by the screen
+    fn pressed_key(&mut self, key: &Key) -> bool {
+        // Scroll by a page less a line, so the last line stays in view for context
+        let page: isize = isize::try_from(self.rows.saturating_sub(1).max(1)).unwrap_or(1);
+
+        match key {
+            Key::Named(NamedKey::ArrowUp) => self.scroll_by(-1),
+            Key::Named(NamedKey::ArrowDown) => self.scroll_by(1),
+            Key::Named(NamedKey::PageUp) => self.scroll_by(-page),
+            Key::Named(NamedKey::PageDown | NamedKey::Space) => self.scroll_by(page),
+            Key::Named(NamedKey::Home) => self.scroll = 0,
+            Key::Named(NamedKey::End) => self.scroll = usize::MAX,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// Whether the licenses screen is open
+///
+/// # Panics
+///
+/// May panic if the screen's lock is poisoned
+pub(crate) fn is_open() -> bool {
+    LICENSES_SCREEN.lock().unwrap().is_some()
+}
+
+/// Opens the licenses screen with the notices stored by the engine, or closes it if it's open
+///
+/// # Panics
+///
+/// May panic if the screen's lock is poisoned
+pub(crate) fn toggle() {
+    let mut screen: MutexGuard<Option<LicensesScreen>> = LICENSES_SCREEN.lock().unwrap();
+
+    *screen = if screen.is_some() {
+        debug!("Closing the licenses screen...");
+        None
+    } else {
+        debug!("Opening the licenses screen...");
+        let notices: ThirdPartyNotices = ThirdPartyNotices::new(&utils::licenses::get_notices());
+
+        Some(LicensesScreen::new(&notices.to_string()))
+    };
+}
+
+/// Handles a key while the licenses screen is open, returning whether it was used
+///
+/// # Panics
+///
+/// May panic if the screen's lock is poisoned
+pub(crate) fn pressed_key(key: &Key) -> bool {
+    LICENSES_SCREEN
+        .lock()
+        .unwrap()
+        .as_mut()
+        .is_some_and(|screen| screen.pressed_key(key))
+}
+
+/// Scrolls the licenses screen if it is open, returning whether it was
+///
+/// # Panics
+///
+/// May panic if the screen's lock is poisoned
+pub(crate) fn scroll_by(lines: isize) -> bool {
+    LICENSES_SCREEN
+        .lock()
+        .unwrap()
+        .as_mut()
+        .map(|screen| screen.scroll_by(lines))
+        .is_some()
+}
+
+/// Draws the licenses screen onto an image the size of the window, if it is open
+///
+/// Characters are drawn `scale` times bigger so they stay readable on high DPI screens
+///
+/// # Panics
+///
+/// May panic if the screen's lock is poisoned
+pub(crate) fn draw(width: u32, height: u32, scale: u32) -> Option<RgbaImage> {
+    let mut guard: MutexGuard<Option<LicensesScreen>> = LICENSES_SCREEN.lock().unwrap();
+    let screen: &mut LicensesScreen = guard.as_mut()?;
+
+    let scale: u32 = scale.max(1);
+    let columns: u32 = width / (GLYPH_WIDTH * scale);
+    let rows: u32 = height / (GLYPH_HEIGHT * scale);
+
+    // Leave a margin of a character around the notices and a line for the hint
+    let page: Vec<String> = screen.page(
+        columns.saturating_sub(2) as usize,
+        rows.saturating_sub(3) as usize,
+    );
+    let hint: String = utils::i18n::translate("client.licenses_hint");
+
+    let mut image: RgbaImage = RgbaImage::from_pixel(width, height, BACKGROUND);
+    crate::render::text::draw_text(&mut image, &page, (1, 1), scale, FOREGROUND);
+    crate::render::text::draw_text(
+        &mut image,
+        &[hint],
+        (1, rows.saturating_sub(1)),
+        scale,
+        FOREGROUND,
+    );
+
+    Some(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::{Key, NamedKey};
+
+    use super::LicensesScreen;
+
+    #[test]
+    fn test_licenses_screen() {
+        let mut screen: LicensesScreen = LicensesScreen::new("# Notices\n\n1\n2\n3\n4\n5");
+        assert_eq!(screen.page(4, 3), vec!["# No", "tice", "s"]);
+
+        assert!(screen.pressed_key(&Key::Named(NamedKey::PageDown)));
+        assert_eq!(screen.page(4, 3), vec!["s", "", "1"]);
+
+        // Scrolling stops at the last page
+        assert!(screen.pressed_key(&Key::Named(NamedKey::End)));
+        assert_eq!(screen.page(80, 3), vec!["3", "4", "5"]);
+
+        screen.scroll_by(-10);
+        assert_eq!(screen.page(80, 1), vec!["# Notices"]);
+        assert!(!screen.pressed_key(&Key::Character("a".into())));
+    }
+}
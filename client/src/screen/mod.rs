@@ -0,0 +1,2 @@
+/// Handles the screen listing the third party notices
+pub(crate) mod licenses;
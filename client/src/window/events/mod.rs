@@ -1,21 +1,25 @@
-use wgpu::{
-    Adapter, CommandEncoder, Device, Queue, RenderPass, RenderPassDescriptor, Surface,
-    SurfaceTexture, TextureView,
-};
+use image::RgbaImage;
+use wgpu::{CommandEncoder, Device, Queue, TextureView};
 use winit::{
-    dpi::PhysicalSize,
-    event::{ElementState, Event, KeyEvent, MouseButton, Touch, TouchPhase},
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{ElementState, Event, KeyEvent, MouseButton, MouseScrollDelta, Touch, TouchPhase},
     event_loop::ActiveEventLoop,
     keyboard::NamedKey,
     window::{Window, WindowAttributes},
 };
 
-use crate::window::window_state::WindowState;
+use crate::{
+    render::{camera::Camera, sprite::DrawList},
+    window::window_state::WindowState,
+};
+
+#[cfg(not(target_family = "wasm"))]
+use crate::render::headless::OffscreenTarget;
 
 /// The close button was pressed. Usually on the top right corner
-pub(crate) fn close_requested(window_target: &ActiveEventLoop) {
+pub(crate) fn close_requested() {
     debug!("The close button was pressed! Stopping...");
-    window_target.exit();
+    utils::exit::request_exit(utils::exit::ExitReason::WindowClosed);
 }
 
 /// This is technically not an event, but is called by the Resume event
@@ -31,9 +35,16 @@ pub(crate) fn create_window(window_target: &ActiveEventLoop) -> WindowState<'sta
     // These features are not useful in a web browser
     #[cfg(any(target_family = "unix", target_family = "windows"))]
     if cfg!(any(target_family = "unix", target_family = "windows")) {
+        let settings: utils::settings::Settings =
+            utils::settings::get_settings().unwrap_or_default();
+
         window_builder = window_builder
             .with_title("Catgirl Engine")
-            .with_window_icon(crate::get_icon());
+            .with_window_icon(crate::get_icon())
+            .with_inner_size(winit::dpi::LogicalSize::new(
+                settings.width,
+                settings.height,
+            ));
     }
 
     if cfg!(target_family = "wasm") {
@@ -92,38 +103,107 @@ pub(crate) fn suspended_window() {
 // processInput()
 // update() - Input gets passed to (internal) server, physics gets passed back
 /// Key was pressed on keyboard
+///
+/// Returns whether the window has to be redrawn
 // TODO: Offload to separate function with key mapping config
-pub(crate) fn pressed_key(event: KeyEvent, window_target: &ActiveEventLoop) {
+pub(crate) fn pressed_key(event: KeyEvent) -> bool {
+    if !event.state.is_pressed() {
+        trace!("Event: {:#?}", event);
+        return false;
+    }
+
+    // The licenses screen takes the keys it uses while it is open
+    if crate::screen::licenses::pressed_key(&event.logical_key) {
+        return true;
+    }
+
     match event.logical_key {
+        winit::keyboard::Key::Named(NamedKey::F1) => {
+            crate::screen::licenses::toggle();
+            true
+        }
+        #[cfg(not(target_family = "wasm"))]
+        winit::keyboard::Key::Named(NamedKey::F12) => {
+            // Taken while drawing the next frame
+            crate::render::screenshot::request();
+            true
+        }
+        winit::keyboard::Key::Named(NamedKey::BrowserBack | NamedKey::Escape)
+            if crate::screen::licenses::is_open() =>
+        {
+            crate::screen::licenses::toggle();
+            true
+        }
         winit::keyboard::Key::Named(NamedKey::BrowserBack) => {
             debug!("The back key Was pressed! Stopping...");
-            window_target.exit();
+            utils::exit::request_exit(utils::exit::ExitReason::Requested);
+            false
         }
         winit::keyboard::Key::Named(NamedKey::Escape) => {
             debug!("The escape key Was pressed! Stopping...");
-            window_target.exit();
+            utils::exit::request_exit(utils::exit::ExitReason::Requested);
+            false
         }
         _ => {
             trace!("Event: {:#?}", event);
+            false
         }
     }
 }
 
+/// Mouse wheel or touchpad was scrolled
+///
+/// Returns whether the window has to be redrawn
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn scrolled_mouse(delta: MouseScrollDelta) -> bool {
+    let lines: f64 = match delta {
+        MouseScrollDelta::LineDelta(_, y) => f64::from(y) * 3.0,
+        MouseScrollDelta::PixelDelta(position) => {
+            position.y / f64::from(crate::render::font::GLYPH_HEIGHT)
+        }
+    };
+
+    // Scrolling up moves towards the start
+    crate::screen::licenses::scroll_by(-lines.round() as isize)
+}
+
+/// Mouse cursor moved over the window
+pub(crate) fn moved_cursor(position: PhysicalPosition<f64>) {
+    #[allow(clippy::cast_possible_truncation)]
+    crate::render::camera::store_cursor_position([position.x as f32, position.y as f32]);
+}
+
+/// Mouse cursor left the window
+pub(crate) fn left_cursor() {
+    crate::render::camera::clear_cursor_position();
+}
+
 /// Mouse was clicked
 pub(crate) fn clicked_mouse(
     state: ElementState,
     button: MouseButton,
     _window_target: &ActiveEventLoop,
 ) {
-    if state.is_pressed() {
-        trace!("Mouse {:?} was pressed...", button);
+    let action: &str = if state.is_pressed() {
+        "pressed"
     } else {
-        trace!("Mouse {:?} was released...", button);
+        "released"
+    };
+
+    if let Some([x, y]) = crate::render::camera::get_cursor_world_position() {
+        trace!("Mouse {button:?} was {action} at ({x}, {y}) in the world...");
+    } else {
+        trace!("Mouse {button:?} was {action}...");
     }
 }
 
 /// Screen was touched
 pub(crate) fn touched_screen(touch: Touch) {
+    #[allow(clippy::cast_possible_truncation)]
+    let [x, y] = crate::render::camera::get_camera()
+        .screen_to_world([touch.location.x as f32, touch.location.y as f32]);
+    trace!("Finger {:?} is at ({x}, {y}) in the world", touch.id);
+
     match touch.phase {
         TouchPhase::Started => {
             trace!(
@@ -161,34 +241,33 @@ pub(crate) fn touched_screen(touch: Touch) {
 }
 
 /// The window was resized
-pub(crate) fn resized_window(window_state: &WindowState, _size: PhysicalSize<u32>) {
-    if window_state.device.is_none() {
-        warn!("Device is not setup... Have graphics been initialized?");
-        return;
-    }
-
-    if window_state.adapter.is_none() {
-        warn!("Adapter is not setup... Have graphics been initialized?");
+pub(crate) fn resized_window(window_state: &mut WindowState, size: PhysicalSize<u32>) {
+    // Minimised windows have no size to draw on, so the surface is kept until they are restored
+    if size.width == 0 || size.height == 0 {
+        trace!("Window was minimised, keeping the surface...");
         return;
     }
 
-    let window: &Window = &window_state.window;
-    let surface: &Surface = window_state.surface.as_ref().unwrap();
-    let device: &Device = window_state.device.as_ref().unwrap();
-    let adapter: &Adapter = window_state.adapter.as_ref().unwrap();
-
     let size: PhysicalSize<u32> = if cfg!(target_family = "wasm") {
         PhysicalSize::new(1000, 500)
     } else {
-        window.inner_size()
+        size
     };
 
-    surface.configure(
-        device,
-        &surface
-            .get_default_config(adapter, size.width, size.height)
-            .expect("Could not get surface default config!"),
+    crate::render::camera::set_viewport(
+        size.width,
+        size.height,
+        window_state.window.scale_factor(),
     );
+    window_state.resize_surface(size);
+}
+
+/// The window was moved to a screen with a different DPI scale, or the scale was changed
+pub(crate) fn changed_scale_factor(window_state: &WindowState, scale_factor: f64) {
+    trace!("Window scale factor: {scale_factor}");
+
+    let size: PhysicalSize<u32> = window_state.window.inner_size();
+    crate::render::camera::set_viewport(size.width, size.height, scale_factor);
 }
 
 /// The window was either just focused or lost focus
@@ -197,7 +276,9 @@ pub(crate) fn changed_focus(focused: bool) {
 }
 
 /// Redraw surface
-pub(crate) fn requested_redraw(window_state: &WindowState) {
+pub(crate) fn requested_redraw(window_state: &mut WindowState) {
+    let _span: tracing::span::EnteredSpan = trace_span!("redraw").entered();
+
     if window_state.device.is_none() {
         warn!("Device is not setup... Have graphics been initialized?");
         return;
@@ -208,19 +289,28 @@ pub(crate) fn requested_redraw(window_state: &WindowState) {
         return;
     }
 
-    // Configure a surface for drawing on
-    // https://sotrh.github.io/learn-wgpu/beginner/tutorial3-pipeline/#what-s-a-pipeline
-    let surface: &Surface = window_state.surface.as_ref().unwrap();
+    // Get a texture to draw onto the surface, skipping the frame if the surface isn't ready
+    // https://docs.rs/wgpu/latest/wgpu/struct.SurfaceTexture.html
+    // https://stackoverflow.com/a/4262634
+    let Some(output) = window_state.acquire_frame() else {
+        return;
+    };
+
     let device: &Device = window_state.device.as_ref().unwrap();
     let queue: &Queue = window_state.queue.as_ref().unwrap();
 
-    // Get a texture to draw onto the surface
-    // https://docs.rs/wgpu/latest/wgpu/struct.SurfaceTexture.html
-    // https://stackoverflow.com/a/4262634
-    // This segfaults when resizing window if no render commands are executed
-    let output: SurfaceTexture = surface
-        .get_current_texture()
-        .expect("Could not get a texture to draw on!");
+    // Upload the textures loaded since the last frame before drawing with them
+    if let Some(textures) = window_state.textures.as_mut() {
+        textures.sync(device, queue);
+    }
+
+    let (Some(textures), Some(sprites)) = (
+        window_state.textures.as_ref(),
+        window_state.sprites.as_mut(),
+    ) else {
+        warn!("Renderers are not setup... Have graphics been initialized?");
+        return;
+    };
 
     // Handle to the TextureView object which describes the texture and related metadata
     // https://docs.rs/wgpu/latest/wgpu/struct.TextureView.html
@@ -233,58 +323,89 @@ pub(crate) fn requested_redraw(window_state: &WindowState) {
     let mut encoder: CommandEncoder =
         device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
 
-    // Color to render
-    // Royal Purple - 104, 71, 141
-    // let texture_format: wgpu::TextureFormat = window_state.surface_config.as_ref().unwrap().format;
-    let color: wgpu::Color = if true {
-        // Renders correctly on all machines I've tested except Arm Mac
-        let ce_color: crate::render::Color = crate::render::srgb_to_linear_srgb(104, 71, 141);
+    // Draw the sprites the game queued for this frame
+    let mut draw_list: DrawList = crate::render::sprite::take_draw_list();
+    let camera: Camera = crate::render::camera::get_camera();
+    crate::render::draw_scene(
+        device,
+        queue,
+        &mut encoder,
+        &view,
+        textures,
+        sprites,
+        &camera,
+        &mut draw_list,
+    );
 
-        crate::render::get_wgpu_color_from_ce_color(ce_color)
-    } else {
-        // Renders correctly only on Arm Mac during my tests
-        wgpu::Color {
-            r: 104.0 / 255.0,
-            g: 71.0 / 255.0,
-            b: 141.0 / 255.0,
-            a: 1.0,
+    // Draw the licenses screen over the game while it is open
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let scale: u32 = window_state.window.scale_factor().round().max(1.0) as u32;
+    let overlay_image: Option<RgbaImage> =
+        crate::screen::licenses::draw(output.texture.width(), output.texture.height(), scale);
+
+    if let (Some(overlay), Some(image)) = (window_state.overlay.as_ref(), overlay_image.as_ref()) {
+        overlay.draw(device, queue, &mut encoder, &view, image);
+    }
+
+    // Draw the frame again onto a texture which can be read back to save it
+    #[cfg(not(target_family = "wasm"))]
+    let screenshot: Option<OffscreenTarget> = if crate::render::screenshot::take_request() {
+        let target: OffscreenTarget = OffscreenTarget::new(
+            device,
+            output.texture.width(),
+            output.texture.height(),
+            output.texture.format(),
+        );
+
+        crate::render::draw_scene(
+            device,
+            queue,
+            &mut encoder,
+            target.view(),
+            textures,
+            sprites,
+            &camera,
+            &mut draw_list,
+        );
+
+        if let (Some(overlay), Some(image)) =
+            (window_state.overlay.as_ref(), overlay_image.as_ref())
+        {
+            overlay.draw(device, queue, &mut encoder, target.view(), image);
         }
-    };
 
-    // Command to render
-    // https://docs.rs/wgpu/latest/wgpu/struct.RenderPassDescriptor.html
-    let render_pass_descriptor: RenderPassDescriptor = wgpu::RenderPassDescriptor {
-        label: Some("Render Pass"),
-        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-            view: &view,
-            resolve_target: None,
-            ops: wgpu::Operations {
-                load: wgpu::LoadOp::Clear(color),
-                store: wgpu::StoreOp::Store,
-            },
-        })],
-        depth_stencil_attachment: None,
-        timestamp_writes: None,
-        occlusion_query_set: None,
+        Some(target)
+    } else {
+        None
     };
 
-    // Block expression for ending the borrow of encoder
-    // https://doc.rust-lang.org/reference/expressions/block-expr.html
-    // https://doc.rust-lang.org/beta/rust-by-example/scope/borrow.html
-    {
-        // Render command
-        // https://docs.rs/wgpu/latest/wgpu/struct.RenderPass.html
-        let _render_pass: RenderPass = encoder.begin_render_pass(&render_pass_descriptor);
-    }
+    // The surface still works when suboptimal, but should be configured again to match the window
+    let suboptimal: bool = output.suboptimal;
 
     queue.submit(core::iter::once(encoder.finish()));
     output.present();
+
+    if suboptimal {
+        debug!("Surface is suboptimal, reconfiguring it...");
+        window_state.reconfigure_surface();
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(target) = screenshot {
+        match target
+            .read_back(device, queue)
+            .and_then(|image| crate::render::screenshot::save(&image))
+        {
+            Ok(path) => info!("Saved a screenshot to {}", path.display()),
+            Err(error) => warn!("Could not take a screenshot: {error}"),
+        }
+    }
 }
 
 /// Exiting loop
 pub(crate) fn exiting_loop() {
     trace!("Winit loop is exiting...");
-    utils::exit::set_exit();
+    utils::exit::request_exit(utils::exit::ExitReason::Requested);
 }
 
 /// Low memory warning
@@ -2,9 +2,12 @@ use std::sync::Arc;
 
 use winit::{dpi::PhysicalSize, window::Window};
 
+use crate::render::{overlay::Overlay, sprite::SpriteRenderer, texture::TextureManager};
+
+use utils::args::Vsync;
 use wgpu::{
-    Adapter, Device, DeviceDescriptor, Instance, Queue, RequestAdapterOptionsBase, Surface,
-    SurfaceConfiguration,
+    Adapter, Device, DeviceDescriptor, Instance, PresentMode, Queue, RequestAdapterOptionsBase,
+    Surface, SurfaceConfiguration, SurfaceError, SurfaceTexture,
 };
 
 /// Struct used for storing the state of a window
@@ -30,6 +33,15 @@ pub(crate) struct WindowState<'a> {
 
     /// Queue in which to send commands to the graphics device
     pub(crate) queue: Option<Queue>,
+
+    /// Draws screens such as the licenses screen over the game
+    pub(crate) overlay: Option<Overlay>,
+
+    /// Uploads the textures the game loaded to the graphics device
+    pub(crate) textures: Option<TextureManager>,
+
+    /// Draws the sprites the game queued for each frame
+    pub(crate) sprites: Option<SpriteRenderer>,
 }
 
 impl WindowState<'_> {
@@ -50,6 +62,9 @@ impl WindowState<'_> {
             adapter: None,
             device: None,
             queue: None,
+            overlay: None,
+            textures: None,
+            sprites: None,
         }
     }
 
@@ -144,16 +159,26 @@ impl WindowState<'_> {
             size.width,
             size.height
         );
+        crate::render::camera::set_viewport(size.width, size.height, self.window.scale_factor());
+
         let surface: &Surface<'_> = self.surface.as_ref().unwrap();
         self.surface_config =
             surface.get_default_config(self.adapter.as_ref().unwrap(), size.width, size.height);
 
-        let surface_config = self
+        let surface_config: &mut SurfaceConfiguration = self
             .surface_config
-            .as_ref()
+            .as_mut()
             .expect("Could not get surface config!");
         // surface_config.format = TextureFormat::Rgba8UnormSrgb;
 
+        let vsync: Vsync = utils::settings::get_settings().unwrap_or_default().vsync;
+        surface_config.present_mode = present_mode(
+            vsync,
+            &surface
+                .get_capabilities(self.adapter.as_ref().unwrap())
+                .present_modes,
+        );
+
         // https://github-wiki-see.page/m/gfx-rs/wgpu/wiki/Texture-Color-Formats-and-Srgb-conversions
         // https://blog.johnnovak.net/2016/09/21/what-every-coder-should-know-about-gamma/
         let texture_format: wgpu::TextureFormat = surface_config.format;
@@ -172,6 +197,21 @@ impl WindowState<'_> {
         trace!("Alpha Mode: {:?}", surface_config.alpha_mode);
 
         surface.configure(self.device.as_ref().unwrap(), surface_config);
+
+        debug!("Creating texture manager...");
+        let textures: TextureManager =
+            TextureManager::new(self.device.as_ref().unwrap(), self.queue.as_ref().unwrap());
+
+        debug!("Creating sprite pipeline...");
+        self.sprites = Some(SpriteRenderer::new(
+            self.device.as_ref().unwrap(),
+            &textures,
+            texture_format,
+        ));
+        self.textures = Some(textures);
+
+        debug!("Creating overlay pipeline...");
+        self.overlay = Some(Overlay::new(self.device.as_ref().unwrap(), texture_format));
     }
 
     /// Recreate the surface after it has been destroyed (e.g. used on Android)
@@ -200,14 +240,128 @@ impl WindowState<'_> {
             .create_surface(self.window.clone())
             .expect("Could not create surface!");
 
+        // The pipelines were created for the stored configuration's format, so it is kept
         let size: PhysicalSize<u32> = self.window.inner_size();
-        surface.configure(
-            self.device.as_ref().unwrap(),
-            &surface
-                .get_default_config(self.adapter.as_ref().unwrap(), size.width, size.height)
-                .expect("Could not get surface default config!"),
-        );
+        let surface_config: &mut SurfaceConfiguration = self
+            .surface_config
+            .as_mut()
+            .expect("Could not get surface config!");
+        if size.width != 0 && size.height != 0 {
+            surface_config.width = size.width;
+            surface_config.height = size.height;
+        }
+        surface.configure(self.device.as_ref().unwrap(), surface_config);
 
         self.surface = Some(surface);
+
+        // The textures may have been lost along with the surface
+        if let Some(textures) = self.textures.as_mut() {
+            textures.reupload(self.device.as_ref().unwrap(), self.queue.as_ref().unwrap());
+        }
+    }
+
+    /// Resizes the surface, ignoring empty sizes (e.g. minimised windows)
+    pub(crate) fn resize_surface(&mut self, size: PhysicalSize<u32>) {
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+
+        let Some(surface_config) = self.surface_config.as_mut() else {
+            warn!("Surface is not configured... Have graphics been initialized?");
+            return;
+        };
+
+        surface_config.width = size.width;
+        surface_config.height = size.height;
+        self.reconfigure_surface();
+    }
+
+    /// Configures the surface again with the stored configuration (e.g. after it was lost or outdated)
+    pub(crate) fn reconfigure_surface(&self) {
+        let (Some(surface), Some(device), Some(surface_config)) = (
+            self.surface.as_ref(),
+            self.device.as_ref(),
+            self.surface_config.as_ref(),
+        ) else {
+            warn!("Surface is not setup... Have graphics been initialized?");
+            return;
+        };
+
+        trace!(
+            "Configuring surface ({}, {})...",
+            surface_config.width,
+            surface_config.height
+        );
+        surface.configure(device, surface_config);
+    }
+
+    /// Retrieves the next frame to draw on, reconfiguring the surface or skipping the frame if there is none
+    pub(crate) fn acquire_frame(&self) -> Option<SurfaceTexture> {
+        let surface: &Surface<'_> = self.surface.as_ref()?;
+
+        match surface.get_current_texture() {
+            Ok(frame) => Some(frame),
+            Err(SurfaceError::Lost | SurfaceError::Outdated) => {
+                debug!("Surface is lost or outdated, reconfiguring it...");
+                self.reconfigure_surface();
+                self.window.request_redraw();
+
+                None
+            }
+            Err(SurfaceError::Timeout) => {
+                warn!("Timed out waiting for the next frame, skipping it...");
+
+                None
+            }
+            Err(SurfaceError::OutOfMemory) => {
+                error!("Graphics device is out of memory, exiting...");
+                utils::exit::request_exit(utils::exit::ExitReason::Error);
+
+                None
+            }
+        }
+    }
+}
+
+/// Picks how frames are presented for a vsync setting, falling back to vsync if the surface doesn't support it
+fn present_mode(vsync: Vsync, supported: &[PresentMode]) -> PresentMode {
+    match vsync {
+        Vsync::On => PresentMode::AutoVsync,
+        Vsync::Off => PresentMode::AutoNoVsync,
+        Vsync::Mailbox if supported.contains(&PresentMode::Mailbox) => PresentMode::Mailbox,
+        Vsync::Mailbox => {
+            warn!("Mailbox presentation is not supported, falling back to vsync...");
+            PresentMode::AutoVsync
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::args::Vsync;
+    use wgpu::PresentMode;
+
+    #[test]
+    fn test_present_mode() {
+        let supported: [PresentMode; 2] = [PresentMode::Fifo, PresentMode::Mailbox];
+
+        assert_eq!(
+            super::present_mode(Vsync::On, &supported),
+            PresentMode::AutoVsync
+        );
+        assert_eq!(
+            super::present_mode(Vsync::Off, &supported),
+            PresentMode::AutoNoVsync
+        );
+        assert_eq!(
+            super::present_mode(Vsync::Mailbox, &supported),
+            PresentMode::Mailbox
+        );
+
+        // Surfaces without mailbox support fall back to vsync
+        assert_eq!(
+            super::present_mode(Vsync::Mailbox, &[PresentMode::Fifo]),
+            PresentMode::AutoVsync
+        );
     }
 }
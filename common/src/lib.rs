@@ -8,3 +8,6 @@
 
 /// Resources structs
 pub mod resources;
+
+/// Messages sent between the client and server
+pub mod protocol;
@@ -0,0 +1,290 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Version of the message schema
+///
+/// Must be incremented whenever the encoding of an existing message changes
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// Size of the protocol version prefix in front of every encoded message
+const VERSION_PREFIX_SIZE: usize = 2;
+
+/// Identifies an entity on both the client and server
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd, Default, Hash, Eq, Ord,
+)]
+pub struct EntityId(pub u64);
+
+/// First message sent by a client after the connection is established
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Handshake {
+    /// Protocol version the client speaks
+    pub protocol_version: u16,
+
+    /// Version of the engine the client is running
+    pub engine_version: String,
+
+    /// Name the player wants to be known by
+    pub player_name: String,
+}
+
+/// The server's answer to a successful handshake
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Welcome {
+    /// Entity controlled by the client
+    pub player: EntityId,
+
+    /// Number of ticks per second the server simulates
+    pub tick_rate: u32,
+
+    /// Tick the server is currently on
+    pub tick: u64,
+}
+
+/// Input sampled by the client for a single tick
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Input {
+    /// Tick the input was sampled for
+    pub tick: u64,
+
+    /// Horizontal movement from -1 (left) to 1 (right)
+    pub movement_x: f32,
+
+    /// Vertical movement from -1 (down) to 1 (up)
+    pub movement_y: f32,
+
+    /// Bitfield of pressed action buttons
+    pub buttons: u32,
+}
+
+/// State of a single entity at a point in time
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct EntityState {
+    /// Which entity this is
+    pub id: EntityId,
+
+    /// Position in world units
+    pub position: [f32; 2],
+
+    /// Velocity in world units per second
+    pub velocity: [f32; 2],
+
+    /// Rotation in radians
+    pub rotation: f32,
+}
+
+/// State of every entity relevant to a client on a single tick
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct EntitySnapshot {
+    /// Tick the snapshot was taken on
+    pub tick: u64,
+
+    /// Entities visible to the client
+    pub entities: Vec<EntityState>,
+}
+
+/// Chat message
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Chat {
+    /// Player who sent the message, or `None` for messages from the server itself
+    pub sender: Option<String>,
+
+    /// Contents of the message
+    pub text: String,
+}
+
+/// Explains why a client is being disconnected or is leaving
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Disconnect {
+    /// Human readable reason
+    pub reason: String,
+}
+
+/// Messages sent from the client to the server
+///
+/// New variants must only ever be appended to keep older messages decodable
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    /// Introduces the client to the server
+    Handshake(Handshake),
+
+    /// Input for a single tick
+    Input(Input),
+
+    /// Chat message typed by the player
+    Chat(Chat),
+
+    /// The client is leaving
+    Disconnect(Disconnect),
+}
+
+/// Messages sent from the server to the client
+///
+/// New variants must only ever be appended to keep older messages decodable
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    /// Accepts the client's handshake
+    Welcome(Welcome),
+
+    /// State of the world on a single tick
+    Snapshot(EntitySnapshot),
+
+    /// Chat message from a player or the server
+    Chat(Chat),
+
+    /// The server is removing the client
+    Disconnect(Disconnect),
+}
+
+/// Encoding shared by every message sent between the client and server
+///
+/// Messages are encoded as the little endian [`PROTOCOL_VERSION`] followed by the message in the postcard format
+pub trait ProtocolMessage: Serialize + DeserializeOwned {
+    /// Encodes the message into bytes
+    ///
+    /// # Errors
+    ///
+    /// Errors if the message cannot be serialized
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut bytes: Vec<u8> = PROTOCOL_VERSION.to_le_bytes().to_vec();
+        let message: Vec<u8> = postcard::to_allocvec(self)
+            .map_err(|error| format!("Could not encode message: {error}"))?;
+
+        bytes.extend_from_slice(&message);
+        Ok(bytes)
+    }
+
+    /// Decodes a message from bytes
+    ///
+    /// # Errors
+    ///
+    /// Errors if the bytes were encoded with a different protocol version or are not a valid message
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let version: u16 = peek_protocol_version(bytes)?;
+        if version != PROTOCOL_VERSION {
+            return Err(format!(
+                "Message uses protocol version {version} instead of {PROTOCOL_VERSION}..."
+            ));
+        }
+
+        let (message, remainder) = postcard::take_from_bytes(&bytes[VERSION_PREFIX_SIZE..])
+            .map_err(|error| format!("Could not decode message: {error}"))?;
+
+        if !remainder.is_empty() {
+            return Err("Message has trailing bytes...".to_string());
+        }
+
+        Ok(message)
+    }
+}
+
+impl ProtocolMessage for ClientMessage {}
+impl ProtocolMessage for ServerMessage {}
+
+/// Reads the protocol version an encoded message was written with
+///
+/// # Errors
+///
+/// Errors if the bytes are too short to contain a version
+pub fn peek_protocol_version(bytes: &[u8]) -> Result<u16, String> {
+    match bytes {
+        [low, high, ..] => Ok(u16::from_le_bytes([*low, *high])),
+        _ => Err("Message is too short to contain a protocol version...".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Chat, ClientMessage, Disconnect, EntityId, EntitySnapshot, EntityState, Handshake, Input,
+        ProtocolMessage, ServerMessage, Welcome, PROTOCOL_VERSION,
+    };
+
+    #[test]
+    fn test_client_round_trip() {
+        let messages: Vec<ClientMessage> = vec![
+            ClientMessage::Handshake(Handshake {
+                protocol_version: PROTOCOL_VERSION,
+                engine_version: "0.14.44".to_string(),
+                player_name: "Alexis".to_string(),
+            }),
+            ClientMessage::Input(Input {
+                tick: 42,
+                movement_x: -1.0,
+                movement_y: 0.5,
+                buttons: 0b11,
+            }),
+            ClientMessage::Chat(Chat {
+                sender: None,
+                text: "meow".to_string(),
+            }),
+            ClientMessage::Disconnect(Disconnect {
+                reason: "Quit".to_string(),
+            }),
+        ];
+
+        for message in messages {
+            let bytes: Vec<u8> = message.encode().unwrap();
+            assert_eq!(ClientMessage::decode(&bytes).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_server_round_trip() {
+        let messages: Vec<ServerMessage> = vec![
+            ServerMessage::Welcome(Welcome {
+                player: EntityId(7),
+                tick_rate: 20,
+                tick: 1000,
+            }),
+            ServerMessage::Snapshot(EntitySnapshot {
+                tick: 1000,
+                entities: vec![EntityState {
+                    id: EntityId(7),
+                    position: [1.0, 2.0],
+                    velocity: [0.0, -9.8],
+                    rotation: std::f32::consts::PI,
+                }],
+            }),
+            ServerMessage::Chat(Chat {
+                sender: Some("Alexis".to_string()),
+                text: "hello".to_string(),
+            }),
+            ServerMessage::Disconnect(Disconnect {
+                reason: "Server closed".to_string(),
+            }),
+        ];
+
+        for message in messages {
+            let bytes: Vec<u8> = message.encode().unwrap();
+            assert_eq!(ServerMessage::decode(&bytes).unwrap(), message);
+        }
+    }
+
+    // If this fails, the wire format changed and PROTOCOL_VERSION needs to be incremented
+    #[test]
+    fn test_wire_compatibility() {
+        let message: ClientMessage = ClientMessage::Chat(Chat {
+            sender: Some("a".to_string()),
+            text: "hi".to_string(),
+        });
+
+        assert_eq!(
+            message.encode().unwrap(),
+            vec![1, 0, 2, 1, 1, b'a', 2, b'h', b'i']
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let mut bytes: Vec<u8> = ServerMessage::Chat(Chat {
+            sender: None,
+            text: "hi".to_string(),
+        })
+        .encode()
+        .unwrap();
+
+        bytes[0] = bytes[0].wrapping_add(1);
+        assert!(ServerMessage::decode(&bytes).is_err());
+        assert!(ServerMessage::decode(&[]).is_err());
+    }
+}
@@ -1,18 +1,122 @@
-use serde::{Deserialize, Serialize};
+/// How an embedded file's contents are stored
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub enum Compression {
+    /// Stored as is
+    #[default]
+    None,
 
-/// Embedded Files
-#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+    /// Compressed as a raw deflate stream
+    Deflate,
+}
+
+/// File embedded in the binary
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
 pub struct EmbeddedFile {
-    /// Relative File Path
-    pub path: String,
+    /// Relative file path using `/` as the separator
+    pub path: &'static str,
+
+    /// Contents of file, compressed if `compression` says so
+    pub contents: &'static [u8],
+
+    /// How the contents are stored
+    pub compression: Compression,
 
-    /// Contents of File
-    pub contents: Vec<u8>,
+    /// Size of the file once decompressed
+    pub size: u64,
 }
 
-/// Embedded Files
-#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+/// Index of the files embedded in the binary
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
 pub struct EmbeddedFiles {
-    /// Vector containing embedded files
-    pub inner: Vec<EmbeddedFile>,
+    /// Embedded files sorted by path
+    files: &'static [EmbeddedFile],
+}
+
+impl EmbeddedFiles {
+    /// Indexes embedded files which are already sorted by path
+    #[must_use]
+    pub const fn new(files: &'static [EmbeddedFile]) -> Self {
+        Self { files }
+    }
+
+    /// Every embedded file sorted by path
+    #[must_use]
+    pub const fn files(&self) -> &'static [EmbeddedFile] {
+        self.files
+    }
+
+    /// Retrieves a file by its path
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&'static EmbeddedFile> {
+        self.files
+            .binary_search_by(|file| file.path.cmp(path))
+            .ok()
+            .map(|index| &self.files[index])
+    }
+
+    /// Embedded files whose path starts with a prefix
+    #[must_use]
+    pub fn with_prefix(&self, prefix: &str) -> &'static [EmbeddedFile] {
+        // Paths sharing a prefix are next to each other when sorted
+        let start: usize = self.files.partition_point(|file| file.path < prefix);
+        let length: usize = self.files[start..]
+            .iter()
+            .take_while(|file| file.path.starts_with(prefix))
+            .count();
+
+        &self.files[start..start + length]
+    }
+
+    /// Number of embedded files
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether no files are embedded
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Compression, EmbeddedFile, EmbeddedFiles};
+
+    #[test]
+    fn test_lookup() {
+        /// Files sorted by path
+        static FILES: [EmbeddedFile; 3] = [
+            EmbeddedFile {
+                path: "resources/assets/a.png",
+                contents: b"a",
+                compression: Compression::None,
+                size: 1,
+            },
+            EmbeddedFile {
+                path: "resources/locales/en.toml",
+                contents: b"en",
+                compression: Compression::None,
+                size: 2,
+            },
+            EmbeddedFile {
+                path: "resources/locales/pt.toml",
+                contents: b"pt",
+                compression: Compression::None,
+                size: 2,
+            },
+        ];
+        let files: EmbeddedFiles = EmbeddedFiles::new(&FILES);
+
+        assert_eq!(
+            files
+                .get("resources/locales/en.toml")
+                .map(|file| file.contents),
+            Some(&b"en"[..])
+        );
+        assert_eq!(files.get("resources/locales"), None);
+        assert_eq!(files.with_prefix("resources/locales/").len(), 2);
+        assert!(files.with_prefix("resources/missing/").is_empty());
+    }
 }
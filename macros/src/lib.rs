@@ -6,10 +6,10 @@
     html_playground_url = "https://play.rust-lang.org"
 )]
 
-use common::resources::{EmbeddedFile, EmbeddedFiles};
 use std::{
     collections::VecDeque,
-    path::{Path, PathBuf},
+    fmt::Write,
+    path::{Component, Path, PathBuf},
 };
 
 use proc_macro::TokenStream;
@@ -28,18 +28,100 @@ pub fn generate_embedded_resources(tokens: TokenStream) -> TokenStream {
         return error_message;
     }
 
-    let files: EmbeddedFiles = get_files(&PathBuf::from(&resources_path_result.unwrap()));
-    let files_json: String = serde_json::to_string(&files).unwrap();
+    let files: Vec<(String, PathBuf)> = get_files(&PathBuf::from(&resources_path_result.unwrap()));
+    let mut report: Vec<ReportRow> = Vec::with_capacity(files.len());
+    let mut entries: Vec<proc_macro2::TokenStream> = Vec::with_capacity(files.len());
+
+    for (path, full_path) in &files {
+        let full_path: &str = full_path.to_str().unwrap();
+        let contents: Vec<u8> = std::fs::read(full_path).unwrap();
+        let size: u64 = contents.len() as u64;
+
+        if let Some(compressed) = compress(&contents) {
+            report.push((path.clone(), size, compressed.len() as u64, true));
+
+            let literal: proc_macro2::Literal = proc_macro2::Literal::byte_string(&compressed);
+            entries.push(quote::quote! {
+                common::resources::EmbeddedFile {
+                    path: #path,
+                    contents: {
+                        // Rebuilds when the file changes, the unused copy is left out of the binary
+                        const _: &[u8] = include_bytes!(#full_path);
+                        #literal
+                    },
+                    compression: common::resources::Compression::Deflate,
+                    size: #size,
+                }
+            });
+        } else {
+            report.push((path.clone(), size, size, false));
+
+            entries.push(quote::quote! {
+                common::resources::EmbeddedFile {
+                    path: #path,
+                    contents: include_bytes!(#full_path),
+                    compression: common::resources::Compression::None,
+                    size: #size,
+                }
+            });
+        }
+    }
+
+    write_report(&report);
 
     quote::quote! {
         pub(super) fn get_embedded_resources() -> common::resources::EmbeddedFiles {
-            let files_json: String = #files_json.to_string();
-            serde_json::from_str::<common::resources::EmbeddedFiles>(&files_json).unwrap()
+            /// Embedded files sorted by path
+            static FILES: &[common::resources::EmbeddedFile] = &[#(#entries),*];
+
+            common::resources::EmbeddedFiles::new(FILES)
         }
     }
     .into()
 }
 
+/// Path, original size, embedded size and whether the file was compressed
+type ReportRow = (String, u64, u64, bool);
+
+/// Compresses a file if that saves at least a tenth of its size
+#[cfg(feature = "compress-assets")]
+fn compress(contents: &[u8]) -> Option<Vec<u8>> {
+    let compressed: Vec<u8> = miniz_oxide::deflate::compress_to_vec(contents, 10);
+
+    (compressed.len() * 10 < contents.len() * 9).then_some(compressed)
+}
+
+/// Files are embedded as is unless compression is compiled in
+#[cfg(not(feature = "compress-assets"))]
+fn compress(_contents: &[u8]) -> Option<Vec<u8>> {
+    None
+}
+
+/// Writes the original and embedded size of every file to `embedded-resources.txt` in the build's output directory
+///
+/// Skipped if the calling crate has no build script
+fn write_report(rows: &[ReportRow]) {
+    let Ok(out_dir) = std::env::var("OUT_DIR") else {
+        return;
+    };
+
+    let mut report: String = String::from("original\tembedded\tcompression\tpath\n");
+    let (mut total_original, mut total_embedded): (u64, u64) = (0, 0);
+    for (path, original, embedded, compressed) in rows {
+        let compression: &str = if *compressed { "deflate" } else { "none" };
+        let _ = writeln!(report, "{original}\t{embedded}\t{compression}\t{path}");
+
+        total_original += original;
+        total_embedded += embedded;
+    }
+    let _ = writeln!(report, "{total_original}\t{total_embedded}\t\ttotal");
+
+    let _ = std::fs::write(
+        PathBuf::from(out_dir).join("embedded-resources.txt"),
+        report,
+    );
+}
+
 /// Determines if a path should be embedded
 #[rustfmt::skip]
 fn should_embed(path: &Path) -> bool {
@@ -49,6 +131,9 @@ fn should_embed(path: &Path) -> bool {
         // Locales should always be embedded
         resources_path.join("locales"),
 
+        // License texts are small and shown in-game, so they should always be embedded
+        resources_path.join("licenses"),
+
         // Only embed if the build target does not support filesystems
         #[cfg(feature = "embed-assets")]
         resources_path.join("assets"),
@@ -76,10 +161,12 @@ fn should_embed(path: &Path) -> bool {
 }
 
 /// Recursively retrieve within the specified directory
-fn get_files(resources_path: &Path) -> EmbeddedFiles {
+///
+/// Returns the relative path using `/` as the separator and the canonical path of each file, sorted by relative path
+fn get_files(resources_path: &Path) -> Vec<(String, PathBuf)> {
     let mut dirs: VecDeque<std::fs::ReadDir> =
         VecDeque::from([std::fs::read_dir(resources_path).unwrap()]);
-    let mut files: EmbeddedFiles = EmbeddedFiles { inner: Vec::new() };
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
 
     while !dirs.is_empty() {
         let dir: std::fs::ReadDir = VecDeque::pop_front(&mut dirs).unwrap();
@@ -92,17 +179,31 @@ fn get_files(resources_path: &Path) -> EmbeddedFiles {
                 dirs.push_back(std::fs::read_dir(&full_path).unwrap());
             } else if should_embed(&path) {
                 // println!("FP: {:?}; SFP: {:?}", &full_path, &path);
-                files.inner.push(EmbeddedFile {
-                    path: path.to_str().unwrap().to_string(),
-                    contents: std::fs::read(path).unwrap(),
-                });
+                // include_bytes! resolves relative paths against the calling file
+                files.push((
+                    to_embedded_path(&path),
+                    std::fs::canonicalize(&full_path).unwrap(),
+                ));
             }
         }
     }
 
+    // Lookups binary search the paths
+    files.sort();
     files
 }
 
+/// Joins the components of a path with `/` so lookups match on every platform
+fn to_embedded_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_str().unwrap()),
+            _ => None,
+        })
+        .collect::<Vec<&str>>()
+        .join("/")
+}
+
 /// Shorten the path to only the part after the tail end
 fn shorten_file_paths(resources_path: &Path, file_path: &Path) -> PathBuf {
     let path_components: std::path::Components<'_> = file_path.components();
@@ -1,17 +1,129 @@
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use common::protocol::EntitySnapshot;
+use utils::network::{MemorySocket, Socket};
+
+use crate::game::network::ServerNetwork;
+use crate::game::scripting::{ScriptLimits, ScriptRuntime};
+use crate::game::tick::TickScheduler;
+use crate::game::world::World;
+
 /// Server side game loop
 ///
+/// Listens for clients on the stored port
+///
 /// # Errors
 ///
-/// Errors not implemented yet...
+/// Errors if the stored tick rate is invalid or the server cannot listen on the stored port
 pub fn server_game_loop() -> Result<(), String> {
-    debug!("Started server game loop...");
-    error!("Dedicated server game loop not implemented yet...");
+    debug!("Started dedicated server game loop...");
+
+    run_game_loop(ServerNetwork::bind(super::get_port())?)
+}
+
+/// Listen server game loop
+///
+/// Only accepts the client in this process through the other half of the memory socket
+///
+/// # Errors
+///
+/// Errors if the stored tick rate is invalid
+pub fn listen_server_game_loop(socket: MemorySocket) -> Result<(), String> {
+    debug!("Started listen server game loop...");
+
+    run_game_loop(ServerNetwork::new(socket))
+}
+
+/// Runs fixed timestep ticks at the stored tick rate until the engine starts exiting
+///
+/// # Errors
+///
+/// Errors if the stored tick rate is invalid
+fn run_game_loop<S: Socket>(mut network: ServerNetwork<S>) -> Result<(), String> {
+    let mut scheduler: TickScheduler = TickScheduler::new(super::get_tick_rate(), Instant::now())?;
+    info!(
+        "Server is ticking at {} ticks per second ({:?} per tick)...",
+        scheduler.tick_rate(),
+        scheduler.tick_duration()
+    );
+
+    let world: Rc<RefCell<World>> = Rc::new(RefCell::new(World::default()));
+    let mut scripts: ScriptRuntime = ScriptRuntime::new(world.clone(), ScriptLimits::default());
+    load_scripts(&mut scripts);
 
-    loop {
-        if utils::exit::is_exiting() {
-            break;
+    while !utils::exit::is_exiting() {
+        network.update(&scheduler, &mut world.borrow_mut());
+
+        let due: u32 = scheduler.advance(Instant::now());
+        for _ in 0..due {
+            // Stop mid catch up so shutdown isn't delayed by a slow server
+            if utils::exit::is_exiting() {
+                break;
+            }
+
+            let tick_start: Instant = Instant::now();
+            update(&scheduler, &world, &mut scripts, &mut network);
+            scheduler.finish_tick(tick_start.elapsed());
+        }
+
+        // Sleeping at most one tick keeps the exit check responsive
+        let wait: Duration = scheduler.time_until_next_tick(Instant::now());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
         }
     }
 
+    shutdown(&scheduler, &mut network);
     Ok(())
 }
+
+/// Loads the scripts shipped with the engine and every mod
+fn load_scripts(scripts: &mut ScriptRuntime) {
+    // Collected first so the virtual filesystem isn't locked while the scripts load
+    let paths: Vec<PathBuf> = crate::game::scripting::find_scripts(&utils::vfs::get_vfs());
+
+    for path in paths {
+        if let Err(error) = scripts.load(&path) {
+            error!("Could not load script: {error}");
+        }
+    }
+
+    debug!("Loaded {} scripts...", scripts.len());
+}
+
+/// Simulates a single tick of the server
+fn update<S: Socket>(
+    scheduler: &TickScheduler,
+    world: &Rc<RefCell<World>>,
+    scripts: &mut ScriptRuntime,
+    network: &mut ServerNetwork<S>,
+) {
+    let tick: u64 = scheduler.current_tick();
+    let _span: tracing::span::EnteredSpan = trace_span!("tick", tick).entered();
+    trace!("Server tick {tick}...");
+
+    world
+        .borrow_mut()
+        .step(scheduler.tick_duration().as_secs_f32());
+    scripts.tick(tick);
+
+    let snapshot: EntitySnapshot = world.borrow().snapshot(tick);
+    network.broadcast_snapshot(snapshot);
+}
+
+/// Cleans up the server after the game loop has stopped
+fn shutdown<S: Socket>(scheduler: &TickScheduler, network: &mut ServerNetwork<S>) {
+    network.shutdown();
+
+    info!(
+        "Server stopped after {} ticks ({} overruns, {} skipped)...",
+        scheduler.current_tick(),
+        scheduler.overruns(),
+        scheduler.skipped()
+    );
+}
@@ -1,4 +1,47 @@
 /// Handles server side game loop
 mod game_loop;
 
+/// Handles connections to clients
+mod network;
+
+/// Runs server side scripts
+pub mod scripting;
+
+/// Handles scheduling fixed timestep ticks
+pub mod tick;
+
+/// Holds the entities simulated by the server
+pub mod world;
+
+use std::sync::OnceLock;
+
+pub use game_loop::listen_server_game_loop;
 pub use game_loop::server_game_loop as game_loop;
+
+/// Holds the number of ticks the server simulates per second
+static TICK_RATE: OnceLock<u32> = OnceLock::new();
+
+/// Holds the port the server listens on
+static PORT: OnceLock<u16> = OnceLock::new();
+
+/// Stores a custom tick rate for the server
+pub fn store_tick_rate(tick_rate: u32) {
+    let _tick_rate: &u32 = TICK_RATE.get_or_init(|| tick_rate);
+}
+
+/// Get the number of ticks the server simulates per second
+#[must_use]
+pub fn get_tick_rate() -> u32 {
+    *TICK_RATE.get().unwrap_or(&tick::DEFAULT_TICK_RATE)
+}
+
+/// Stores a custom port for the server to listen on
+pub fn store_port(port: u16) {
+    let _port: &u16 = PORT.get_or_init(|| port);
+}
+
+/// Get the port the server listens on
+#[must_use]
+pub fn get_port() -> u16 {
+    *PORT.get().unwrap_or(&utils::network::DEFAULT_PORT)
+}
@@ -0,0 +1,431 @@
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::Instant,
+};
+
+use common::protocol::{
+    peek_protocol_version, Chat, ClientMessage, Disconnect, EntityId, EntitySnapshot, Handshake,
+    ProtocolMessage, ServerMessage, Welcome, PROTOCOL_VERSION,
+};
+use utils::network::{Channel, DisconnectReason, Endpoint, EndpointConfig, NetworkEvent, Socket};
+
+use crate::game::tick::TickScheduler;
+use crate::game::world::World;
+
+/// Entities sent per snapshot message so each one fits within a single packet
+const SNAPSHOT_CHUNK_SIZE: usize = 32;
+
+/// Player who finished the handshake
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Player {
+    /// Name the player is known by
+    name: String,
+
+    /// Entity controlled by the player
+    entity: EntityId,
+}
+
+/// Handles connections to clients and the messages exchanged with them
+#[derive(Debug)]
+pub(crate) struct ServerNetwork<S: Socket = UdpSocket> {
+    /// Endpoint clients connect to
+    endpoint: Endpoint<S>,
+
+    /// Players keyed by their address
+    players: HashMap<SocketAddr, Player>,
+}
+
+impl ServerNetwork<UdpSocket> {
+    /// Starts listening for clients on the requested port
+    ///
+    /// # Errors
+    ///
+    /// Errors if the port cannot be bound
+    pub(crate) fn bind(port: u16) -> Result<Self, String> {
+        let endpoint: Endpoint = Endpoint::bind((Ipv4Addr::UNSPECIFIED, port), config())?;
+        info!("Server is listening on {}...", endpoint.local_address()?);
+
+        Ok(Self::from_endpoint(endpoint))
+    }
+}
+
+impl<S: Socket> ServerNetwork<S> {
+    /// Starts listening for clients on an existing socket
+    pub(crate) fn new(socket: S) -> Self {
+        Self::from_endpoint(Endpoint::new(socket, config()))
+    }
+
+    /// Wraps an endpoint which accepts connections
+    fn from_endpoint(endpoint: Endpoint<S>) -> Self {
+        Self {
+            endpoint,
+            players: HashMap::new(),
+        }
+    }
+
+    /// Exchanges packets with clients and handles every received message
+    pub(crate) fn update(&mut self, scheduler: &TickScheduler, world: &mut World) {
+        self.endpoint.update(Instant::now());
+
+        while let Some(event) = self.endpoint.poll_event() {
+            match event {
+                NetworkEvent::Connected(address) => info!("Client {address} connected..."),
+                NetworkEvent::Disconnected(address, reason) => {
+                    info!("Client {address} disconnected ({reason})...");
+
+                    if let Some(player) = self.players.remove(&address) {
+                        debug!("Removing entity {:?} of {}...", player.entity, player.name);
+                        world.despawn(player.entity);
+                        self.broadcast_chat(utils::i18n::translate_with(
+                            "server.player_left",
+                            &[("player", player.name.into())],
+                        ));
+                    }
+                }
+                NetworkEvent::Message {
+                    address, payload, ..
+                } => match peek_protocol_version(&payload) {
+                    // Messages of another protocol cannot be decoded, so the client is told before anything else
+                    Ok(version) if version != PROTOCOL_VERSION => {
+                        self.reject_version(address, version);
+                    }
+                    _ => match ClientMessage::decode(&payload) {
+                        Ok(message) => self.handle_message(address, message, scheduler, world),
+                        Err(error) => warn!("Could not decode message from {address}: {error}"),
+                    },
+                },
+            }
+        }
+    }
+
+    /// Handles a single message received from a client
+    fn handle_message(
+        &mut self,
+        address: SocketAddr,
+        message: ClientMessage,
+        scheduler: &TickScheduler,
+        world: &mut World,
+    ) {
+        match message {
+            ClientMessage::Handshake(handshake) => {
+                self.handle_handshake(address, handshake, scheduler, world);
+            }
+            ClientMessage::Input(input) => {
+                trace!("Received input for tick {} from {address}...", input.tick);
+            }
+            ClientMessage::Chat(chat) => {
+                let Some(player) = self.players.get(&address) else {
+                    return;
+                };
+
+                let chat: Chat = Chat {
+                    sender: Some(player.name.clone()),
+                    text: chat.text,
+                };
+                info!("<{}> {}", player.name, chat.text);
+                self.broadcast(&ServerMessage::Chat(chat));
+            }
+            ClientMessage::Disconnect(disconnect) => {
+                debug!("Client {address} is leaving: {}", disconnect.reason);
+                self.endpoint
+                    .disconnect(address, DisconnectReason::Requested);
+            }
+        }
+    }
+
+    /// Welcomes a client or disconnects it if it speaks a different protocol
+    fn handle_handshake(
+        &mut self,
+        address: SocketAddr,
+        handshake: Handshake,
+        scheduler: &TickScheduler,
+        world: &mut World,
+    ) {
+        if handshake.protocol_version != PROTOCOL_VERSION {
+            self.reject_version(address, handshake.protocol_version);
+            return;
+        }
+
+        // Spawning another entity would leave the player's first one in the world
+        if let Some(player) = self.players.get(&address) {
+            warn!(
+                "Ignoring repeated handshake from {} at {address}...",
+                player.name
+            );
+            return;
+        }
+
+        let entity: EntityId = world.spawn([0.0, 0.0]);
+
+        info!("{} joined from {address}...", handshake.player_name);
+        self.players.insert(
+            address,
+            Player {
+                name: handshake.player_name.clone(),
+                entity,
+            },
+        );
+
+        self.send(
+            address,
+            &ServerMessage::Welcome(Welcome {
+                player: entity,
+                tick_rate: scheduler.tick_rate(),
+                tick: scheduler.current_tick(),
+            }),
+        );
+        self.broadcast_chat(utils::i18n::translate_with(
+            "server.player_joined",
+            &[("player", handshake.player_name.into())],
+        ));
+    }
+
+    /// Disconnects a client which speaks a different protocol, telling it why
+    fn reject_version(&mut self, address: SocketAddr, version: u16) {
+        warn!(
+            "Client {address} speaks protocol version {version} instead of {PROTOCOL_VERSION}..."
+        );
+
+        self.send(
+            address,
+            &ServerMessage::Disconnect(Disconnect {
+                reason: "Protocol versions do not match".to_string(),
+            }),
+        );
+
+        // Disconnecting drops the queued message, so it has to be sent first
+        self.endpoint.flush(Instant::now());
+        self.endpoint
+            .disconnect(address, DisconnectReason::VersionMismatch);
+    }
+
+    /// Sends the state of every entity to every player
+    pub(crate) fn broadcast_snapshot(&mut self, snapshot: EntitySnapshot) {
+        if self.players.is_empty() {
+            return;
+        }
+
+        for entities in snapshot.entities.chunks(SNAPSHOT_CHUNK_SIZE) {
+            self.broadcast(&ServerMessage::Snapshot(EntitySnapshot {
+                tick: snapshot.tick,
+                entities: entities.to_vec(),
+            }));
+        }
+    }
+
+    /// Sends a chat message from the server to every player
+    fn broadcast_chat(&mut self, text: String) {
+        self.broadcast(&ServerMessage::Chat(Chat { sender: None, text }));
+    }
+
+    /// Sends a message to every player
+    fn broadcast(&mut self, message: &ServerMessage) {
+        let addresses: Vec<SocketAddr> = self.players.keys().copied().collect();
+
+        for address in addresses {
+            self.send(address, message);
+        }
+    }
+
+    /// Sends a message to a single client
+    fn send(&mut self, address: SocketAddr, message: &ServerMessage) {
+        let result: Result<(), String> = message
+            .encode()
+            .and_then(|bytes| self.endpoint.send(address, channel(message), bytes));
+
+        if let Err(error) = result {
+            warn!("Could not send message to {address}: {error}");
+        }
+    }
+
+    /// Tells every client the server is shutting down
+    pub(crate) fn shutdown(&mut self) {
+        self.broadcast(&ServerMessage::Disconnect(Disconnect {
+            reason: "Server is shutting down".to_string(),
+        }));
+
+        // Flush the disconnect messages before closing the connections
+        self.endpoint.flush(Instant::now());
+        self.endpoint.disconnect_all(DisconnectReason::Shutdown);
+        self.players.clear();
+    }
+}
+
+/// Endpoint settings used by the server
+fn config() -> EndpointConfig {
+    EndpointConfig {
+        accept_connections: true,
+        ..EndpointConfig::default()
+    }
+}
+
+/// Channel a message is sent on
+fn channel(message: &ServerMessage) -> Channel {
+    match message {
+        // Snapshots are replaced by newer ones, so resending old ones is pointless
+        ServerMessage::Snapshot(_) => Channel::Unreliable,
+        _ => Channel::ReliableOrdered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        time::{Duration, Instant},
+    };
+
+    use common::protocol::{
+        Chat, ClientMessage, Disconnect, Handshake, ProtocolMessage, ServerMessage,
+        PROTOCOL_VERSION,
+    };
+    use utils::network::{
+        Channel, DisconnectReason, Endpoint, EndpointConfig, MemorySocket, NetworkEvent,
+    };
+
+    use super::ServerNetwork;
+    use crate::game::{tick::TickScheduler, world::World};
+
+    /// Server and a client connected to it over memory sockets
+    struct Harness {
+        /// Server under test
+        server: ServerNetwork<MemorySocket>,
+
+        /// Client talking to the server
+        client: Endpoint<MemorySocket>,
+
+        /// Address of the server as seen by the client
+        server_address: SocketAddr,
+
+        /// Schedules the server's ticks
+        scheduler: TickScheduler,
+
+        /// World the server spawns players in
+        world: World,
+    }
+
+    impl Harness {
+        /// Connects a client to a new server
+        fn connect() -> Self {
+            let (server_socket, client_socket) = MemorySocket::pair();
+            let server_address: SocketAddr = client_socket.peer_address();
+
+            let mut harness: Self = Self {
+                server: ServerNetwork::new(server_socket),
+                client: Endpoint::new(client_socket, EndpointConfig::default()),
+                server_address,
+                scheduler: TickScheduler::new(20, Instant::now()).unwrap(),
+                world: World::default(),
+            };
+
+            harness.client.connect(server_address, Instant::now());
+            harness.pump(|events| events.contains(&NetworkEvent::Connected(server_address)));
+            harness
+        }
+
+        /// Sends a handshake to the server as a client of a protocol version would
+        fn handshake(&mut self, protocol_version: u16) {
+            let mut bytes: Vec<u8> = ClientMessage::Handshake(Handshake {
+                protocol_version,
+                engine_version: "0.0.0".to_string(),
+                player_name: "Neko".to_string(),
+            })
+            .encode()
+            .unwrap();
+
+            // Every message of that client is prefixed with its version, not the current one
+            bytes[..2].copy_from_slice(&protocol_version.to_le_bytes());
+            self.send_bytes(bytes);
+        }
+
+        /// Sends a message to the server
+        fn send(&mut self, message: &ClientMessage) {
+            self.send_bytes(message.encode().unwrap());
+        }
+
+        /// Sends an encoded message to the server
+        fn send_bytes(&mut self, bytes: Vec<u8>) {
+            self.client
+                .send(self.server_address, Channel::ReliableOrdered, bytes)
+                .unwrap();
+        }
+
+        /// Updates both sides until the client's events meet a condition
+        fn pump(&mut self, condition: impl Fn(&[NetworkEvent]) -> bool) -> Vec<NetworkEvent> {
+            let deadline: Instant = Instant::now() + Duration::from_secs(5);
+            let mut events: Vec<NetworkEvent> = Vec::new();
+
+            while !condition(&events) {
+                assert!(Instant::now() < deadline, "Timed out, events: {events:?}");
+
+                self.client.update(Instant::now());
+                self.server.update(&self.scheduler, &mut self.world);
+                while let Some(event) = self.client.poll_event() {
+                    events.push(event);
+                }
+
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            events
+        }
+    }
+
+    #[test]
+    fn test_repeated_handshake() {
+        let mut harness: Harness = Harness::connect();
+
+        harness.handshake(PROTOCOL_VERSION);
+        harness.handshake(PROTOCOL_VERSION);
+
+        // Messages arrive in order, so both handshakes were handled once the chat comes back
+        harness.send(&ClientMessage::Chat(Chat {
+            sender: None,
+            text: "Hello".to_string(),
+        }));
+        harness.pump(|events| {
+            events.iter().any(|event| match event {
+                NetworkEvent::Message { payload, .. } => matches!(
+                    ServerMessage::decode(payload),
+                    Ok(ServerMessage::Chat(Chat {
+                        sender: Some(_),
+                        ..
+                    }))
+                ),
+                _ => false,
+            })
+        });
+
+        // Only the first handshake spawns the player
+        assert_eq!(harness.world.len(), 1);
+        assert_eq!(harness.server.players.len(), 1);
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        let mut harness: Harness = Harness::connect();
+        let server_address: SocketAddr = harness.server_address;
+
+        harness.handshake(PROTOCOL_VERSION + 1);
+        let events: Vec<NetworkEvent> = harness.pump(|events| {
+            events.contains(&NetworkEvent::Disconnected(
+                server_address,
+                DisconnectReason::VersionMismatch,
+            ))
+        });
+
+        // The reason arrives before the connection is closed
+        let reason: Option<ServerMessage> = events.iter().find_map(|event| match event {
+            NetworkEvent::Message { payload, .. } => ServerMessage::decode(payload).ok(),
+            _ => None,
+        });
+        assert_eq!(
+            reason,
+            Some(ServerMessage::Disconnect(Disconnect {
+                reason: "Protocol versions do not match".to_string(),
+            }))
+        );
+        assert!(harness.world.is_empty());
+    }
+}
@@ -0,0 +1,686 @@
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use common::protocol::{EntityId, EntityState};
+use rhai::{
+    module_resolvers::DummyModuleResolver, CallFnOptions, Dynamic, Engine, EvalAltResult, FnPtr,
+    ParseError, Position, Scope, AST, FLOAT, INT,
+};
+
+use utils::vfs::Vfs;
+
+use crate::game::world::World;
+
+/// Directory holding a directory of assets for every namespace, such as vanilla and each mod
+const ASSETS_DIRECTORY: &str = "resources/assets";
+
+/// Directory of a namespace's assets holding its scripts
+const SCRIPTS_DIRECTORY: &str = "scripts";
+
+/// Extension of script files
+const SCRIPT_EXTENSION: &str = "rhai";
+
+/// Function called once after a script has been loaded
+const LOAD_HOOK: &str = "on_load";
+
+/// Function called with the tick number every tick
+const TICK_HOOK: &str = "on_tick";
+
+/// Limits enforced on every script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptLimits {
+    /// Operations a single call into a script may run
+    pub max_operations: u64,
+
+    /// Depth of nested function calls
+    pub max_call_levels: usize,
+
+    /// Depth of nested expressions
+    pub max_expression_depth: usize,
+
+    /// Bytes in a single string
+    pub max_string_size: usize,
+
+    /// Items in a single array
+    pub max_array_size: usize,
+
+    /// Properties in a single object map
+    pub max_map_size: usize,
+
+    /// Timers a script may have pending at once
+    pub max_timers: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 100_000,
+            max_call_levels: 32,
+            max_expression_depth: 64,
+            max_string_size: 64 * 1024,
+            max_array_size: 10_000,
+            max_map_size: 10_000,
+            max_timers: 256,
+        }
+    }
+}
+
+/// Callback a script asked to run at a later tick
+#[derive(Debug, Clone)]
+struct Timer {
+    /// Id handed back to the script
+    id: INT,
+
+    /// Function to call once due
+    callback: FnPtr,
+
+    /// Tick the timer is due on
+    due: u64,
+
+    /// Ticks between calls if the timer repeats
+    interval: Option<u64>,
+}
+
+/// State shared between a script and the functions it calls
+#[derive(Debug, Default)]
+struct ScriptState {
+    /// Tick currently being simulated
+    tick: u64,
+
+    /// Id given to the next timer
+    next_timer: INT,
+
+    /// Pending timers
+    timers: Vec<Timer>,
+}
+
+impl ScriptState {
+    /// Removes every due timer and returns their callbacks
+    ///
+    /// Repeating timers are rescheduled before being returned, so a callback can clear its own timer
+    fn take_due(&mut self, tick: u64) -> Vec<FnPtr> {
+        let mut due: Vec<FnPtr> = Vec::new();
+
+        self.timers.retain_mut(|timer| {
+            if timer.due > tick {
+                return true;
+            }
+
+            due.push(timer.callback.clone());
+            match timer.interval {
+                Some(interval) => {
+                    timer.due = tick + interval;
+                    true
+                }
+                None => false,
+            }
+        });
+
+        due
+    }
+}
+
+/// A compiled script with its own engine and variables
+struct Script {
+    /// Path the script was loaded from
+    path: PathBuf,
+
+    /// Engine with the curated API registered
+    engine: Engine,
+
+    /// Compiled script
+    ast: AST,
+
+    /// Variables defined by the script's top level statements
+    scope: Scope<'static>,
+
+    /// State shared with the registered API
+    state: Rc<RefCell<ScriptState>>,
+}
+
+impl Script {
+    /// Calls a hook if the script defines it
+    ///
+    /// # Errors
+    ///
+    /// Errors with the file and line if the hook fails
+    fn call_hook(&mut self, name: &str, arguments: Vec<Dynamic>) -> Result<(), String> {
+        let defined: bool = self
+            .ast
+            .iter_functions()
+            .any(|function| function.name == name && function.params.len() == arguments.len());
+
+        if !defined {
+            return Ok(());
+        }
+
+        // The top level statements already ran when the script was loaded
+        let options: CallFnOptions = CallFnOptions::new().eval_ast(false).rewind_scope(true);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut self.scope, &self.ast, name, arguments)
+            .map(|_| ())
+            .map_err(|error| runtime_error(&self.path, *error))
+    }
+
+    /// Runs the tick hook and any due timers
+    ///
+    /// # Errors
+    ///
+    /// Errors with the file and line if the hook or a timer fails
+    fn tick(&mut self, tick: u64) -> Result<(), String> {
+        self.state.borrow_mut().tick = tick;
+        self.call_hook(TICK_HOOK, vec![Dynamic::from_int(to_int(tick))])?;
+
+        let due: Vec<FnPtr> = self.state.borrow_mut().take_due(tick);
+        for callback in due {
+            callback
+                .call::<Dynamic>(&self.engine, &self.ast, ())
+                .map(|_| ())
+                .map_err(|error| runtime_error(&self.path, *error))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs server side scripts
+pub(crate) struct ScriptRuntime {
+    /// World scripts spawn entities into
+    world: Rc<RefCell<World>>,
+
+    /// Limits enforced on every script
+    limits: ScriptLimits,
+
+    /// Loaded scripts in the order they were loaded
+    scripts: Vec<Script>,
+}
+
+impl ScriptRuntime {
+    /// Creates a runtime without any scripts loaded
+    pub(crate) fn new(world: Rc<RefCell<World>>, limits: ScriptLimits) -> Self {
+        Self {
+            world,
+            limits,
+            scripts: Vec::new(),
+        }
+    }
+
+    /// Loads a script from the resources
+    ///
+    /// # Errors
+    ///
+    /// Errors if the script cannot be found, does not compile, or fails while loading
+    pub(crate) fn load(&mut self, path: &Path) -> Result<(), String> {
+        let source: String = utils::resources::get_resource_string(path)?;
+
+        self.load_source(path, &source)
+    }
+
+    /// Compiles a script, runs its top level statements and its load hook
+    ///
+    /// # Errors
+    ///
+    /// Errors with the file and line if the script does not compile or fails while loading
+    pub(crate) fn load_source(&mut self, path: &Path, source: &str) -> Result<(), String> {
+        let state: Rc<RefCell<ScriptState>> = Rc::new(RefCell::new(ScriptState::default()));
+        let engine: Engine = self.create_engine(path, &state);
+
+        let ast: AST = engine
+            .compile(source)
+            .map_err(|error| parse_error(path, error))?;
+
+        let mut scope: Scope<'static> = Scope::new();
+        engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|error| runtime_error(path, *error))?;
+
+        let mut script: Script = Script {
+            path: path.to_path_buf(),
+            engine,
+            ast,
+            scope,
+            state,
+        };
+        script.call_hook(LOAD_HOOK, Vec::new())?;
+
+        debug!("Loaded script {}...", path.display());
+        self.scripts.push(script);
+        Ok(())
+    }
+
+    /// Number of scripts still running
+    pub(crate) fn len(&self) -> usize {
+        self.scripts.len()
+    }
+
+    /// Runs every script's tick hook and due timers
+    ///
+    /// Scripts which fail are unloaded so one broken script can't flood the logs every tick
+    pub(crate) fn tick(&mut self, tick: u64) {
+        self.scripts.retain_mut(|script| match script.tick(tick) {
+            Ok(()) => true,
+            Err(error) => {
+                error!("{error}");
+                warn!("Unloading script {}...", script.path.display());
+                false
+            }
+        });
+    }
+
+    /// Creates an engine with the limits and curated API for a single script
+    fn create_engine(&self, path: &Path, state: &Rc<RefCell<ScriptState>>) -> Engine {
+        let mut engine: Engine = Engine::new();
+
+        // Scripts may only reach the resources they are handed
+        engine.set_module_resolver(DummyModuleResolver::new());
+
+        engine
+            .set_max_operations(self.limits.max_operations)
+            .set_max_call_levels(self.limits.max_call_levels)
+            .set_max_expr_depths(
+                self.limits.max_expression_depth,
+                self.limits.max_expression_depth,
+            )
+            .set_max_string_size(self.limits.max_string_size)
+            .set_max_array_size(self.limits.max_array_size)
+            .set_max_map_size(self.limits.max_map_size);
+
+        register_logging(&mut engine, path);
+        register_entities(&mut engine, &self.world);
+        register_timers(&mut engine, state, self.limits.max_timers);
+
+        engine
+    }
+}
+
+/// Finds the scripts in every namespace's scripts directory
+///
+/// Scripts are ordered by the source providing them, so the engine's scripts load before the ones of mods
+pub(crate) fn find_scripts(vfs: &Vfs) -> Vec<PathBuf> {
+    let assets: &Path = Path::new(ASSETS_DIRECTORY);
+
+    vfs.walk_files(assets)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|file| {
+            // Scripts live at assets/<namespace>/scripts/...
+            let Ok(relative) = file.strip_prefix(assets) else {
+                return false;
+            };
+            let mut components: std::path::Components<'_> = relative.components();
+
+            components.next().is_some()
+                && components
+                    .next()
+                    .is_some_and(|directory| directory.as_os_str() == SCRIPTS_DIRECTORY)
+                && file
+                    .extension()
+                    .is_some_and(|extension| extension == SCRIPT_EXTENSION)
+        })
+        .collect()
+}
+
+/// Registers functions which log through the engine's logger
+fn register_logging(engine: &mut Engine, path: &Path) {
+    let source: String = path.display().to_string();
+
+    let print_source: String = source.clone();
+    engine.on_print(move |text| info!("[{print_source}] {text}"));
+
+    let debug_source: String = source.clone();
+    engine.on_debug(move |text, _, position| {
+        debug!(
+            "[{debug_source}:{}] {text}",
+            position.line().unwrap_or_default()
+        );
+    });
+
+    let trace_source: String = source.clone();
+    engine.register_fn("log_trace", move |text: &str| {
+        trace!("[{trace_source}] {text}");
+    });
+
+    let debug_source: String = source.clone();
+    engine.register_fn("log_debug", move |text: &str| {
+        debug!("[{debug_source}] {text}");
+    });
+
+    let info_source: String = source.clone();
+    engine.register_fn("log_info", move |text: &str| {
+        info!("[{info_source}] {text}");
+    });
+
+    let warn_source: String = source.clone();
+    engine.register_fn("log_warn", move |text: &str| {
+        warn!("[{warn_source}] {text}");
+    });
+
+    engine.register_fn("log_error", move |text: &str| {
+        error!("[{source}] {text}");
+    });
+}
+
+/// Registers functions which spawn and move entities
+fn register_entities(engine: &mut Engine, world: &Rc<RefCell<World>>) {
+    let spawn_world: Rc<RefCell<World>> = world.clone();
+    engine.register_fn(
+        "spawn_entity",
+        move |x: FLOAT, y: FLOAT| -> Result<INT, Box<EvalAltResult>> {
+            let id: EntityId = spawn_world.borrow_mut().spawn([to_f32(x), to_f32(y)]);
+
+            INT::try_from(id.0).map_err(|_| "Ran out of entity ids".into())
+        },
+    );
+
+    let despawn_world: Rc<RefCell<World>> = world.clone();
+    engine.register_fn("despawn_entity", move |id: INT| -> bool {
+        to_entity(id).is_some_and(|id| despawn_world.borrow_mut().despawn(id))
+    });
+
+    let exists_world: Rc<RefCell<World>> = world.clone();
+    engine.register_fn("entity_exists", move |id: INT| -> bool {
+        to_entity(id).is_some_and(|id| exists_world.borrow().get(id).is_some())
+    });
+
+    let count_world: Rc<RefCell<World>> = world.clone();
+    engine.register_fn("entity_count", move || -> INT {
+        INT::try_from(count_world.borrow().len()).unwrap_or(INT::MAX)
+    });
+
+    let position_world: Rc<RefCell<World>> = world.clone();
+    engine.register_fn(
+        "set_entity_position",
+        move |id: INT, x: FLOAT, y: FLOAT| -> bool {
+            modify_entity(&position_world, id, |entity| {
+                entity.position = [to_f32(x), to_f32(y)];
+            })
+        },
+    );
+
+    let velocity_world: Rc<RefCell<World>> = world.clone();
+    engine.register_fn(
+        "set_entity_velocity",
+        move |id: INT, x: FLOAT, y: FLOAT| -> bool {
+            modify_entity(&velocity_world, id, |entity| {
+                entity.velocity = [to_f32(x), to_f32(y)];
+            })
+        },
+    );
+}
+
+/// Registers the tick counter and functions which schedule callbacks
+fn register_timers(engine: &mut Engine, state: &Rc<RefCell<ScriptState>>, max_timers: usize) {
+    let tick_state: Rc<RefCell<ScriptState>> = state.clone();
+    engine.register_fn("current_tick", move || -> INT {
+        to_int(tick_state.borrow().tick)
+    });
+
+    let timeout_state: Rc<RefCell<ScriptState>> = state.clone();
+    engine.register_fn(
+        "set_timeout",
+        move |callback: FnPtr, ticks: INT| -> Result<INT, Box<EvalAltResult>> {
+            add_timer(&timeout_state, max_timers, callback, ticks, false)
+        },
+    );
+
+    let interval_state: Rc<RefCell<ScriptState>> = state.clone();
+    engine.register_fn(
+        "set_interval",
+        move |callback: FnPtr, ticks: INT| -> Result<INT, Box<EvalAltResult>> {
+            add_timer(&interval_state, max_timers, callback, ticks, true)
+        },
+    );
+
+    let clear_state: Rc<RefCell<ScriptState>> = state.clone();
+    engine.register_fn("clear_timer", move |id: INT| -> bool {
+        let timers: &mut Vec<Timer> = &mut clear_state.borrow_mut().timers;
+        let count: usize = timers.len();
+        timers.retain(|timer| timer.id != id);
+
+        timers.len() != count
+    });
+}
+
+/// Schedules a callback a number of ticks from now
+///
+/// # Errors
+///
+/// Errors if the delay isn't positive or the script has too many pending timers
+fn add_timer(
+    state: &Rc<RefCell<ScriptState>>,
+    max_timers: usize,
+    callback: FnPtr,
+    ticks: INT,
+    repeat: bool,
+) -> Result<INT, Box<EvalAltResult>> {
+    let ticks: u64 = match u64::try_from(ticks) {
+        Ok(ticks) if ticks > 0 => ticks,
+        _ => return Err(format!("Timers must wait at least one tick, not {ticks}").into()),
+    };
+
+    let mut state = state.borrow_mut();
+    if state.timers.len() >= max_timers {
+        return Err(format!("Scripts may only have {max_timers} pending timers").into());
+    }
+
+    let id: INT = state.next_timer;
+    state.next_timer += 1;
+
+    let due: u64 = state.tick + ticks;
+    state.timers.push(Timer {
+        id,
+        callback,
+        due,
+        interval: repeat.then_some(ticks),
+    });
+
+    Ok(id)
+}
+
+/// Applies a change to an entity, returning false if it does not exist
+fn modify_entity(
+    world: &Rc<RefCell<World>>,
+    id: INT,
+    change: impl FnOnce(&mut EntityState),
+) -> bool {
+    let Some(id) = to_entity(id) else {
+        return false;
+    };
+
+    match world.borrow_mut().get_mut(id) {
+        Some(entity) => {
+            change(entity);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Converts a script integer into an entity id
+fn to_entity(id: INT) -> Option<EntityId> {
+    u64::try_from(id).ok().map(EntityId)
+}
+
+/// Converts a tick into a script integer
+fn to_int(tick: u64) -> INT {
+    INT::try_from(tick).unwrap_or(INT::MAX)
+}
+
+/// Converts a script float into an entity coordinate
+#[allow(clippy::cast_possible_truncation)]
+fn to_f32(value: FLOAT) -> f32 {
+    value as f32
+}
+
+/// Formats a compile error with the file and line it happened on
+fn parse_error(path: &Path, error: ParseError) -> String {
+    locate(path, error.1, &error.0)
+}
+
+/// Formats a runtime error with the file and line it happened on
+fn runtime_error(path: &Path, error: EvalAltResult) -> String {
+    let mut error: EvalAltResult = innermost_error(error);
+    let position: Position = error.take_position();
+
+    locate(path, position, &error)
+}
+
+/// Unwraps errors raised inside functions so the line points at the actual failure
+fn innermost_error(error: EvalAltResult) -> EvalAltResult {
+    match error {
+        EvalAltResult::ErrorInFunctionCall(_, _, inner, _)
+        | EvalAltResult::ErrorInModule(_, inner, _) => innermost_error(*inner),
+        error => error,
+    }
+}
+
+/// Prefixes an error with its file, line and column
+fn locate(path: &Path, position: Position, error: &impl Display) -> String {
+    match (position.line(), position.position()) {
+        (Some(line), Some(column)) => format!("{}:{line}:{column}: {error}", path.display()),
+        (Some(line), None) => format!("{}:{line}: {error}", path.display()),
+        _ => format!("{}: {error}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        path::{Path, PathBuf},
+        rc::Rc,
+        sync::Arc,
+    };
+
+    use utils::vfs::{Layer, MemoryMount, Vfs};
+
+    use crate::game::world::World;
+
+    use super::{ScriptLimits, ScriptRuntime};
+
+    /// Creates a runtime sharing the returned world
+    fn runtime() -> (ScriptRuntime, Rc<RefCell<World>>) {
+        let world: Rc<RefCell<World>> = Rc::new(RefCell::new(World::default()));
+
+        (
+            ScriptRuntime::new(world.clone(), ScriptLimits::default()),
+            world,
+        )
+    }
+
+    #[test]
+    fn test_hooks_and_timers_spawn_entities() {
+        let (mut runtime, world) = runtime();
+        let source: &str = r"
+            fn on_load() {
+                spawn_entity(1.0, 2.0);
+                set_timeout(|| spawn_entity(0.0, 0.0), 2);
+            }
+
+            fn on_tick(tick) {
+                if tick == 3 {
+                    spawn_entity(0.0, 0.0);
+                }
+            }
+        ";
+
+        runtime.load_source(Path::new("test.rhai"), source).unwrap();
+        assert_eq!(world.borrow().len(), 1);
+
+        runtime.tick(1);
+        assert_eq!(world.borrow().len(), 1);
+
+        runtime.tick(2);
+        assert_eq!(world.borrow().len(), 2);
+
+        runtime.tick(3);
+        assert_eq!(world.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_errors_report_file_and_line() {
+        let (mut runtime, _) = runtime();
+
+        let error: String = runtime
+            .load_source(Path::new("broken.rhai"), "let a = 1;\nlet b = ;")
+            .unwrap_err();
+        assert!(error.starts_with("broken.rhai:2:"), "{error}");
+
+        let error: String = runtime
+            .load_source(Path::new("missing.rhai"), "fn on_load() {\n    nope();\n}")
+            .unwrap_err();
+        assert!(error.starts_with("missing.rhai:2:"), "{error}");
+    }
+
+    #[test]
+    fn test_operation_limit_unloads_script() {
+        let (mut runtime, _) = runtime();
+
+        runtime
+            .load_source(Path::new("loop.rhai"), "fn on_tick(tick) { loop {} }")
+            .unwrap();
+        assert_eq!(runtime.len(), 1);
+
+        runtime.tick(1);
+        assert_eq!(runtime.len(), 0);
+    }
+
+    #[test]
+    fn test_scripts_load_from_every_source() {
+        let engine: MemoryMount = MemoryMount::new();
+        for (path, contents) in [
+            (
+                "resources/assets/vanilla/scripts/main.rhai",
+                "spawn_entity(0.0, 0.0);",
+            ),
+            ("resources/assets/vanilla/texture/logo.png", ""),
+        ] {
+            engine
+                .insert(Path::new(path), contents.as_bytes().to_vec())
+                .unwrap();
+        }
+
+        // The mod's namespace sorts first, but its scripts load after the engine's
+        let mod_files: MemoryMount = MemoryMount::new();
+        for (path, contents) in [
+            (
+                "assets/cats/scripts/spawn/cat.rhai",
+                "spawn_entity(1.0, 1.0);",
+            ),
+            ("assets/cats/scripts/readme.txt", ""),
+            ("assets/cats/notes.rhai", "spawn_entity(2.0, 2.0);"),
+        ] {
+            mod_files
+                .insert(Path::new(path), contents.as_bytes().to_vec())
+                .unwrap();
+        }
+
+        let mut vfs: Vfs = Vfs::new();
+        vfs.mount(Path::new("resources"), Layer::Mod, Arc::new(mod_files))
+            .unwrap();
+        vfs.mount(Path::new(""), Layer::Embedded, Arc::new(engine))
+            .unwrap();
+
+        let scripts: Vec<PathBuf> = super::find_scripts(&vfs);
+        assert_eq!(
+            scripts,
+            vec![
+                PathBuf::from("resources/assets/vanilla/scripts/main.rhai"),
+                PathBuf::from("resources/assets/cats/scripts/spawn/cat.rhai"),
+            ]
+        );
+
+        let (mut runtime, world) = runtime();
+        for path in &scripts {
+            runtime
+                .load_source(path, &vfs.read_string(path).unwrap())
+                .unwrap();
+        }
+        assert_eq!(runtime.len(), 2);
+        assert_eq!(world.borrow().len(), 2);
+    }
+}
@@ -0,0 +1,192 @@
+use std::time::{Duration, Instant};
+
+/// Default number of ticks the server simulates per second
+pub const DEFAULT_TICK_RATE: u32 = 20;
+
+/// Maximum number of ticks which may be ran to catch up in a single iteration
+///
+/// Anything further behind than this is dropped to prevent a spiral of death
+const MAX_CATCH_UP_TICKS: u32 = 5;
+
+/// Fixed timestep scheduler which decides when the server should tick
+///
+/// Uses an accumulator so the simulation always advances in steps of exactly [`TickScheduler::tick_duration()`]
+/// regardless of how long the loop spent sleeping or processing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickScheduler {
+    /// Number of ticks per second
+    tick_rate: u32,
+
+    /// How long a single tick lasts
+    tick_duration: Duration,
+
+    /// Time which has passed but has not been simulated yet
+    accumulator: Duration,
+
+    /// The last time the scheduler was advanced
+    last_instant: Instant,
+
+    /// Number of ticks which have been simulated
+    tick: u64,
+
+    /// Number of ticks which took longer than their allotted time
+    overruns: u64,
+
+    /// Number of ticks which were dropped because the server fell too far behind
+    skipped: u64,
+}
+
+impl TickScheduler {
+    /// Create a new tick scheduler starting at `now`
+    ///
+    /// # Errors
+    ///
+    /// Errors if the tick rate is zero
+    pub fn new(tick_rate: u32, now: Instant) -> Result<Self, String> {
+        if tick_rate == 0 {
+            return Err("Tick rate must be greater than zero...".to_string());
+        }
+
+        Ok(Self {
+            tick_rate,
+            tick_duration: Duration::from_secs(1) / tick_rate,
+            accumulator: Duration::ZERO,
+            last_instant: now,
+            tick: 0,
+            overruns: 0,
+            skipped: 0,
+        })
+    }
+
+    /// Number of ticks per second
+    #[must_use]
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// How long a single tick lasts
+    #[must_use]
+    pub fn tick_duration(&self) -> Duration {
+        self.tick_duration
+    }
+
+    /// Number of ticks which have been simulated so far
+    #[must_use]
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Number of ticks which took longer than their allotted time
+    #[must_use]
+    pub fn overruns(&self) -> u64 {
+        self.overruns
+    }
+
+    /// Number of ticks which were dropped because the server fell too far behind
+    #[must_use]
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Adds the time passed since the last call and returns how many ticks are due
+    ///
+    /// If more than [`MAX_CATCH_UP_TICKS`] are due, the excess time is dropped
+    pub fn advance(&mut self, now: Instant) -> u32 {
+        self.accumulator += now.saturating_duration_since(self.last_instant);
+        self.last_instant = now;
+
+        let due: u128 = self.accumulator.as_nanos() / self.tick_duration.as_nanos();
+        if due > u128::from(MAX_CATCH_UP_TICKS) {
+            #[allow(clippy::cast_possible_truncation)]
+            let dropped: u64 = (due - u128::from(MAX_CATCH_UP_TICKS)) as u64;
+
+            warn!("Server is running behind by {due} ticks, skipping {dropped} ticks...");
+
+            self.skipped += dropped;
+            self.accumulator = self.tick_duration * MAX_CATCH_UP_TICKS;
+            return MAX_CATCH_UP_TICKS;
+        }
+
+        // Due is at most MAX_CATCH_UP_TICKS here
+        #[allow(clippy::cast_possible_truncation)]
+        let due: u32 = due as u32;
+        due
+    }
+
+    /// Marks a single tick as simulated
+    ///
+    /// The elapsed time is the time spent running the tick and is used to detect overruns
+    pub fn finish_tick(&mut self, elapsed: Duration) {
+        self.accumulator = self.accumulator.saturating_sub(self.tick_duration);
+        self.tick += 1;
+
+        if elapsed > self.tick_duration {
+            self.overruns += 1;
+
+            warn!(
+                "Tick {} took {:?} which is longer than the tick duration of {:?}...",
+                self.tick, elapsed, self.tick_duration
+            );
+        }
+    }
+
+    /// How long to wait until the next tick is due
+    #[must_use]
+    pub fn time_until_next_tick(&self, now: Instant) -> Duration {
+        let pending: Duration = self.accumulator + now.saturating_duration_since(self.last_instant);
+
+        self.tick_duration.saturating_sub(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::TickScheduler;
+
+    #[test]
+    fn test_zero_tick_rate() {
+        assert!(TickScheduler::new(0, Instant::now()).is_err());
+    }
+
+    #[test]
+    fn test_fixed_steps() {
+        let start: Instant = Instant::now();
+        let mut scheduler: TickScheduler = TickScheduler::new(20, start).unwrap();
+        assert_eq!(scheduler.tick_duration(), Duration::from_millis(50));
+
+        // Not enough time has passed for a tick
+        assert_eq!(scheduler.advance(start + Duration::from_millis(30)), 0);
+        assert_eq!(
+            scheduler.time_until_next_tick(start + Duration::from_millis(30)),
+            Duration::from_millis(20)
+        );
+
+        // The remainder carries over into the next advance
+        assert_eq!(scheduler.advance(start + Duration::from_millis(110)), 2);
+        scheduler.finish_tick(Duration::ZERO);
+        scheduler.finish_tick(Duration::ZERO);
+        assert_eq!(scheduler.current_tick(), 2);
+        assert_eq!(
+            scheduler.time_until_next_tick(start + Duration::from_millis(110)),
+            Duration::from_millis(40)
+        );
+    }
+
+    #[test]
+    fn test_overruns_and_skips() {
+        let start: Instant = Instant::now();
+        let mut scheduler: TickScheduler = TickScheduler::new(10, start).unwrap();
+
+        scheduler.finish_tick(Duration::from_millis(150));
+        assert_eq!(scheduler.overruns(), 1);
+
+        // Ten seconds behind is capped to the catch up limit
+        assert_eq!(
+            scheduler.advance(start + Duration::from_secs(10)),
+            super::MAX_CATCH_UP_TICKS
+        );
+        assert_eq!(scheduler.skipped(), 95);
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use common::protocol::{EntityId, EntitySnapshot, EntityState};
+
+/// Every entity simulated by the server
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct World {
+    /// Entities keyed by their id
+    entities: BTreeMap<EntityId, EntityState>,
+
+    /// Id given to the next spawned entity
+    next_id: u64,
+}
+
+impl World {
+    /// Spawns a new entity at the requested position
+    pub fn spawn(&mut self, position: [f32; 2]) -> EntityId {
+        let id: EntityId = EntityId(self.next_id);
+        self.next_id += 1;
+
+        self.entities.insert(
+            id,
+            EntityState {
+                id,
+                position,
+                ..EntityState::default()
+            },
+        );
+
+        id
+    }
+
+    /// Removes an entity, returning false if it did not exist
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        self.entities.remove(&id).is_some()
+    }
+
+    /// Retrieves an entity
+    #[must_use]
+    pub fn get(&self, id: EntityId) -> Option<&EntityState> {
+        self.entities.get(&id)
+    }
+
+    /// Retrieves an entity for modification
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut EntityState> {
+        self.entities.get_mut(&id)
+    }
+
+    /// Number of entities
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Whether there are no entities
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Moves every entity along its velocity
+    pub fn step(&mut self, delta_seconds: f32) {
+        for entity in self.entities.values_mut() {
+            entity.position[0] += entity.velocity[0] * delta_seconds;
+            entity.position[1] += entity.velocity[1] * delta_seconds;
+        }
+    }
+
+    /// Captures the state of every entity
+    #[must_use]
+    pub fn snapshot(&self, tick: u64) -> EntitySnapshot {
+        EntitySnapshot {
+            tick,
+            entities: self.entities.values().copied().collect(),
+        }
+    }
+}
@@ -1,13 +1,89 @@
 use build_info::{BuildInfo, CrateInfo};
+use serde::Serialize;
 use std::collections::BTreeMap;
 
+use utils::licenses::Notice;
+
+/// Repository the engine is developed in
+pub(super) const REPOSITORY: &str = "https://github.com/foxgirl-labs/catgirl-engine";
+
+/// Crate as exported in machine readable version info and bills of materials
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(super) struct Package {
+    /// Name of the crate
+    pub(super) name: String,
+
+    /// Version of the crate
+    pub(super) version: String,
+
+    /// SPDX license expression, if the crate declares one
+    pub(super) license: Option<String>,
+
+    /// Where the crate can be downloaded from
+    pub(super) source: String,
+
+    /// Crates this crate depends on
+    pub(super) dependencies: Vec<PackageId>,
+}
+
+/// Identifies a crate by name and version, as several versions of one crate may be built into the engine
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(super) struct PackageId {
+    /// Name of the crate
+    pub(super) name: String,
+
+    /// Version of the crate
+    pub(super) version: String,
+}
+
+impl PackageId {
+    /// Identifies a crate from its build info
+    fn from_crate(info: &CrateInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            version: info.version.to_string(),
+        }
+    }
+}
+
+impl Package {
+    /// Describes a dependency, which build info doesn't record the source of
+    ///
+    /// Every dependency of the engine is published on crates.io, as it is itself
+    fn from_crate(info: &CrateInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            version: info.version.to_string(),
+            license: info.license.as_deref().map(license_expression),
+            source: format!(
+                "https://crates.io/api/v1/crates/{}/{}/download",
+                info.name, info.version
+            ),
+            dependencies: info
+                .dependencies
+                .iter()
+                .map(PackageId::from_crate)
+                .collect(),
+        }
+    }
+}
+
+/// Converts the legacy `MIT/Apache-2.0` license syntax to an SPDX expression
+fn license_expression(license: &str) -> String {
+    license
+        .split('/')
+        .map(str::trim)
+        .collect::<Vec<&str>>()
+        .join(" OR ")
+}
+
 /// Get the list of dependencies used in the engine
-fn get_dependencies(info: &BuildInfo) -> BTreeMap<String, CrateInfo> {
-    let mut dependencies: BTreeMap<String, CrateInfo> = BTreeMap::new();
+fn get_dependencies(info: &BuildInfo) -> BTreeMap<PackageId, CrateInfo> {
+    let mut dependencies: BTreeMap<PackageId, CrateInfo> = BTreeMap::new();
     let mut stack: Vec<&CrateInfo> = info.crate_info.dependencies.iter().collect();
     let crate_name: &String = &info.crate_info.name;
 
-    // Add each dependency only once
+    // Add each version of a dependency only once
     while let Some(dep) = stack.pop() {
         if dep.name.starts_with(crate_name) {
             // If one of my own crates, remove from results
@@ -15,7 +91,7 @@ fn get_dependencies(info: &BuildInfo) -> BTreeMap<String, CrateInfo> {
         }
 
         if dependencies
-            .insert(dep.name.as_str().to_string(), dep.to_owned())
+            .insert(PackageId::from_crate(dep), dep.to_owned())
             .is_none()
         {
             stack.extend(dep.dependencies.iter());
@@ -25,34 +101,90 @@ fn get_dependencies(info: &BuildInfo) -> BTreeMap<String, CrateInfo> {
     dependencies
 }
 
-/// Get all dependencies from the workspace used to build the engine
-#[must_use]
-pub(super) fn get_all_dependencies() -> BTreeMap<String, CrateInfo> {
-    let mut dependencies: BTreeMap<String, CrateInfo> =
-        get_dependencies(crate::build::build_info());
+/// Get the build info of every crate of the workspace built into the engine
+fn get_workspace_build_info() -> Vec<&'static BuildInfo> {
+    let mut workspace: Vec<&'static BuildInfo> =
+        vec![crate::build::build_info(), utils::build::build_info()];
 
-    let mut util_dependencies: BTreeMap<String, CrateInfo> =
-        get_dependencies(utils::build::build_info());
+    #[cfg(feature = "client")]
+    workspace.push(client::build::build_info());
 
-    dependencies.append(&mut util_dependencies);
+    #[cfg(feature = "server")]
+    workspace.push(server::build::build_info());
 
-    #[cfg(feature = "client")]
-    {
-        let mut client_dependencies: BTreeMap<String, CrateInfo> =
-            get_dependencies(client::build::build_info());
+    workspace
+}
 
-        dependencies.append(&mut client_dependencies);
+/// Get all dependencies from the workspace used to build the engine
+#[must_use]
+pub(super) fn get_all_dependencies() -> BTreeMap<PackageId, CrateInfo> {
+    let mut dependencies: BTreeMap<PackageId, CrateInfo> = BTreeMap::new();
+
+    for info in get_workspace_build_info() {
+        dependencies.append(&mut get_dependencies(info));
     }
 
-    #[cfg(feature = "server")]
-    {
-        let mut server_dependencies: BTreeMap<String, CrateInfo> =
-            get_dependencies(server::build::build_info());
+    dependencies
+}
+
+/// Describes every dependency used to build the engine, sorted by name and version
+#[must_use]
+pub(super) fn get_packages() -> Vec<Package> {
+    get_all_dependencies()
+        .values()
+        .map(Package::from_crate)
+        .collect()
+}
 
-        dependencies.append(&mut server_dependencies);
+/// Describes the engine itself, depending on what every crate of the workspace depends on directly
+#[must_use]
+pub(super) fn get_engine_package() -> Package {
+    let info: &BuildInfo = crate::build::build_info();
+
+    let mut dependencies: Vec<PackageId> = get_workspace_build_info()
+        .into_iter()
+        .flat_map(|workspace| {
+            workspace
+                .crate_info
+                .dependencies
+                .iter()
+                .filter(|dependency| !dependency.name.starts_with(&workspace.crate_info.name))
+                .map(PackageId::from_crate)
+        })
+        .collect();
+    dependencies.sort();
+    dependencies.dedup();
+
+    Package {
+        name: info.crate_info.name.clone(),
+        version: info.crate_info.version.to_string(),
+        license: info.crate_info.license.as_deref().map(license_expression),
+        source: REPOSITORY.to_string(),
+        dependencies,
     }
+}
 
-    dependencies
+/// Describes the engine and every dependency for the third party notices
+///
+/// The engine's own crates share its notice
+#[must_use]
+pub(super) fn get_notices() -> Vec<Notice> {
+    let engine: &CrateInfo = &crate::build::build_info().crate_info;
+    let dependencies: BTreeMap<PackageId, CrateInfo> = get_all_dependencies();
+
+    std::iter::once(engine)
+        .chain(
+            dependencies
+                .values()
+                .filter(|dependency| !dependency.name.starts_with(&engine.name)),
+        )
+        .map(|info| Notice {
+            name: info.name.clone(),
+            version: info.version.to_string(),
+            license: info.license.as_deref().map(license_expression),
+            copyright: info.authors.clone(),
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -61,20 +193,22 @@ mod tests {
     use build_info::CrateInfo;
     use std::collections::BTreeMap;
 
+    use super::PackageId;
+
     #[test]
     fn test_get_dependencies() {
         use crate::build::build_info;
         use build_info::BuildInfo;
 
         let build_info: &BuildInfo = build_info();
-        let dependencies: BTreeMap<String, CrateInfo> = super::get_dependencies(build_info);
+        let dependencies: BTreeMap<PackageId, CrateInfo> = super::get_dependencies(build_info);
 
         for (_, _) in dependencies {}
     }
 
     #[test]
     fn test_get_all_dependencies() {
-        let dependencies: BTreeMap<String, CrateInfo> = super::get_all_dependencies();
+        let dependencies: BTreeMap<PackageId, CrateInfo> = super::get_all_dependencies();
 
         for (_, _) in dependencies {}
     }
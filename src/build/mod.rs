@@ -1,10 +1,16 @@
 /// Module for grabbing and organizing dependencies
 mod dependencies;
 
-use build_info::{chrono::Datelike, BuildInfo, CrateInfo};
+/// Module for exporting software bills of materials
+mod sbom;
+
+use build_info::{
+    chrono::{Datelike, SecondsFormat},
+    BuildInfo, CrateInfo,
+};
 use std::collections::BTreeMap;
 
-use utils::println_string;
+use utils::{args::SbomFormat, licenses::Notice, println_string};
 
 // Generate build_info() function at compile time
 build_info::build_info!(
@@ -38,7 +44,8 @@ pub(super) fn print_version() {
 ///
 /// May panic if the dependency license info cannot be unwrapped
 pub(super) fn print_dependencies() {
-    let dependencies: BTreeMap<String, CrateInfo> = dependencies::get_all_dependencies();
+    let dependencies: BTreeMap<dependencies::PackageId, CrateInfo> =
+        dependencies::get_all_dependencies();
 
     // Only add newline if there are dependencies to print
     #[cfg(not(target_family = "wasm"))]
@@ -48,17 +55,70 @@ pub(super) fn print_dependencies() {
 
     // Print all dependencies
     // Loop through dependency list to print
-    for (name, dep) in dependencies {
+    for dep in dependencies.values() {
         let license: &String = if dep.license.is_some() {
             dep.license.as_ref().unwrap()
         } else {
             &"Unknown".to_string()
         };
 
-        println_string!("{} v{} - License {}", name, dep.version, license);
+        println_string!("{} v{} - License {}", dep.name, dep.version, license);
     }
 }
 
+/// Print the version, build info and dependencies of the engine as JSON
+pub(super) fn print_version_json() {
+    let info: &BuildInfo = build_info();
+
+    let commit: Option<serde_json::Value> = utils::build::get_version_control_build_info()
+        .map(|git| serde_json::json!({ "id": git.commit_id, "dirty": git.dirty }));
+
+    let version: serde_json::Value = serde_json::json!({
+        "name": info.crate_info.name,
+        "version": info.crate_info.version.to_string(),
+        "license": info.crate_info.license,
+        "authors": info.crate_info.authors,
+        "compiler": info.compiler.to_string(),
+        "timestamp": info.timestamp.to_string(),
+        "target": {
+            "arch": info.target.cpu.arch,
+            "os": info.target.os,
+        },
+        "profile": info.profile,
+        "commit": commit,
+        "dependencies": dependencies::get_packages(),
+    });
+
+    let version: String = serde_json::to_string_pretty(&version).unwrap_or_default();
+    utils::println_string!("{}", version);
+}
+
+/// Creates a software bill of materials listing the engine and every dependency it was built with
+pub(super) fn sbom(format: SbomFormat) -> String {
+    let info: &BuildInfo = build_info();
+    let created: String = info.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let engine: dependencies::Package = dependencies::get_engine_package();
+    let packages: Vec<dependencies::Package> = dependencies::get_packages();
+    let sbom: serde_json::Value = match format {
+        SbomFormat::Spdx => sbom::spdx(&engine, &packages, &created),
+        SbomFormat::CycloneDx => sbom::cyclonedx(&engine, &packages, &created),
+    };
+
+    serde_json::to_string_pretty(&sbom).unwrap_or_default()
+}
+
+/// Get the notices of the engine, its dependencies and the assets bundled by the client
+pub(super) fn get_notices() -> Vec<Notice> {
+    #[allow(unused_mut)]
+    let mut notices: Vec<Notice> = dependencies::get_notices();
+
+    #[cfg(feature = "client")]
+    notices.extend(client::get_notices());
+
+    notices
+}
+
 /// Print the dependencies of the engine
 ///
 /// # Panics
@@ -127,13 +187,30 @@ pub(super) fn log_build_info() {
     }
 }
 
+/// Describes the build and the target it was built for, such as for crash reports
+pub(super) fn describe_build() -> (String, String) {
+    let info: &BuildInfo = build_info();
+
+    (
+        format!(
+            "{} v{} built with {} at {}",
+            info.crate_info.name, info.crate_info.version, info.compiler, info.timestamp
+        ),
+        format!(
+            "{} {} with {} profile",
+            info.target.cpu.arch, info.target.os, info.profile
+        ),
+    )
+}
+
 /// Helps those who fork the project comply with the license
 pub(super) fn license_compliance_helper() {
     let info: &BuildInfo = build_info();
     let crate_name: &String = &info.crate_info.name;
-    let repo_url: String = "https://github.com/foxgirl-labs/catgirl-engine".to_string();
+    let repo_url: &str = dependencies::REPOSITORY;
 
     if !crate_name.starts_with("catgirl-engine") {
         info!("{crate_name} is based off of the Catgirl Engine from {repo_url}");
+        info!("Ship the output of the notices command with {crate_name} to comply with the licenses of its dependencies");
     }
 }
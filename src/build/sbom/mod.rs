@@ -0,0 +1,267 @@
+use serde_json::{json, Value};
+
+use super::dependencies::{Package, PackageId, REPOSITORY};
+
+/// Package URL identifying a crate
+fn purl(package: &Package) -> String {
+    format!("pkg:cargo/{}@{}", package.name, package.version)
+}
+
+/// Identifier of a package within an SPDX document, which only allows letters, digits, `.` and `-`
+///
+/// Crate names never contain `.`, so `_` is written as `.` to keep it apart from `-`,
+/// and a version never contains `..`, so the `+` of build metadata is written as `..`
+fn spdx_id(package: &Package) -> String {
+    let name: String = package.name.replace('_', ".");
+    let version: String = package.version.replace('+', "..");
+
+    format!("SPDXRef-Package-{name}-{version}")
+}
+
+/// Finds the package a dependency refers to
+///
+/// Dependencies which are not part of the bill of materials, such as the engine's own crates, are not found
+fn find<'a>(packages: &'a [Package], dependency: &PackageId) -> Option<&'a Package> {
+    packages
+        .iter()
+        .find(|package| package.name == dependency.name && package.version == dependency.version)
+}
+
+/// Creates an SPDX 2.3 document describing the engine and every dependency
+///
+/// `created` is the time the document describes, as an RFC 3339 timestamp in UTC
+pub(super) fn spdx(engine: &Package, packages: &[Package], created: &str) -> Value {
+    let spdx_packages: Vec<Value> = std::iter::once(engine)
+        .chain(packages)
+        .map(|package| {
+            json!({
+                "SPDXID": spdx_id(package),
+                "name": package.name,
+                "versionInfo": package.version,
+                "downloadLocation": package.source,
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": package.license.as_deref().unwrap_or("NOASSERTION"),
+                "copyrightText": "NOASSERTION",
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": purl(package),
+                }],
+            })
+        })
+        .collect();
+
+    let mut relationships: Vec<Value> = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": spdx_id(engine),
+    })];
+    for package in std::iter::once(engine).chain(packages) {
+        for dependency in package
+            .dependencies
+            .iter()
+            .filter_map(|dependency| find(packages, dependency))
+        {
+            relationships.push(json!({
+                "spdxElementId": spdx_id(package),
+                "relationshipType": "DEPENDS_ON",
+                "relatedSpdxElement": spdx_id(dependency),
+            }));
+        }
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{}-{}", engine.name, engine.version),
+        "documentNamespace": format!(
+            "{REPOSITORY}/spdx/{}-{}-{created}",
+            engine.name, engine.version
+        ),
+        "creationInfo": {
+            "created": created,
+            "creators": [format!("Tool: {}-{}", engine.name, engine.version)],
+        },
+        "packages": spdx_packages,
+        "relationships": relationships,
+    })
+}
+
+/// Creates a `CycloneDX` 1.5 bill of materials describing the engine and every dependency
+///
+/// `created` is the time the document describes, as an RFC 3339 timestamp in UTC
+pub(super) fn cyclonedx(engine: &Package, packages: &[Package], created: &str) -> Value {
+    let component = |package: &Package, kind: &str| -> Value {
+        let mut component: Value = json!({
+            "type": kind,
+            "bom-ref": purl(package),
+            "name": package.name,
+            "version": package.version,
+            "purl": purl(package),
+            "externalReferences": [{
+                "type": "distribution",
+                "url": package.source,
+            }],
+        });
+
+        if let Some(license) = &package.license {
+            component["licenses"] = json!([{ "expression": license }]);
+        }
+
+        component
+    };
+
+    let components: Vec<Value> = packages
+        .iter()
+        .map(|package| component(package, "library"))
+        .collect();
+
+    let dependencies: Vec<Value> = std::iter::once(engine)
+        .chain(packages)
+        .map(|package| {
+            let depends_on: Vec<String> = package
+                .dependencies
+                .iter()
+                .filter_map(|dependency| find(packages, dependency))
+                .map(purl)
+                .collect();
+
+            json!({
+                "ref": purl(package),
+                "dependsOn": depends_on,
+            })
+        })
+        .collect();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": created,
+            "component": component(engine, "application"),
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::super::dependencies::{Package, PackageId};
+
+    /// Describes a crate for the tests
+    fn package(name: &str, license: Option<&str>, dependencies: &[&str]) -> Package {
+        versioned_package(
+            name,
+            "1.0.0",
+            license,
+            &dependencies
+                .iter()
+                .map(|dependency| (*dependency, "1.0.0"))
+                .collect::<Vec<(&str, &str)>>(),
+        )
+    }
+
+    /// Describes a crate of a specific version, depending on specific versions of other crates, for the tests
+    fn versioned_package(
+        name: &str,
+        version: &str,
+        license: Option<&str>,
+        dependencies: &[(&str, &str)],
+    ) -> Package {
+        Package {
+            name: name.to_string(),
+            version: version.to_string(),
+            license: license.map(ToString::to_string),
+            source: format!("https://crates.io/api/v1/crates/{name}/{version}/download"),
+            dependencies: dependencies
+                .iter()
+                .map(|(name, version)| PackageId {
+                    name: (*name).to_string(),
+                    version: (*version).to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_sbom() {
+        let engine: Package = package("engine", Some("Zlib"), &["serde", "engine-utils"]);
+        let packages: Vec<Package> = vec![
+            package("serde", Some("MIT OR Apache-2.0"), &["serde_derive"]),
+            package("serde_derive", None, &[]),
+        ];
+
+        let spdx: Value = super::spdx(&engine, &packages, "2024-02-20T07:40:40Z");
+        assert_eq!(spdx["packages"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            spdx["packages"][2]["SPDXID"],
+            "SPDXRef-Package-serde.derive-1.0.0"
+        );
+        assert_eq!(spdx["packages"][2]["licenseDeclared"], "NOASSERTION");
+        // Describes the engine, which depends on serde, which depends on serde_derive
+        assert_eq!(spdx["relationships"].as_array().unwrap().len(), 3);
+
+        let cyclonedx: Value = super::cyclonedx(&engine, &packages, "2024-02-20T07:40:40Z");
+        assert_eq!(
+            cyclonedx["metadata"]["component"]["purl"],
+            "pkg:cargo/engine@1.0.0"
+        );
+        assert_eq!(
+            cyclonedx["components"][0]["licenses"][0]["expression"],
+            "MIT OR Apache-2.0"
+        );
+        assert!(cyclonedx["components"][1].get("licenses").is_none());
+        assert_eq!(
+            cyclonedx["dependencies"][0]["dependsOn"],
+            serde_json::json!(["pkg:cargo/serde@1.0.0"])
+        );
+    }
+
+    #[test]
+    fn test_sbom_multiple_versions() {
+        let engine: Package = package("engine", Some("Zlib"), &["rand"]);
+        let packages: Vec<Package> = vec![
+            versioned_package("rand", "1.0.0", None, &[("rand_core", "0.6.4")]),
+            versioned_package("rand_core", "0.6.4", None, &[]),
+            versioned_package("rand_core", "0.9.0+extra", None, &[]),
+            versioned_package("rand-core", "0.9.0", None, &[]),
+        ];
+
+        let spdx: Value = super::spdx(&engine, &packages, "2024-02-20T07:40:40Z");
+        let ids: Vec<&str> = spdx["packages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|package| package["SPDXID"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                "SPDXRef-Package-engine-1.0.0",
+                "SPDXRef-Package-rand-1.0.0",
+                "SPDXRef-Package-rand.core-0.6.4",
+                "SPDXRef-Package-rand.core-0.9.0..extra",
+                "SPDXRef-Package-rand-core-0.9.0",
+            ]
+        );
+        // Only the version rand depends on is related to it
+        assert_eq!(
+            spdx["relationships"][2]["relatedSpdxElement"],
+            "SPDXRef-Package-rand.core-0.6.4"
+        );
+        assert_eq!(spdx["relationships"].as_array().unwrap().len(), 3);
+
+        let cyclonedx: Value = super::cyclonedx(&engine, &packages, "2024-02-20T07:40:40Z");
+        assert_eq!(cyclonedx["components"].as_array().unwrap().len(), 4);
+        assert_eq!(
+            cyclonedx["dependencies"][1]["dependsOn"],
+            serde_json::json!(["pkg:cargo/rand_core@0.6.4"])
+        );
+    }
+}
@@ -20,3 +20,7 @@ mod build;
 
 /// Module for storing resources
 mod resources;
+
+/// Module for setting up the logger
+#[cfg(feature = "logging-subscriber")]
+mod logging;
@@ -0,0 +1,235 @@
+use std::fmt::Write;
+
+use tracing::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::format::FmtSpan,
+    layer::{Context, Layered, SubscriberExt},
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+use utils::settings::Settings;
+
+#[cfg(not(target_family = "wasm"))]
+use std::{path::Path, sync::Mutex};
+#[cfg(not(target_family = "wasm"))]
+use tracing_appender::{
+    non_blocking::{NonBlocking, WorkerGuard},
+    rolling::{RollingFileAppender, Rotation},
+};
+#[cfg(not(target_family = "wasm"))]
+use utils::args::OutputFormat;
+
+/// Tag the logs are written under in logcat
+#[cfg(target_os = "android")]
+const TAG: &str = "CatgirlEngine";
+
+/// Name the log files start with, followed by the date
+#[cfg(not(target_family = "wasm"))]
+const LOG_FILE_PREFIX: &str = "catgirl-engine";
+
+/// Number of daily log files kept before the oldest is deleted
+#[cfg(not(target_family = "wasm"))]
+const MAX_LOG_FILES: usize = 7;
+
+/// Writes out the lines buffered for the log file when dropped
+#[cfg(not(target_family = "wasm"))]
+static LOG_FILE_GUARD: Mutex<Option<WorkerGuard>> = Mutex::new(None);
+
+/// Subscriber the outputs are added to, which filters the logs before they reach them
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+
+/// Layer writing the logs somewhere
+type Output = Box<dyn Layer<Filtered> + Send + Sync>;
+
+/// Setup the logger for the current platform
+///
+/// The filter, format, log directory and span timings are read from the settings,
+/// the filter can be changed afterwards with [`utils::logging::set_log_filter`]
+///
+/// # Panics
+///
+/// May panic if a logger was already set up
+pub(super) fn setup_logger() {
+    // Android and browsers can't pass arguments, so they log with the default settings
+    let settings: Settings = if cfg!(any(target_os = "android", target_family = "wasm")) {
+        Settings::default()
+    } else {
+        crate::setup::get_settings()
+    };
+
+    // Fall back to the default filter so a typo doesn't hide every log
+    let (filter, filter_error): (EnvFilter, Option<String>) =
+        match EnvFilter::try_new(&settings.log_filter) {
+            Ok(filter) => (filter, None),
+            Err(error) => (
+                EnvFilter::new(utils::settings::DEFAULT_LOG_FILTER),
+                Some(error.to_string()),
+            ),
+        };
+    let (filter, handle) = reload::Layer::new(filter);
+
+    #[cfg_attr(target_family = "wasm", allow(unused_mut))]
+    let mut outputs: Vec<Output> = vec![console_output(&settings), Box::new(RecentLogs)];
+
+    #[cfg(not(target_family = "wasm"))]
+    let file_error: Option<String> = match &settings.log_directory {
+        Some(directory) => match file_output(directory, &settings) {
+            Ok(output) => {
+                outputs.push(output);
+                None
+            }
+            Err(error) => Some(error),
+        },
+        None => None,
+    };
+
+    // Also captures the logs of dependencies which use the log crate
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(outputs)
+        .try_init()
+        .unwrap();
+
+    let active_filter: &str = if let Some(error) = &filter_error {
+        warn!(
+            "Invalid log filter {}, using the default: {error}",
+            settings.log_filter
+        );
+        utils::settings::DEFAULT_LOG_FILTER
+    } else {
+        &settings.log_filter
+    };
+
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(error) = file_error {
+        warn!("Not writing the logs to a file: {error}");
+    }
+
+    utils::logging::store_filter_reloader(active_filter, move |filter: &str| {
+        let filter: EnvFilter = EnvFilter::try_new(filter)
+            .map_err(|error| format!("Invalid log filter {filter}: {error}"))?;
+
+        handle
+            .reload(filter)
+            .map_err(|error| format!("Could not change the log filter: {error}"))
+    });
+}
+
+/// Span events to log, the closing of each span along with how long it took if span timings are on
+fn span_events(settings: &Settings) -> FmtSpan {
+    if settings.log_span_timings {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    }
+}
+
+/// Writes the logs to standard error, as text or JSON
+#[cfg(not(any(target_os = "android", target_family = "wasm")))]
+fn console_output(settings: &Settings) -> Output {
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(span_events(settings));
+
+    match settings.log_format {
+        OutputFormat::Text => layer.boxed(),
+        OutputFormat::Json => layer.json().boxed(),
+    }
+}
+
+/// Writes the logs to logcat
+#[cfg(target_os = "android")]
+fn console_output(settings: &Settings) -> Output {
+    paranoid_android::layer(TAG)
+        .with_span_events(span_events(settings))
+        .boxed()
+}
+
+/// Writes the logs to the browser's console
+#[cfg(target_family = "wasm")]
+fn console_output(settings: &Settings) -> Output {
+    // Browsers don't provide the system time the timestamps are read from
+    tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .without_time()
+        .with_writer(tracing_web::MakeWebConsoleWriter::new())
+        .with_span_events(span_events(settings))
+        .boxed()
+}
+
+/// Writes the logs to a file in a directory, starting a new file each day
+///
+/// The file is written on another thread, the lines still buffered are written out while shutting down
+///
+/// # Errors
+///
+/// Errors if the directory cannot be created
+#[cfg(not(target_family = "wasm"))]
+fn file_output(directory: &Path, settings: &Settings) -> Result<Output, String> {
+    let appender: RollingFileAppender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(directory)
+        .map_err(|error| format!("Could not log to {}: {error}", directory.display()))?;
+
+    let (writer, guard): (NonBlocking, WorkerGuard) = tracing_appender::non_blocking(appender);
+    *LOG_FILE_GUARD.lock().unwrap() = Some(guard);
+    utils::exit::register_hook(utils::exit::ShutdownPhase::FlushLogs, "log file", || {
+        drop(LOG_FILE_GUARD.lock().unwrap().take());
+        Ok(())
+    });
+
+    let layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(writer)
+        .with_span_events(span_events(settings));
+
+    Ok(match settings.log_format {
+        OutputFormat::Text => layer.boxed(),
+        OutputFormat::Json => layer.json().boxed(),
+    })
+}
+
+/// Keeps the most recent logs for crash reports
+struct RecentLogs;
+
+impl<S: Subscriber> Layer<S> for RecentLogs {
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, S>) {
+        let mut fields: FieldsVisitor = FieldsVisitor::default();
+        event.record(&mut fields);
+
+        utils::crash::record_log_line(format!(
+            "{} {} > {}",
+            event.metadata().level(),
+            event.metadata().target(),
+            fields.line
+        ));
+    }
+}
+
+/// Formats the fields of an event as its message followed by `name=value` pairs
+#[derive(Debug, Default)]
+struct FieldsVisitor {
+    /// Fields formatted so far
+    line: String,
+}
+
+impl Visit for FieldsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if !self.line.is_empty() {
+            self.line.push(' ');
+        }
+
+        let _ = if field.name() == "message" {
+            write!(self.line, "{value:?}")
+        } else {
+            write!(self.line, "{}={value:?}", field.name())
+        };
+    }
+}
@@ -8,6 +8,8 @@
     html_playground_url = "https://play.rust-lang.org"
 )]
 
+use std::process::ExitCode;
+
 #[macro_use]
 extern crate tracing;
 
@@ -20,15 +22,17 @@ mod build;
 /// Module for storing resources
 mod resources;
 
+/// Module for setting up the logger
+#[cfg(feature = "logging-subscriber")]
+mod logging;
+
 /// Run as Executable (e.g. Linux)
 ///
-/// # Errors
-///
-/// May return a `String` if an error propagated up the stack
-fn main() -> Result<(), String> {
+/// Exits with the code of the reason the engine stopped, or 1 if a command failed
+fn main() -> ExitCode {
     // Setup logger for debugging
     #[cfg(feature = "logging-subscriber")]
-    setup::setup_logger();
+    logging::setup_logger();
 
     // Transfers embedded resources into utility crate
     utils::resources::store_embedded_resources(resources::get_embedded_resources());
@@ -36,15 +40,17 @@ fn main() -> Result<(), String> {
     // Helps with license compliance
     build::license_compliance_helper();
 
-    // Print version and copyright info
-    if setup::get_args().version {
-        build::print_version();
-        build::print_build_info();
-        build::print_dependencies();
-
-        println!();
-        build::print_license();
-        return Ok(());
+    // Run a command which doesn't start the engine (e.g. version)
+    let command: utils::args::Command = setup::get_args().command();
+    if !command.starts_engine() {
+        return match setup::run_command(&command) {
+            Err(error) => {
+                eprintln!("Error: {error}");
+
+                ExitCode::FAILURE
+            }
+            _ => ExitCode::SUCCESS,
+        };
     }
 
     // Process args for future use
@@ -57,10 +63,10 @@ fn main() -> Result<(), String> {
     debug!("Setting SIGINT hook...");
     ctrlc::set_handler(move || {
         debug!("SIGINT (Ctrl+C) Was Called! Stopping...");
-        utils::exit::set_exit();
+        utils::exit::request_exit(utils::exit::ExitReason::Interrupted);
 
         #[cfg(feature = "client")]
-        if !setup::get_args().server {
+        if !matches!(setup::get_args().command(), utils::args::Command::Serve(_)) {
             #[cfg(not(target_family = "wasm"))]
             let _ = client::game::advance_event_loop();
         }
@@ -69,9 +75,9 @@ fn main() -> Result<(), String> {
 
     if let Err(error) = setup::start() {
         error!("{:?}", error);
-
-        return Err(error);
+        utils::exit::request_exit(utils::exit::ExitReason::Error);
     }
 
-    Ok(())
+    let reason: utils::exit::ExitReason = utils::exit::shutdown(utils::exit::SHUTDOWN_TIMEOUT);
+    ExitCode::from(reason.code())
 }
@@ -1,32 +1,49 @@
 use clap::Parser;
-use utils::args::Args;
+use utils::{
+    args::{Args, Command},
+    exit::ShutdownPhase,
+    settings::Settings,
+};
 
-// Constants
-#[cfg(target_os = "android")]
-const TAG: &str = "CatgirlEngine";
+#[cfg(not(any(target_family = "wasm", target_os = "android", target_os = "ios")))]
+use std::path::PathBuf;
+#[cfg(not(any(target_family = "wasm", target_os = "android", target_os = "ios")))]
+use utils::{
+    args::{I18nCommand, ModsCommand, OutputFormat},
+    i18n::{check::CoverageReport, I18N},
+    licenses::{ThirdPartyNotices, LICENSES_PATH},
+    mods::{ModDiagnostic, ModSummary},
+};
+
+#[cfg(feature = "client")]
+use std::thread::JoinHandle;
 
 /// Process args for future use
 pub(super) fn process_args() {
+    // Report problems with the settings now the logger can show them
+    for diagnostic in utils::settings::get_diagnostics() {
+        warn!("{diagnostic}");
+    }
+
     // Store resources path in separate variable
     #[cfg(feature = "client")]
-    client::game::store_resources_path(get_args().resources);
+    client::game::store_resources_path(get_settings().resources);
 
-    // Uninstall desktop files
-    #[cfg(all(feature = "client", target_os = "linux"))]
-    if get_args().uninstall_desktop_files {
-        trace!("Uninstalling desktop files...");
-        let _ = client::uninstall_desktop_files();
-    }
+    // Offer the report of a crash from the last launch
+    offer_last_crash();
 
-    // Install desktop files
-    #[cfg(all(feature = "client", target_os = "linux"))]
-    if get_args().install_desktop_files {
-        trace!("Installing desktop files...");
-        let _ = client::install_desktop_files();
+    // Store tick rate for the server game loop
+    #[cfg(feature = "server")]
+    if let Command::Serve(serve) = get_args().command() {
+        server::game::store_tick_rate(serve.tick_rate);
     }
 
+    // Store port for the server to listen on
+    #[cfg(feature = "server")]
+    server::game::store_port(get_settings().port);
+
     #[cfg(not(target_family = "wasm"))]
-    if get_args().print_environment_variables {
+    if get_args().global.print_environment_variables {
         trace!("Printing environment variables...");
         utils::environment::print_environment_vars();
     }
@@ -35,6 +52,211 @@ pub(super) fn process_args() {
     trace!("Resources Path: {:?}", client::game::get_resources_path());
 }
 
+/// Loads the data every platform's engine needs before the game starts
+///
+/// Called from [`start`] since Android and the web don't process arguments
+fn load_engine_data() {
+    // Store the third party notices for the licenses screen
+    #[cfg(feature = "client")]
+    utils::licenses::store_notices(crate::build::get_notices());
+
+    // Make the resources directory and mods available, which the web has no filesystem for
+    #[cfg(not(target_family = "wasm"))]
+    mount_resources();
+
+    // Load translations once mods and the resources directory can provide them
+    utils::i18n::load_translations(get_settings().locale.as_deref());
+}
+
+/// Mounts the resources directory and the mods inside it over the embedded resources
+#[cfg(not(target_family = "wasm"))]
+fn mount_resources() {
+    // Mount the resources directory so the user can override embedded resources
+    if let Err(error) = utils::resources::mount_resources_directory(get_settings().resources) {
+        warn!("Could not mount resources directory: {error}");
+    }
+
+    // Discover mods which override resources
+    utils::mods::load_mods(&get_settings().resources);
+}
+
+/// Runs a command which doesn't start the engine
+///
+/// # Errors
+///
+/// Errors if the command fails, such as a translation check finding problems
+#[cfg(not(any(target_family = "wasm", target_os = "android", target_os = "ios")))]
+pub(super) fn run_command(command: &Command) -> Result<(), String> {
+    use crate::build;
+
+    match command {
+        Command::Run(_) | Command::Serve(_) => {
+            Err("The run and serve commands start the engine".to_string())
+        }
+        Command::Version(version) => {
+            match version.format {
+                OutputFormat::Json => build::print_version_json(),
+                OutputFormat::Text => {
+                    build::print_version();
+                    build::print_build_info();
+                    build::print_dependencies();
+
+                    println!();
+                    build::print_license();
+                }
+            }
+
+            Ok(())
+        }
+        Command::Sbom(sbom) => {
+            let document: String = build::sbom(sbom.format);
+
+            match &sbom.output {
+                Some(output) => std::fs::write(output, document)
+                    .map_err(|error| format!("Could not write {}: {error}", output.display())),
+                None => {
+                    println!("{document}");
+                    Ok(())
+                }
+            }
+        }
+        Command::Notices(notices) => {
+            // The resources directory can provide license texts which aren't embedded
+            mount_resources();
+
+            let document: ThirdPartyNotices = ThirdPartyNotices::new(&build::get_notices());
+            for id in document.missing_texts() {
+                eprintln!("No license text for {id}, add it as {LICENSES_PATH}/{id}.txt");
+            }
+
+            match &notices.output {
+                Some(output) => std::fs::write(output, format!("{document}\n"))
+                    .map_err(|error| format!("Could not write {}: {error}", output.display())),
+                None => {
+                    println!("{document}");
+                    Ok(())
+                }
+            }
+        }
+        #[cfg(all(feature = "client", target_os = "linux"))]
+        Command::InstallDesktop => {
+            // The desktop files and icon come from the resources directory unless they are embedded
+            mount_resources();
+            client::install_desktop_files()
+        }
+        #[cfg(all(feature = "client", target_os = "linux"))]
+        Command::UninstallDesktop => {
+            mount_resources();
+            client::uninstall_desktop_files()
+        }
+        #[cfg(not(all(feature = "client", target_os = "linux")))]
+        Command::InstallDesktop | Command::UninstallDesktop => {
+            Err("Desktop files can only be installed on Linux builds with the client".to_string())
+        }
+        Command::Pack(pack) => {
+            let archive: PathBuf =
+                utils::mods::pack::pack_mod(&pack.directory, pack.output.as_deref())?;
+            println!("Packed {}", archive.display());
+
+            Ok(())
+        }
+        Command::Mods {
+            command: ModsCommand::List { format },
+        } => list_mods(*format),
+        Command::LastCrash => {
+            let report: PathBuf = utils::crash::crash_directory()
+                .as_deref()
+                .and_then(utils::crash::latest_report)
+                .ok_or_else(|| "The engine hasn't crashed yet".to_string())?;
+
+            let contents: String = std::fs::read_to_string(&report)
+                .map_err(|error| format!("Could not read {}: {error}", report.display()))?;
+            println!("Crash report from {}\n", report.display());
+            print!("{contents}");
+
+            Ok(())
+        }
+        Command::I18n {
+            command: I18nCommand::Check { format, sources },
+        } => {
+            mount_resources();
+            check_translations(*format, sources)
+        }
+    }
+}
+
+/// Prints the installed mods in load order followed by the disabled mods
+///
+/// # Errors
+///
+/// Errors if the list cannot be serialized
+#[cfg(not(any(target_family = "wasm", target_os = "android", target_os = "ios")))]
+fn list_mods(format: OutputFormat) -> Result<(), String> {
+    let (mods, diagnostics): (Vec<ModSummary>, Vec<ModDiagnostic>) =
+        utils::mods::list_mods(&get_settings().resources);
+
+    match format {
+        OutputFormat::Text => {
+            if mods.is_empty() {
+                println!("No mods installed");
+            }
+
+            for listed in &mods {
+                let status: &str = if listed.loaded { "loaded" } else { "disabled" };
+                println!(
+                    "{} {} ({}) - {status} from {}",
+                    listed.name,
+                    listed.version,
+                    listed.id,
+                    listed.path.display()
+                );
+            }
+
+            for diagnostic in &diagnostics {
+                println!("{diagnostic}");
+            }
+        }
+        OutputFormat::Json => {
+            let diagnostics: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+            let list: serde_json::Value = serde_json::json!({
+                "mods": mods,
+                "diagnostics": diagnostics,
+            });
+
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&list).map_err(|error| error.to_string())?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks every locale against English and prints the report
+///
+/// # Errors
+///
+/// Errors if the report cannot be created or found problems
+#[cfg(not(any(target_family = "wasm", target_os = "android", target_os = "ios")))]
+fn check_translations(format: OutputFormat, sources: &[PathBuf]) -> Result<(), String> {
+    let report: CoverageReport = CoverageReport::new(&I18N::load(), sources)?;
+
+    match format {
+        OutputFormat::Text => println!("{report}"),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|error| error.to_string())?
+        ),
+    }
+
+    if report.passed() {
+        Ok(())
+    } else {
+        Err("Translation check found problems".to_string())
+    }
+}
+
 /// Retrieve parsed out command line arguments
 ///
 /// # Panics
@@ -49,70 +271,15 @@ pub(super) fn get_args() -> Args {
     }
 }
 
-/// Setup the logger for the current platform
-#[cfg(feature = "logging-subscriber")]
-pub(super) fn setup_logger() {
-    if cfg!(target_os = "android") {
-        // Limited Filter: trace,android_activity=debug,winit=debug
-        // Stronger Filter: trace,android_activity=off,winit=off
-
-        #[cfg(target_os = "android")]
-        android_logger::init_once(
-            android_logger::Config::default()
-                .with_max_level(tracing::log::LevelFilter::Trace)
-                .with_tag(TAG)
-                .with_filter(
-                    android_logger::FilterBuilder::new()
-                        .parse("main=trace,catgirl_engine=trace")
-                        .build(),
-                ),
-        );
-    } else if cfg!(target_family = "wasm") {
-        // https://github.com/daboross/fern/issues/134
-        #[cfg(target_family = "wasm")]
-        if let Err(_error) = fern::Dispatch::new()
-            .level(tracing::log::LevelFilter::Off)
-            .level_for("main", tracing::log::LevelFilter::Trace)
-            .level_for("catgirl_engine", tracing::log::LevelFilter::Trace)
-            .level_for("catgirl_engine_client", tracing::log::LevelFilter::Trace)
-            .level_for("catgirl_engine_server", tracing::log::LevelFilter::Trace)
-            .level_for("catgirl_engine_utils", tracing::log::LevelFilter::Trace)
-            .chain(fern::Output::call(console_log::log))
-            .apply()
-        {
-            warn!("Failed to initialize console logger...")
-        }
-    } else {
-        // windows, unix (which includes Linux, BSD, and OSX), or target_os = "macos"
-        let mut builder = pretty_env_logger::formatted_builder();
-        if let Ok(s) = std::env::var("RUST_LOG") {
-            // Set logger according to RUST_LOG environment variable
-            builder.parse_filters(&s);
-        } else {
-            // Failed to find RUST_LOG environment variable
-            builder
-                .default_format()
-                .filter(Some("main"), tracing::log::LevelFilter::Info)
-                .filter(Some("catgirl_engine"), tracing::log::LevelFilter::Info)
-                .filter(
-                    Some("catgirl_engine_client"),
-                    tracing::log::LevelFilter::Info,
-                )
-                .filter(
-                    Some("catgirl_engine_server"),
-                    tracing::log::LevelFilter::Info,
-                )
-                .filter(
-                    Some("catgirl_engine_utils"),
-                    tracing::log::LevelFilter::Info,
-                );
-        }
-
-        builder.try_init().unwrap();
-    }
+/// Retrieve the settings resolved from the config file, environment and command line arguments
+///
+/// The settings are loaded on first use
+#[must_use]
+pub(super) fn get_settings() -> Settings {
+    utils::settings::get_settings().unwrap_or_else(|| utils::settings::load_settings(&get_args()))
 }
 
-/// Setup a hook to catch panics for logging before shutdown
+/// Setup a hook to catch panics for logging and writing a crash report before shutdown
 fn set_panic_hook() {
     std::panic::set_hook(Box::new(|info| {
         let location_string = if let Some(location) = info.location() {
@@ -126,16 +293,60 @@ fn set_panic_hook() {
             String::new()
         };
 
-        if let Some(string) = info.payload().downcast_ref::<String>() {
+        let message: Option<String> =
+            info.payload()
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| {
+                    info.payload()
+                        .downcast_ref::<&str>()
+                        .map(ToString::to_string)
+                });
+        if let Some(string) = &message {
             error!("Caught panic{location_string}: {string}");
         } else {
             error!("Caught panic{location_string}");
         }
 
-        utils::exit::set_exit();
+        write_crash_report(message, info.location());
+        utils::exit::request_exit(utils::exit::ExitReason::Panicked);
     }));
 }
 
+/// Writes a report of a panic to the crash directory so it can be attached to bug reports
+fn write_crash_report(message: Option<String>, location: Option<&std::panic::Location>) {
+    let Some(directory) = utils::crash::crash_directory() else {
+        return;
+    };
+
+    let (build, target): (String, String) = crate::build::describe_build();
+    let report: utils::crash::CrashReport = utils::crash::CrashReport::capture(
+        message.unwrap_or_else(|| "Panicked without a message".to_string()),
+        location.map(ToString::to_string),
+        build,
+        target,
+    );
+
+    match utils::crash::write_report(&directory, &report) {
+        Ok(path) => error!("Wrote crash report to {}", path.display()),
+        Err(error) => error!("Could not write crash report: {error}"),
+    }
+}
+
+/// Tells the player where the report of the last crash is, if it wasn't offered before
+fn offer_last_crash() {
+    let Some(directory) = utils::crash::crash_directory() else {
+        return;
+    };
+
+    if let Some(report) = utils::crash::take_unseen_report(&directory) {
+        warn!(
+            "The engine crashed last time, run the last-crash command to show the report from {}",
+            report.display()
+        );
+    }
+}
+
 // /// This functions intentionally triggers a panic
 // ///
 // /// # Panics
@@ -163,19 +374,94 @@ pub(super) fn start() -> Result<(), String> {
     debug!("Setting panic hook...");
     set_panic_hook();
 
+    debug!("Loading notices, resources and translations...");
+    load_engine_data();
+
+    debug!("Registering shutdown hooks...");
+    utils::exit::register_hook(ShutdownPhase::FlushLogs, "standard streams", || {
+        use std::io::Write;
+
+        std::io::stdout()
+            .flush()
+            .and_then(|()| std::io::stderr().flush())
+            .map_err(|error| format!("Could not flush the logs: {error}"))
+    });
+
     debug!("Starting main loop...");
 
     #[cfg(feature = "server")]
-    if cfg!(not(feature = "client")) || get_args().server {
+    if cfg!(not(feature = "client")) || matches!(get_args().command(), Command::Serve(_)) {
         // Server exists, client may exist
         return server::game::game_loop();
     }
 
     // Client exists, server may exist
     #[cfg(feature = "client")]
-    return client::game::game_loop();
+    {
+        if let Some(listen_server) = connect_client()? {
+            // The listen server stops once the engine is exiting and disconnects the client on its way out
+            utils::exit::register_hook(
+                ShutdownPhase::DisconnectClients,
+                "listen server",
+                move || {
+                    debug!("Waiting for listen server to stop...");
+                    match listen_server.join() {
+                        Ok(result) => result,
+                        Err(_) => Err("Listen server panicked...".to_string()),
+                    }
+                },
+            );
+        }
+
+        return client::game::game_loop();
+    }
 
     // Server doesn't exist, client doesn't exist
     #[cfg(not(feature = "client"))]
     Err("Neither the client nor server features were configured at build time...".to_string())
 }
+
+/// Points the client at a remote server, or starts a listen server for single player
+///
+/// Returns the listen server's thread if one was started
+///
+/// # Errors
+///
+/// Errors if the remote server's address cannot be resolved or the listen server thread cannot be spawned
+#[cfg(feature = "client")]
+fn connect_client() -> Result<Option<JoinHandle<Result<(), String>>>, String> {
+    use std::net::{SocketAddr, ToSocketAddrs};
+
+    if let Command::Run(utils::args::RunArgs {
+        connect: Some(address),
+        ..
+    }) = get_args().command()
+    {
+        let resolved: SocketAddr = address
+            .to_socket_addrs()
+            .map_err(|error| format!("Could not resolve server address {address}: {error}"))?
+            .next()
+            .ok_or_else(|| format!("Server address {address} did not resolve to anything..."))?;
+
+        client::game::store_server_address(resolved);
+        return Ok(None);
+    }
+
+    // Threads are not available on Wasm
+    #[cfg(all(feature = "server", not(target_family = "wasm")))]
+    {
+        debug!("Starting listen server...");
+        let (server_socket, client_socket) = utils::network::MemorySocket::pair();
+        client::game::store_listen_server_socket(client_socket);
+
+        let listen_server: JoinHandle<Result<(), String>> = std::thread::Builder::new()
+            .name("listen-server".to_string())
+            .spawn(move || server::game::listen_server_game_loop(server_socket))
+            .map_err(|error| format!("Could not start listen server: {error}"))?;
+
+        return Ok(Some(listen_server));
+    }
+
+    #[cfg(not(all(feature = "server", not(target_family = "wasm"))))]
+    Ok(None)
+}
@@ -10,7 +10,7 @@ pub fn android_main(app: AndroidApp) {
 
     // Setup logger for debugging
     #[cfg(feature = "logging-subscriber")]
-    setup::setup_logger();
+    crate::logging::setup_logger();
 
     // Transfers embedded resources into utility crate
     utils::resources::store_embedded_resources(crate::resources::get_embedded_resources());
@@ -23,6 +23,9 @@ pub fn android_main(app: AndroidApp) {
 
     client::game::store_android_app(app);
     if let Err(error) = setup::start() {
-        error!("{:?}", error)
+        error!("{:?}", error);
+        utils::exit::request_exit(utils::exit::ExitReason::Error);
     }
+
+    utils::exit::shutdown(utils::exit::SHUTDOWN_TIMEOUT);
 }
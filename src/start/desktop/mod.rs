@@ -9,14 +9,12 @@ use core::ffi::{c_char, c_int};
 /// Catgirl Engine start
 ///
 /// The starting point when calling as a desktop library
+///
+/// Returns the code of the reason the engine stopped, or 1 if a command failed
 #[unsafe(no_mangle)]
 pub extern "C" fn start_engine(argc: c_int, argv: *const *const c_char) -> c_int {
     use crate::{build, setup};
 
-    // Setup logger for debugging
-    #[cfg(feature = "logging-subscriber")]
-    setup::setup_logger();
-
     // Transfers embedded resources into utility crate
     utils::resources::store_embedded_resources(crate::resources::get_embedded_resources());
 
@@ -37,15 +35,21 @@ pub extern "C" fn start_engine(argc: c_int, argv: *const *const c_char) -> c_int
         utils::args::set_parsed_args(args);
     }
 
-    // Print version and copyright info
-    if setup::get_args().version {
-        build::print_version();
-        build::print_build_info();
-        build::print_dependencies();
+    // Setup logger for debugging, once the args which can filter it are known
+    #[cfg(feature = "logging-subscriber")]
+    crate::logging::setup_logger();
+
+    // Run a command which doesn't start the engine (e.g. version)
+    let command: utils::args::Command = setup::get_args().command();
+    if !command.starts_engine() {
+        return match setup::run_command(&command) {
+            Err(error) => {
+                error!("{:?}", error);
 
-        println!();
-        build::print_license();
-        return 0;
+                1
+            }
+            _ => 0,
+        };
     }
 
     // Process args for future use
@@ -58,22 +62,21 @@ pub extern "C" fn start_engine(argc: c_int, argv: *const *const c_char) -> c_int
     debug!("Setting SIGINT hook...");
     ctrlc::set_handler(move || {
         debug!("SIGINT (Ctrl+C) Was Called! Stopping...");
-        utils::exit::set_exit();
+        utils::exit::request_exit(utils::exit::ExitReason::Interrupted);
 
         #[cfg(feature = "client")]
-        if !setup::get_args().server {
+        if !matches!(setup::get_args().command(), utils::args::Command::Serve(_)) {
             #[cfg(not(target_family = "wasm"))]
             let _ = client::game::advance_event_loop();
         }
     })
     .expect("Could not create Interrupt Handler (e.g. Ctrl+C)...");
 
-    match setup::start() {
-        Err(error) => {
-            error!("{:?}", error);
-
-            1
-        }
-        _ => 0,
+    if let Err(error) = setup::start() {
+        error!("{:?}", error);
+        utils::exit::request_exit(utils::exit::ExitReason::Error);
     }
+
+    let reason: utils::exit::ExitReason = utils::exit::shutdown(utils::exit::SHUTDOWN_TIMEOUT);
+    c_int::from(reason.code())
 }
@@ -13,7 +13,7 @@ fn wasm_start() -> Result<(), JsValue> {
 
     // Setup logger for debugging
     #[cfg(feature = "logging-subscriber")]
-    setup::setup_logger();
+    crate::logging::setup_logger();
 
     // Transfers embedded resources into utility crate
     utils::resources::store_embedded_resources(crate::resources::get_embedded_resources());
@@ -0,0 +1,205 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand, ValueEnum};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Commands the engine can run
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Subcommand, Debug, Clone, PartialEq, PartialOrd, Hash, Eq, Ord)]
+pub enum Command {
+    /// Start the game, connecting to a server or starting a single player listen server
+    Run(RunArgs),
+
+    /// Start the engine in dedicated server mode
+    Serve(ServeArgs),
+
+    /// Install the desktop files for launching from the application menu (Linux only)
+    InstallDesktop,
+
+    /// Uninstall the previously installed desktop files (Linux only)
+    UninstallDesktop,
+
+    /// Pack a mod directory into a zip archive which can be installed in the mods directory
+    Pack(PackArgs),
+
+    /// Work with the mods installed in the resources directory
+    Mods {
+        /// Mod command to run
+        #[command(subcommand)]
+        command: ModsCommand,
+    },
+
+    /// Display version and copyright info
+    Version(VersionArgs),
+
+    /// Export a software bill of materials listing every dependency and its license
+    Sbom(SbomArgs),
+
+    /// Write the notices of the bundled third party software, grouped by license with the license texts
+    Notices(NoticesArgs),
+
+    /// Show the report of the last time the engine crashed
+    LastCrash,
+
+    /// Work with the translations of every locale
+    I18n {
+        /// Translation command to run
+        #[command(subcommand)]
+        command: I18nCommand,
+    },
+}
+
+impl Command {
+    /// Whether the command starts the engine rather than running and exiting
+    #[must_use]
+    pub fn starts_engine(&self) -> bool {
+        matches!(self, Self::Run(_) | Self::Serve(_))
+    }
+}
+
+/// Options for starting the game
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Args, Debug, Clone, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub struct RunArgs {
+    /// Connect to a remote server instead of starting a single player listen server
+    #[arg(short, long)]
+    pub connect: Option<String>,
+
+    /// Width of the window in logical pixels, 1280 if not set
+    #[arg(long, env = "CATGIRL_ENGINE_WIDTH", value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: Option<u32>,
+
+    /// Height of the window in logical pixels, 720 if not set
+    #[arg(long, env = "CATGIRL_ENGINE_HEIGHT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: Option<u32>,
+
+    /// How frames are synchronized with the display, on if not set
+    #[arg(long, value_enum, env = "CATGIRL_ENGINE_VSYNC")]
+    pub vsync: Option<Vsync>,
+}
+
+/// Options for starting a dedicated server
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Args, Debug, Clone, PartialEq, PartialOrd, Hash, Eq, Ord)]
+pub struct ServeArgs {
+    /// Port the server listens on, 7777 if not set
+    #[arg(long, env = "CATGIRL_ENGINE_PORT", value_parser = clap::value_parser!(u16).range(1..))]
+    pub port: Option<u16>,
+
+    /// Number of ticks per second the server simulates
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+    pub tick_rate: u32,
+}
+
+/// Options for packing a mod
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Args, Debug, Clone, PartialEq, PartialOrd, Hash, Eq, Ord)]
+pub struct PackArgs {
+    /// Directory of the mod, with its manifest at the root
+    pub directory: PathBuf,
+
+    /// Archive to write, `<id>-<version>.zip` next to the directory if not set
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Commands for working with mods
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Subcommand, Debug, Clone, PartialEq, PartialOrd, Hash, Eq, Ord)]
+pub enum ModsCommand {
+    /// List the installed mods in load order, followed by the mods which cannot be loaded
+    List {
+        /// Format of the list
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+}
+
+/// Options for displaying the version
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Args, Debug, Clone, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub struct VersionArgs {
+    /// Format of the version, build info and dependencies
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// Options for exporting a software bill of materials
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Args, Debug, Clone, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub struct SbomArgs {
+    /// Standard the bill of materials follows
+    #[arg(long, value_enum, default_value_t = SbomFormat::Spdx)]
+    pub format: SbomFormat,
+
+    /// File to write the bill of materials to, printed if not set
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Options for writing the third party notices
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Args, Debug, Clone, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub struct NoticesArgs {
+    /// File to write the notices to, printed if not set
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Commands for working with translations
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Subcommand, Debug, Clone, PartialEq, PartialOrd, Hash, Eq, Ord)]
+pub enum I18nCommand {
+    /// Check every locale against English, exiting with an error if any problem is found
+    Check {
+        /// Format of the report
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Source directories to search for translation keys, unused keys are only reported if given
+        #[arg(long)]
+        sources: Vec<PathBuf>,
+    },
+}
+
+/// Format of a command's output or the logs
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+
+    /// Machine readable JSON
+    Json,
+}
+
+/// How frames are synchronized with the display's refresh rate
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub enum Vsync {
+    /// Wait for the display to refresh, so frames never tear
+    #[default]
+    On,
+
+    /// Show frames as soon as they are drawn, which may tear
+    Off,
+
+    /// Replace the waiting frame with the newest one, so frames never tear without waiting to draw
+    Mailbox,
+}
+
+/// Standard a software bill of materials follows
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub enum SbomFormat {
+    /// SPDX 2.3 as JSON
+    #[default]
+    Spdx,
+
+    /// `CycloneDX` 1.5 as JSON
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+}
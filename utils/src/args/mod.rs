@@ -2,30 +2,120 @@
 // #![cfg(not(target_os = "android"))]
 // #![cfg(not(target_os = "ios"))]
 
-/// Handles generic version of Args
-mod generic;
-
-/// Handles Linux version of Args
-mod linux;
-
 /// Handles parsing from C ABI
 pub mod c_abi;
 
-use std::sync::OnceLock;
+/// Handles the commands and their options
+pub mod commands;
+
+use std::{path::PathBuf, sync::OnceLock};
 
-#[cfg(not(target_os = "linux"))]
-pub use generic::Args;
+use clap::{Args as ClapArgs, Parser};
 
-#[cfg(target_os = "linux")]
-pub use linux::Args;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub use commands::{
+    Command, I18nCommand, ModsCommand, NoticesArgs, OutputFormat, PackArgs, RunArgs, SbomArgs,
+    SbomFormat, ServeArgs, VersionArgs, Vsync,
+};
 
 /// Reference to command line args specified by function
 static ARGS: OnceLock<Args> = OnceLock::new();
 
+/// Unparsed command line args specified by function, as the process's own args belong to the host
+static RAW_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Parser, Debug, Clone, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+#[command(
+    author,
+    about="A game engine for cool moddability and procedurally generated data",
+    long_about = None,
+    args_conflicts_with_subcommands = true
+)]
+/// List of possible command line arguments
+pub struct Args {
+    /// What to do, starts the game if not set
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options of the run command, for starting the game without naming it
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Options shared by every command
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    /// Display version and copyright info, the same as the version command
+    #[arg(short, long, default_value_t = false)]
+    pub version: bool,
+
+    /// Format of the version info, the same as the version command's format
+    #[arg(long, value_enum, requires = "version")]
+    pub format: Option<OutputFormat>,
+
+    /// Shows the AppImage help arguments
+    #[allow(clippy::doc_markdown)]
+    #[cfg(all(feature = "appimage", target_os = "linux"))]
+    #[arg(long, default_value_t = false)]
+    appimage_help: bool,
+}
+
+impl Args {
+    /// Command to run, which is the run command with the top level options if none was named
+    #[must_use]
+    pub fn command(&self) -> Command {
+        match &self.command {
+            Some(command) => command.clone(),
+            None if self.version => Command::Version(VersionArgs {
+                format: self.format.unwrap_or_default(),
+            }),
+            None => Command::Run(self.run.clone()),
+        }
+    }
+}
+
+/// Options every command accepts, before or after its name
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(ClapArgs, Debug, Clone, PartialEq, PartialOrd, Default, Hash, Eq, Ord)]
+pub struct GlobalArgs {
+    /// Set custom resources path, the resources directory if not set
+    #[arg(short, long, global = true, env = "CATGIRL_ENGINE_RESOURCES")]
+    pub resources: Option<PathBuf>,
+
+    /// Language to translate into, detected from the system if not set (e.g. pt-BR)
+    #[arg(short, long, global = true, env = "CATGIRL_ENGINE_LOCALE")]
+    pub locale: Option<String>,
+
+    /// Which logs are shown (e.g. `info,catgirl_engine=debug`)
+    #[arg(long, global = true, env = "RUST_LOG")]
+    pub log_filter: Option<String>,
+
+    /// Format the logs are written in, text if not set
+    #[arg(long, global = true, value_enum, env = "CATGIRL_ENGINE_LOG_FORMAT")]
+    pub log_format: Option<OutputFormat>,
+
+    /// Also write the logs to files in this directory, rotated daily
+    #[arg(long, global = true, env = "CATGIRL_ENGINE_LOG_DIRECTORY")]
+    pub log_directory: Option<PathBuf>,
+
+    /// Log how long each span took when it closes
+    #[arg(long, global = true, default_value_t = false)]
+    pub log_span_timings: bool,
+
+    /// Read and write settings in this config file instead of the platform config directory
+    #[arg(long, global = true, env = "CATGIRL_ENGINE_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Print all environment variables
+    #[arg(long, global = true, default_value_t = false)]
+    pub print_environment_variables: bool,
+}
+
 /// Set parsed args passed in from function
 pub fn set_parsed_args(args: Vec<String>) {
-    use clap::Parser;
-
     // If we already set the args, don't save again
     // It's a OnceLock, we can only set it once anyway
     if ARGS.get().is_some() {
@@ -33,9 +123,65 @@ pub fn set_parsed_args(args: Vec<String>) {
     }
 
     let _ = ARGS.set(Args::parse_from(args.iter()));
+    let _ = RAW_ARGS.set(args);
 }
 
 /// Retrieve parsed args previously passed in from function
 pub fn get_args() -> Option<Args> {
     ARGS.get().cloned()
 }
+
+/// Retrieve the unparsed args previously passed in from function
+pub fn get_raw_args() -> Option<Vec<String>> {
+    RAW_ARGS.get().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Args, Command, ModsCommand, OutputFormat, SbomFormat, VersionArgs};
+
+    #[test]
+    fn test_commands() {
+        let args: Args = Args::parse_from(["engine", "--connect", "localhost:7777"]);
+        let Command::Run(run) = args.command() else {
+            panic!("Expected the run command");
+        };
+        assert_eq!(run.connect.as_deref(), Some("localhost:7777"));
+
+        let args: Args = Args::parse_from(["engine", "serve", "--port", "8000", "-l", "pt-BR"]);
+        let Command::Serve(serve) = args.command() else {
+            panic!("Expected the serve command");
+        };
+        assert_eq!(serve.port, Some(8000));
+        assert_eq!(args.global.locale.as_deref(), Some("pt-BR"));
+
+        let args: Args = Args::parse_from(["engine", "mods", "list", "--format", "json"]);
+        assert_eq!(
+            args.command(),
+            Command::Mods {
+                command: ModsCommand::List {
+                    format: OutputFormat::Json
+                }
+            }
+        );
+
+        assert_eq!(
+            Args::parse_from(["engine", "--version", "--format", "json"]).command(),
+            Command::Version(VersionArgs {
+                format: OutputFormat::Json
+            })
+        );
+        assert!(Args::try_parse_from(["engine", "--format", "json"]).is_err());
+
+        let Command::Sbom(sbom) =
+            Args::parse_from(["engine", "sbom", "--format", "cyclonedx"]).command()
+        else {
+            panic!("Expected the sbom command");
+        };
+        assert_eq!(sbom.format, SbomFormat::CycloneDx);
+        assert!(Args::try_parse_from(["engine", "--connect", "localhost", "serve"]).is_err());
+        assert!(Args::try_parse_from(["engine", "serve", "--port", "0"]).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Directory inside the engine's data directory holding the crash reports
+const CRASH_DIRECTORY: &str = "crashes";
+
+/// File naming the report of a crash which wasn't offered to the player yet
+const UNSEEN_REPORT: &str = "unseen";
+
+/// Number of log lines kept for crash reports
+const RECENT_LOG_LINES: usize = 200;
+
+/// The most recent log lines, oldest first
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// What the engine was doing when it crashed
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CrashReport {
+    /// Seconds since the Unix epoch when the engine crashed
+    pub timestamp: u64,
+
+    /// Message of the panic
+    pub message: String,
+
+    /// File, line and column the panic came from, if known
+    pub location: Option<String>,
+
+    /// Name of the thread which panicked, if it has one
+    pub thread: Option<String>,
+
+    /// Name, version and compiler of the engine build
+    pub build: String,
+
+    /// Architecture, operating system and profile the engine was built for
+    pub target: String,
+
+    /// Commit the engine was built from, if known
+    pub commit: Option<String>,
+
+    /// Command line arguments with the values of likely secrets masked
+    pub args: Vec<String>,
+
+    /// Stack of the thread which panicked
+    pub backtrace: String,
+
+    /// Log lines written before the crash, oldest first
+    pub recent_logs: Vec<String>,
+}
+
+impl CrashReport {
+    /// Captures a report of a panic on the current thread
+    ///
+    /// `build` and `target` describe the engine binary, since this crate doesn't know which one it's part of
+    #[must_use]
+    pub fn capture(
+        message: String,
+        location: Option<String>,
+        build: String,
+        target: String,
+    ) -> Self {
+        let commit: Option<String> = crate::build::get_version_control_build_info().map(|git| {
+            if git.dirty {
+                format!("{}-dirty", git.commit_id)
+            } else {
+                git.commit_id
+            }
+        });
+
+        // When started through the C ABI, the process's args are the host's (e.g. the Python interpreter)
+        let args: Vec<String> = crate::args::get_raw_args().unwrap_or_else(|| {
+            std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        });
+
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            message,
+            location,
+            thread: std::thread::current().name().map(ToString::to_string),
+            build,
+            target,
+            commit,
+            args: mask_args(&args),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            recent_logs: get_recent_log_lines(),
+        }
+    }
+}
+
+impl Display for CrashReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Crash Report")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Crashed {} seconds after the Unix epoch on thread {}",
+            self.timestamp,
+            self.thread.as_deref().unwrap_or("<unnamed>")
+        )?;
+        writeln!(f)?;
+
+        writeln!(f, "## Panic")?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.message)?;
+        if let Some(location) = &self.location {
+            writeln!(f)?;
+            writeln!(f, "At {location}")?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "## Build")?;
+        writeln!(f)?;
+        writeln!(f, "* {}", self.build)?;
+        writeln!(f, "* Built for {}", self.target)?;
+        writeln!(
+            f,
+            "* Built from commit {}",
+            self.commit.as_deref().unwrap_or("unknown")
+        )?;
+        writeln!(f, "* Arguments: {}", self.args.join(" "))?;
+        writeln!(f)?;
+
+        writeln!(f, "## Backtrace")?;
+        writeln!(f)?;
+        writeln!(f, "```")?;
+        writeln!(f, "{}", self.backtrace.trim_end())?;
+        writeln!(f, "```")?;
+        writeln!(f)?;
+
+        writeln!(f, "## Recent Logs")?;
+        writeln!(f)?;
+        writeln!(f, "```")?;
+        for line in &self.recent_logs {
+            writeln!(f, "{line}")?;
+        }
+        write!(f, "```")
+    }
+}
+
+/// Masks the values of arguments which are likely secrets, such as `--token=...` or `--password ...`
+#[must_use]
+pub fn mask_args(args: &[String]) -> Vec<String> {
+    let mut masked: Vec<String> = Vec::with_capacity(args.len());
+    let mut mask_next: bool = false;
+
+    for arg in args {
+        if mask_next {
+            mask_next = false;
+            masked.push(crate::string::mask_string(arg));
+            continue;
+        }
+
+        match arg.split_once('=') {
+            Some((key, value)) if crate::string::is_likely_secret(key) => {
+                masked.push(format!("{key}={}", crate::string::mask_string(value)));
+                continue;
+            }
+            None if arg.starts_with('-') && crate::string::is_likely_secret(arg) => {
+                mask_next = true;
+            }
+            _ => {}
+        }
+
+        masked.push(arg.clone());
+    }
+
+    masked
+}
+
+/// Keeps a log line for crash reports, forgetting the oldest once enough are kept
+pub fn record_log_line(line: String) {
+    let mut logs: MutexGuard<VecDeque<String>> =
+        RECENT_LOGS.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if logs.len() == RECENT_LOG_LINES {
+        logs.pop_front();
+    }
+
+    logs.push_back(line);
+}
+
+/// Retrieves the most recent log lines, oldest first
+#[must_use]
+pub fn get_recent_log_lines() -> Vec<String> {
+    RECENT_LOGS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Directory crash reports are written to, if the platform has a data directory
+#[must_use]
+pub fn crash_directory() -> Option<PathBuf> {
+    Some(crate::settings::file::engine_data_directory()?.join(CRASH_DIRECTORY))
+}
+
+/// Writes a crash report to a directory, marking it to be offered on the next launch
+///
+/// Returns the path of the report
+///
+/// # Errors
+///
+/// Errors if the directory or the report cannot be written
+pub fn write_report(directory: &Path, report: &CrashReport) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|error| format!("Could not create {}: {error}", directory.display()))?;
+
+    let name: String = format!("crash-{}.md", report.timestamp);
+    let path: PathBuf = directory.join(&name);
+    std::fs::write(&path, format!("{report}\n"))
+        .map_err(|error| format!("Could not write {}: {error}", path.display()))?;
+
+    let unseen: PathBuf = directory.join(UNSEEN_REPORT);
+    std::fs::write(&unseen, name)
+        .map_err(|error| format!("Could not write {}: {error}", unseen.display()))?;
+
+    Ok(path)
+}
+
+/// Retrieves the report of a crash which wasn't offered to the player yet, so it's only offered once
+#[must_use]
+pub fn take_unseen_report(directory: &Path) -> Option<PathBuf> {
+    let unseen: PathBuf = directory.join(UNSEEN_REPORT);
+    let name: String = std::fs::read_to_string(&unseen).ok()?;
+
+    if let Err(error) = std::fs::remove_file(&unseen) {
+        warn!("Could not remove {}: {error}", unseen.display());
+    }
+
+    Some(directory.join(name.trim())).filter(|path| path.is_file())
+}
+
+/// Finds the most recent crash report in a directory
+#[must_use]
+pub fn latest_report(directory: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(directory)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name: String = entry.file_name().to_string_lossy().into_owned();
+            let timestamp: u64 = name
+                .strip_prefix("crash-")?
+                .strip_suffix(".md")?
+                .parse()
+                .ok()?;
+
+            Some((timestamp, entry.path()))
+        })
+        .max_by_key(|(timestamp, _)| *timestamp)
+        .map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::CrashReport;
+
+    #[test]
+    fn test_crash_report() {
+        let args: Vec<String> = [
+            "engine",
+            "--api-token=hunter2",
+            "--password",
+            "hunter2",
+            "run",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        assert_eq!(
+            super::mask_args(&args),
+            vec![
+                "engine",
+                "--api-token=*******",
+                "--password",
+                "*******",
+                "run"
+            ]
+        );
+
+        let report: CrashReport = CrashReport {
+            timestamp: 1_708_414_840,
+            message: "Index out of bounds".to_string(),
+            location: Some("src/main.rs:1:1".to_string()),
+            recent_logs: vec!["INFO Starting Game...".to_string()],
+            ..CrashReport::default()
+        };
+
+        let directory: PathBuf =
+            std::env::temp_dir().join(format!("catgirl-engine-crash-{}", std::process::id()));
+        let path: PathBuf = super::write_report(&directory, &report).unwrap();
+        assert_eq!(super::latest_report(&directory), Some(path.clone()));
+
+        // The report is only offered once
+        assert_eq!(super::take_unseen_report(&directory), Some(path.clone()));
+        assert_eq!(super::take_unseen_report(&directory), None);
+
+        let contents: String = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("## Panic\n\nIndex out of bounds\n\nAt src/main.rs:1:1"));
+        assert!(contents.ends_with("INFO Starting Game...\n```\n"));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        // Args passed in from function are reported instead of the host process's
+        crate::args::set_parsed_args(vec!["engine".to_string(), "run".to_string()]);
+        let report: CrashReport = CrashReport::capture(
+            "Index out of bounds".to_string(),
+            None,
+            "debug".to_string(),
+            "x86_64-unknown-linux-gnu".to_string(),
+        );
+        assert_eq!(report.args, vec!["engine", "run"]);
+    }
+}
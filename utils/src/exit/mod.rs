@@ -1,20 +1,370 @@
-use std::sync::OnceLock;
+use std::{
+    fmt::{Display, Formatter},
+    panic::AssertUnwindSafe,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
 
-/// To help check if [`set_exit()`] was already called
-static EXITING: OnceLock<bool> = OnceLock::new();
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-/// Tells the game engine to start exiting next time it checks the exit status
-pub fn set_exit() {
-    if EXITING.get().is_some() {
-        return;
+/// How long the shutdown hooks may run before the engine exits without them
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The pending exit and the hooks to run when shutting down
+static EXIT_STATE: Mutex<ExitState> = Mutex::new(ExitState {
+    reason: None,
+    confirmed: false,
+    hooks: Vec::new(),
+});
+
+/// A function cleaning up part of the engine, failing with a message
+type Hook = Box<dyn FnOnce() -> Result<(), String> + Send>;
+
+/// Why the engine is exiting
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    /// The player or a command asked to quit
+    Requested,
+
+    /// The window was closed
+    WindowClosed,
+
+    /// The process was interrupted (e.g. Ctrl+C)
+    Interrupted,
+
+    /// The engine stopped because of an error
+    Error,
+
+    /// A thread panicked
+    Panicked,
+}
+
+impl ExitReason {
+    /// Process exit code reported for the reason
+    #[must_use]
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::Requested | Self::WindowClosed => 0,
+            Self::Error => 1,
+            // Matches the code Rust exits with after a panic and shells use after SIGINT
+            Self::Panicked => 101,
+            Self::Interrupted => 130,
+        }
+    }
+
+    /// Whether the reason is a failure rather than a normal exit
+    #[must_use]
+    pub const fn is_failure(self) -> bool {
+        self.code() != 0
+    }
+}
+
+impl Display for ExitReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason: &str = match self {
+            Self::Requested => "exit was requested",
+            Self::WindowClosed => "window was closed",
+            Self::Interrupted => "process was interrupted",
+            Self::Error => "an error occurred",
+            Self::Panicked => "a thread panicked",
+        };
+
+        write!(f, "{reason}")
+    }
+}
+
+/// Steps of shutting down, run in the order they are declared
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownPhase {
+    /// Stop accepting input from the player and the network
+    StopInput,
+
+    /// Save anything which has to outlive the process, such as the world
+    SaveWorld,
+
+    /// Tell connected clients the server is going away
+    DisconnectClients,
+
+    /// Write out any buffered logs
+    FlushLogs,
+}
+
+impl Display for ShutdownPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let phase: &str = match self {
+            Self::StopInput => "stop input",
+            Self::SaveWorld => "save world",
+            Self::DisconnectClients => "disconnect clients",
+            Self::FlushLogs => "flush logs",
+        };
+
+        write!(f, "{phase}")
+    }
+}
+
+/// State of the engine's exit
+struct ExitState {
+    /// Why the engine is exiting, if an exit is pending
+    reason: Option<ExitReason>,
+
+    /// Whether the exit was confirmed, after which the loops stop and it can't be cancelled
+    confirmed: bool,
+
+    /// Hooks to run when shutting down along with their phase and name
+    hooks: Vec<(ShutdownPhase, String, Hook)>,
+}
+
+/// Locks the exit state, even if a panicking thread poisoned it since the panic hook requests an exit
+fn exit_state() -> MutexGuard<'static, ExitState> {
+    EXIT_STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Asks the game engine to exit, leaving the exit pending until it is confirmed or cancelled
+///
+/// Failures can't wait on the player, so they confirm the exit straight away.
+/// The first reason is kept, unless a failure comes after a normal exit so the failure is reported
+pub fn request_exit(reason: ExitReason) {
+    let mut state: MutexGuard<ExitState> = exit_state();
+
+    match state.reason {
+        Some(pending) if !reason.is_failure() || pending.is_failure() => return,
+        _ => state.reason = Some(reason),
+    }
+
+    if reason.is_failure() {
+        state.confirmed = true;
+        trace!("Engine is exiting, {reason}...");
+    } else {
+        trace!("Engine was asked to exit, {reason}...");
+    }
+}
+
+/// Confirms a pending exit (e.g. the player answered yes to "are you sure?"), stopping the loops
+///
+/// Returns false if there was no exit to confirm
+pub fn confirm_exit() -> bool {
+    let mut state: MutexGuard<ExitState> = exit_state();
+
+    let Some(reason) = state.reason else {
+        return false;
+    };
+
+    if !state.confirmed {
+        trace!("Engine is exiting, {reason}...");
+        state.confirmed = true;
+    }
+
+    true
+}
+
+/// Cancels a pending exit (e.g. the player answered no to "are you sure?")
+///
+/// Returns false if there was no exit to cancel or it was already confirmed
+pub fn cancel_exit() -> bool {
+    let mut state: MutexGuard<ExitState> = exit_state();
+
+    if state.confirmed || state.reason.is_none() {
+        return false;
     }
 
-    let _ = EXITING.set(true);
+    debug!("Exit was cancelled...");
+    state.reason = None;
+    true
+}
+
+/// Retrieves if an exit was requested but not yet confirmed or cancelled
+#[must_use]
+pub fn is_exit_pending() -> bool {
+    let state: MutexGuard<ExitState> = exit_state();
 
-    trace!("Engine is exiting...");
+    state.reason.is_some() && !state.confirmed
 }
 
-/// Retrieves if the game engine is exiting
+/// Retrieves if the game engine is exiting, which loops check to know when to stop
+#[must_use]
 pub fn is_exiting() -> bool {
-    *EXITING.get().unwrap_or(&false)
+    exit_state().confirmed
+}
+
+/// Retrieves why the game engine is exiting, if it is
+#[must_use]
+pub fn get_exit_reason() -> Option<ExitReason> {
+    exit_state().reason
+}
+
+/// Registers a hook to run during a phase of shutting down
+///
+/// Hooks of the same phase run in the order they were registered
+pub fn register_hook(
+    phase: ShutdownPhase,
+    name: &str,
+    hook: impl FnOnce() -> Result<(), String> + Send + 'static,
+) {
+    trace!("Registering {phase} shutdown hook {name}...");
+    exit_state()
+        .hooks
+        .push((phase, name.to_string(), Box::new(hook)));
+}
+
+/// Shuts down the engine by running the registered hooks phase by phase
+///
+/// If the hooks take longer than `timeout`, the rest are abandoned so the engine can exit anyway.
+/// Returns why the engine exited, which is [`ExitReason::Requested`] if no exit was pending.
+pub fn shutdown(timeout: Duration) -> ExitReason {
+    let hooks: Vec<(ShutdownPhase, String, Hook)> = {
+        let mut state: MutexGuard<ExitState> = exit_state();
+        state.reason.get_or_insert(ExitReason::Requested);
+        state.confirmed = true;
+
+        let mut hooks: Vec<(ShutdownPhase, String, Hook)> = std::mem::take(&mut state.hooks);
+        // Stable, so hooks of a phase keep their order
+        hooks.sort_by_key(|(phase, _, _)| *phase);
+        hooks
+    };
+
+    info!("Shutting down...");
+    run_hooks(hooks, timeout);
+
+    let reason: ExitReason = get_exit_reason().unwrap_or(ExitReason::Requested);
+    info!("Engine exited, {reason}...");
+
+    reason
+}
+
+/// Runs the hooks on their own thread, giving up on them after `timeout`
+#[cfg(not(target_family = "wasm"))]
+fn run_hooks(hooks: Vec<(ShutdownPhase, String, Hook)>, timeout: Duration) {
+    let (sender, receiver) = std::sync::mpsc::channel::<()>();
+
+    let spawned: std::io::Result<std::thread::JoinHandle<()>> = std::thread::Builder::new()
+        .name("shutdown".to_string())
+        .spawn(move || {
+            run_hooks_in_order(hooks);
+            let _ = sender.send(());
+        });
+
+    if let Err(error) = spawned {
+        error!("Could not start the shutdown thread, skipping the shutdown hooks: {error}");
+        return;
+    }
+
+    if receiver.recv_timeout(timeout).is_err() {
+        error!("Shutdown hooks did not finish within {timeout:?}, forcing the exit...");
+    }
+}
+
+/// Runs the hooks, as threads are not available on Wasm to time them out
+#[cfg(target_family = "wasm")]
+fn run_hooks(hooks: Vec<(ShutdownPhase, String, Hook)>, _timeout: Duration) {
+    run_hooks_in_order(hooks);
+}
+
+/// Runs every hook, logging the ones which fail so the rest still get to run
+fn run_hooks_in_order(hooks: Vec<(ShutdownPhase, String, Hook)>) {
+    for (phase, name, hook) in hooks {
+        debug!("Running {phase} shutdown hook {name}...");
+
+        match std::panic::catch_unwind(AssertUnwindSafe(hook)) {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => error!("Shutdown hook {name} failed: {error}"),
+            Err(_) => error!("Shutdown hook {name} panicked..."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc, Mutex,
+        },
+        thread::JoinHandle,
+        time::Duration,
+    };
+
+    use super::{ExitReason, ShutdownPhase};
+
+    #[test]
+    fn test_shutdown() {
+        assert!(!super::cancel_exit());
+        assert!(!super::confirm_exit());
+
+        // A loop polling for the exit like the game loops do
+        let ticks: Arc<AtomicU32> = Arc::new(AtomicU32::new(0));
+        let polling: JoinHandle<()> = {
+            let ticks: Arc<AtomicU32> = ticks.clone();
+            std::thread::spawn(move || {
+                while !super::is_exiting() {
+                    ticks.fetch_add(1, Ordering::Relaxed);
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            })
+        };
+
+        // A pending exit doesn't stop the loop, so cancelling it keeps the loop running
+        super::request_exit(ExitReason::WindowClosed);
+        assert!(super::is_exit_pending());
+        assert!(!super::is_exiting());
+        assert!(super::cancel_exit());
+        assert!(!super::is_exit_pending());
+
+        let before: u32 = ticks.load(Ordering::Relaxed);
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!polling.is_finished());
+        assert!(ticks.load(Ordering::Relaxed) > before);
+
+        // Confirming stops the loop and can't be cancelled
+        super::request_exit(ExitReason::Requested);
+        assert!(super::confirm_exit());
+        assert!(super::is_exiting());
+        assert!(!super::cancel_exit());
+        polling.join().unwrap();
+
+        // A failure replaces a normal exit, but not the other way around
+        super::request_exit(ExitReason::Requested);
+        super::request_exit(ExitReason::Interrupted);
+        super::request_exit(ExitReason::WindowClosed);
+        assert_eq!(super::get_exit_reason(), Some(ExitReason::Interrupted));
+
+        let order: Arc<Mutex<Vec<&str>>> = Arc::new(Mutex::new(Vec::new()));
+        for (phase, name) in [
+            (ShutdownPhase::FlushLogs, "logs"),
+            (ShutdownPhase::StopInput, "input"),
+            (ShutdownPhase::DisconnectClients, "clients"),
+            (ShutdownPhase::SaveWorld, "world"),
+            (ShutdownPhase::SaveWorld, "failing"),
+        ] {
+            let order: Arc<Mutex<Vec<&str>>> = order.clone();
+            super::register_hook(phase, name, move || {
+                order.lock().unwrap().push(name);
+                if name == "failing" {
+                    return Err("Disk is full".to_string());
+                }
+
+                // The last hook outlives the timeout
+                if name == "logs" {
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+
+                Ok(())
+            });
+        }
+
+        assert_eq!(
+            super::shutdown(Duration::from_millis(500)),
+            ExitReason::Interrupted
+        );
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["input", "world", "failing", "clients", "logs"]
+        );
+        assert_eq!(ExitReason::Interrupted.code(), 130);
+
+        // The engine is already shutting down
+        assert!(!super::cancel_exit());
+    }
 }
@@ -0,0 +1,343 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Message, TranslationDiagnostic, DEFAULT_LOCALE, I18N};
+
+/// Extensions of the source files searched for translation keys
+const SOURCE_EXTENSIONS: [&str; 2] = ["rs", "rhai"];
+
+/// Translation whose placeholders differ from the default locale's
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceholderMismatch {
+    /// Translation key
+    pub key: String,
+
+    /// Arguments used by the default locale
+    pub expected: Vec<String>,
+
+    /// Arguments used by this locale
+    pub found: Vec<String>,
+}
+
+/// Translation which isn't valid `MessageFormat` syntax
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMessage {
+    /// Translation key
+    pub key: String,
+
+    /// Why the message could not be parsed
+    pub error: String,
+}
+
+/// Coverage of a single locale compared to the default locale
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleCoverage {
+    /// Locale checked
+    pub locale: String,
+
+    /// Number of keys the locale translates
+    pub translated: usize,
+
+    /// Keys of the default locale which neither this locale nor its fallbacks translate
+    pub missing: Vec<String>,
+
+    /// Keys this locale translates which the default locale doesn't
+    pub extra: Vec<String>,
+
+    /// Translations whose placeholders differ from the default locale's
+    pub placeholders: Vec<PlaceholderMismatch>,
+
+    /// Translations which cannot be parsed
+    pub invalid: Vec<InvalidMessage>,
+}
+
+impl LocaleCoverage {
+    /// Whether no problems were found in the locale
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.placeholders.is_empty()
+            && self.invalid.is_empty()
+    }
+}
+
+/// Coverage of every locale compared to the default locale
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Locale every other locale is compared to
+    pub reference: String,
+
+    /// Coverage of each locale, sorted by locale
+    pub locales: Vec<LocaleCoverage>,
+
+    /// Keys of the default locale which no source file uses, if source directories were searched
+    pub unused: Option<Vec<String>>,
+
+    /// Problems found while loading the translations
+    pub diagnostics: Vec<TranslationDiagnostic>,
+}
+
+impl CoverageReport {
+    /// Compares every locale to the default locale
+    ///
+    /// Unused keys are only reported if source directories are given,
+    /// keys built at runtime (e.g. with `format!`) cannot be found and are reported as unused
+    ///
+    /// # Errors
+    ///
+    /// Errors if a source directory cannot be read
+    pub fn new(i18n: &I18N, sources: &[PathBuf]) -> Result<Self, String> {
+        let mut locales: Vec<&str> = i18n.available_locales();
+        locales.sort_unstable();
+
+        let locales: Vec<LocaleCoverage> = locales
+            .into_iter()
+            .map(|locale| check_locale(i18n, locale))
+            .collect();
+
+        let unused: Option<Vec<String>> = if sources.is_empty() {
+            None
+        } else {
+            Some(find_unused_keys(&i18n.keys(DEFAULT_LOCALE), sources)?)
+        };
+
+        let diagnostics: Vec<TranslationDiagnostic> = i18n
+            .diagnostics()
+            .into_iter()
+            .filter(|diagnostic| !matches!(diagnostic, TranslationDiagnostic::Missing { .. }))
+            .collect();
+
+        Ok(Self {
+            reference: DEFAULT_LOCALE.to_string(),
+            locales,
+            unused,
+            diagnostics,
+        })
+    }
+
+    /// Whether no problems were found in any locale
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.locales.iter().all(LocaleCoverage::passed)
+            && self.unused.as_ref().is_none_or(Vec::is_empty)
+            && self.diagnostics.is_empty()
+    }
+}
+
+impl Display for CoverageReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for locale in &self.locales {
+            let status: &str = if locale.passed() { "ok" } else { "failed" };
+            writeln!(
+                f,
+                "{}: {} keys translated, {status}",
+                locale.locale, locale.translated
+            )?;
+
+            for key in &locale.missing {
+                writeln!(f, "  missing: {key}")?;
+            }
+            for key in &locale.extra {
+                writeln!(f, "  extra: {key}")?;
+            }
+            for mismatch in &locale.placeholders {
+                writeln!(
+                    f,
+                    "  placeholders: {} uses {{{}}} instead of {{{}}}",
+                    mismatch.key,
+                    mismatch.found.join("}, {"),
+                    mismatch.expected.join("}, {")
+                )?;
+            }
+            for invalid in &locale.invalid {
+                writeln!(f, "  invalid: {}: {}", invalid.key, invalid.error)?;
+            }
+        }
+
+        if let Some(unused) = &self.unused {
+            for key in unused {
+                writeln!(f, "unused: {key}")?;
+            }
+        }
+
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{diagnostic}")?;
+        }
+
+        let result: &str = if self.passed() { "passed" } else { "failed" };
+        write!(f, "Translation check {result}")
+    }
+}
+
+/// Compares a locale to the keys of the default locale
+fn check_locale(i18n: &I18N, locale: &str) -> LocaleCoverage {
+    let keys: Vec<&str> = i18n.keys(locale);
+    let is_reference: bool = locale == DEFAULT_LOCALE;
+
+    let mut coverage: LocaleCoverage = LocaleCoverage {
+        locale: locale.to_string(),
+        translated: keys.len(),
+        missing: Vec::new(),
+        extra: Vec::new(),
+        placeholders: Vec::new(),
+        invalid: Vec::new(),
+    };
+
+    if !is_reference {
+        coverage.missing = i18n
+            .missing_keys(locale)
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+    }
+
+    for key in keys {
+        let Some(translation) = i18n.translation(locale, key) else {
+            continue;
+        };
+
+        let message: Message = match Message::parse(&translation.text) {
+            Ok(message) => message,
+            Err(error) => {
+                coverage.invalid.push(InvalidMessage {
+                    key: key.to_string(),
+                    error,
+                });
+                continue;
+            }
+        };
+
+        if is_reference {
+            continue;
+        }
+
+        let Some(expected) = i18n.translation(DEFAULT_LOCALE, key) else {
+            coverage.extra.push(key.to_string());
+            continue;
+        };
+
+        // Invalid reference messages are reported by the reference locale itself
+        if let Ok(expected) = Message::parse(&expected.text) {
+            let expected: Vec<&str> = expected.arguments();
+            let found: Vec<&str> = message.arguments();
+
+            if expected != found {
+                coverage.placeholders.push(PlaceholderMismatch {
+                    key: key.to_string(),
+                    expected: expected.into_iter().map(ToString::to_string).collect(),
+                    found: found.into_iter().map(ToString::to_string).collect(),
+                });
+            }
+        }
+    }
+
+    coverage
+}
+
+/// Finds the keys which don't appear as a quoted string in any source file
+///
+/// # Errors
+///
+/// Errors if a source directory cannot be read
+fn find_unused_keys(keys: &[&str], sources: &[PathBuf]) -> Result<Vec<String>, String> {
+    let mut unused: Vec<&str> = keys.to_vec();
+    let mut directories: VecDeque<PathBuf> = sources.iter().cloned().collect();
+
+    while let Some(directory) = directories.pop_front() {
+        let entries: std::fs::ReadDir = std::fs::read_dir(&directory)
+            .map_err(|error| format!("Could not read {}: {error}", directory.display()))?;
+
+        for entry in entries.flatten() {
+            let path: PathBuf = entry.path();
+
+            if path.is_dir() {
+                directories.push_back(path);
+                continue;
+            }
+
+            let is_source: bool = path.extension().is_some_and(|extension| {
+                SOURCE_EXTENSIONS.iter().any(|source| extension == *source)
+            });
+            if !is_source {
+                continue;
+            }
+
+            // Files which aren't UTF-8 can't contain the keys as strings
+            if let Ok(contents) = std::fs::read_to_string(&path) {
+                unused.retain(|key| !contents.contains(&format!("\"{key}\"")));
+            }
+        }
+    }
+
+    Ok(unused.into_iter().map(ToString::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use crate::i18n::{TranslationSource, I18N};
+
+    use super::{CoverageReport, LocaleCoverage};
+
+    #[test]
+    fn test_coverage() {
+        let mut i18n: I18N = I18N::new();
+        for (locale, translations) in [
+            (
+                "en",
+                vec![
+                    ("joined", "{player} joined"),
+                    ("left", "{player} left"),
+                    ("quit", "Quit"),
+                ],
+            ),
+            (
+                "pt",
+                vec![
+                    ("joined", "{jogador} entrou"),
+                    ("quit", "Sair"),
+                    ("extra", "Extra"),
+                    ("broken", "{"),
+                ],
+            ),
+        ] {
+            let translations: HashMap<String, String> = translations
+                .into_iter()
+                .map(|(key, text)| (key.to_string(), text.to_string()))
+                .collect();
+            i18n.add_translations(&TranslationSource::Vanilla, locale, translations);
+        }
+
+        let report: CoverageReport = CoverageReport::new(&i18n, &[]).unwrap();
+        assert!(!report.passed());
+        assert_eq!(report.unused, None);
+        assert!(report.locales[0].passed());
+
+        let portuguese: &LocaleCoverage = &report.locales[1];
+        assert_eq!(portuguese.missing, vec!["left"]);
+        assert_eq!(portuguese.extra, vec!["extra"]);
+        assert_eq!(portuguese.placeholders[0].key, "joined");
+        assert_eq!(portuguese.placeholders[0].found, vec!["jogador"]);
+        assert_eq!(portuguese.invalid[0].key, "broken");
+
+        // Every key appears quoted in this file
+        let sources: Vec<PathBuf> = vec![PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("i18n")
+            .join("check")];
+        let report: CoverageReport = CoverageReport::new(&i18n, &sources).unwrap();
+        assert_eq!(report.unused, Some(Vec::new()));
+    }
+}
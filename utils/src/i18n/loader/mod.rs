@@ -0,0 +1,154 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::RwLockReadGuard,
+};
+
+use crate::{
+    mods::Mod,
+    vfs::{DirEntry, EntryKind, Layer, Vfs},
+};
+
+use super::{locale, TranslationDiagnostic, TranslationSource, I18N};
+
+/// Directory inside a mod holding its `<locale>.toml` files
+const MOD_LOCALES_DIRECTORY: &str = "locales";
+
+/// Reads every layer of translations in order of precedence
+///
+/// * Locales embedded in the binary
+/// * Vanilla locales in the resources directory
+/// * Mod locales, in load order
+/// * User overrides
+pub(super) fn load_layers(i18n: &mut I18N) {
+    let vfs: RwLockReadGuard<'static, Vfs> = crate::vfs::get_vfs();
+
+    load_directory(
+        i18n,
+        &vfs,
+        &TranslationSource::Embedded,
+        Layer::Embedded,
+        Path::new(super::LOCALES_PATH),
+    );
+    load_directory(
+        i18n,
+        &vfs,
+        &TranslationSource::Vanilla,
+        Layer::User,
+        Path::new(super::LOCALES_PATH),
+    );
+
+    for loaded_mod in crate::mods::get_mods() {
+        load_mod(i18n, loaded_mod);
+    }
+
+    load_directory(
+        i18n,
+        &vfs,
+        &TranslationSource::User,
+        Layer::User,
+        Path::new(super::USER_LOCALES_PATH),
+    );
+}
+
+/// Reads the locale files in a directory of a single layer of the virtual filesystem
+pub(super) fn load_directory(
+    i18n: &mut I18N,
+    vfs: &Vfs,
+    source: &TranslationSource,
+    layer: Layer,
+    directory: &Path,
+) {
+    // Layers without the directory simply don't provide translations
+    let entries: Vec<DirEntry> = vfs.list_layer(layer, directory).unwrap_or_default();
+
+    for entry in entries {
+        if entry.kind != EntryKind::File {
+            continue;
+        }
+
+        let path: PathBuf = directory.join(&entry.name);
+        load_file(i18n, source, &path, vfs.read_layer(layer, &path));
+    }
+}
+
+/// Reads the locale files a mod provides
+fn load_mod(i18n: &mut I18N, loaded_mod: &Mod) {
+    let source: TranslationSource = TranslationSource::Mod(loaded_mod.id().to_string());
+
+    for path in &loaded_mod.files {
+        if path.parent() == Some(Path::new(MOD_LOCALES_DIRECTORY)) {
+            load_file(i18n, &source, path, loaded_mod.read(path));
+        }
+    }
+}
+
+/// Adds the translations in a `<locale>.toml` file, recording a diagnostic if it cannot be used
+fn load_file(
+    i18n: &mut I18N,
+    source: &TranslationSource,
+    path: &Path,
+    contents: Result<Cow<'static, [u8]>, String>,
+) {
+    if path.extension().is_none_or(|extension| extension != "toml") {
+        return;
+    }
+
+    let result: Result<(String, HashMap<String, String>), String> = path
+        .file_stem()
+        .and_then(|name| locale::normalize(&name.to_string_lossy()))
+        .ok_or_else(|| "File isn't named after a locale".to_string())
+        .and_then(|locale| {
+            let contents: String = String::from_utf8(contents?.into_owned())
+                .map_err(|_| "File is not valid UTF-8".to_string())?;
+
+            Ok((locale, parse_locale(&contents)?))
+        });
+
+    match result {
+        Ok((locale, translations)) => i18n.add_translations(source, &locale, translations),
+        Err(error) => i18n.diagnostics.push(TranslationDiagnostic::Invalid {
+            source: source.clone(),
+            path: path.to_path_buf(),
+            error,
+        }),
+    }
+}
+
+/// Parses the translations of a single locale
+///
+/// Keys in nested tables are joined with a `.` (e.g. `player_joined` in `[server]` becomes `server.player_joined`)
+///
+/// # Errors
+///
+/// Errors if the contents aren't valid TOML
+pub fn parse_locale(contents: &str) -> Result<HashMap<String, String>, String> {
+    let table: toml::Table = contents
+        .parse::<toml::Table>()
+        .map_err(|error| error.to_string())?;
+
+    let mut translations: HashMap<String, String> = HashMap::new();
+    flatten_table("", table, &mut translations);
+
+    Ok(translations)
+}
+
+/// Collects the strings in a table and the tables nested in it
+fn flatten_table(prefix: &str, table: toml::Table, translations: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key: String = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+
+        match value {
+            toml::Value::String(translation) => {
+                translations.insert(key, translation);
+            }
+            toml::Value::Table(table) => flatten_table(&key, table, translations),
+            _ => warn!("Skipping translation {key}, it isn't a string"),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+/// Locale every other locale falls back to, its translations are always embedded in the binary
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Environment variables describing the system locale, most specific first
+const LOCALE_VARIABLES: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Converts a locale to a BCP 47 style tag (e.g. `pt_BR.UTF-8` to `pt-BR`)
+///
+/// Returns `None` for locales which don't name a language, such as `C` and `POSIX`
+#[must_use]
+pub fn normalize(locale: &str) -> Option<String> {
+    // Drops the encoding and modifier of POSIX locales
+    let locale: &str = locale.split(['.', '@']).next().unwrap_or_default().trim();
+
+    let mut subtags = locale.split(['-', '_']);
+    let language: &str = subtags.next()?;
+    if !(2..=3).contains(&language.len())
+        || !language
+            .chars()
+            .all(|character| character.is_ascii_alphabetic())
+    {
+        return None;
+    }
+
+    let mut tag: String = language.to_ascii_lowercase();
+    for subtag in subtags {
+        if subtag.is_empty()
+            || !subtag
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric())
+        {
+            return None;
+        }
+
+        tag.push('-');
+        match subtag.len() {
+            // Regions are upper case (e.g. BR)
+            2 => tag.push_str(&subtag.to_ascii_uppercase()),
+            // Scripts are title case (e.g. Hant)
+            4 => {
+                tag.push_str(&subtag[..1].to_ascii_uppercase());
+                tag.push_str(&subtag[1..].to_ascii_lowercase());
+            }
+            _ => tag.push_str(&subtag.to_ascii_lowercase()),
+        }
+    }
+
+    Some(tag)
+}
+
+/// Locales to look for translations in, from the most specific to [`DEFAULT_LOCALE`]
+///
+/// `zh-Hant-TW` falls back to `zh-Hant`, then `zh` and then `en`
+#[must_use]
+pub fn fallback_chain(locale: &str) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+
+    if let Some(mut tag) = normalize(locale) {
+        loop {
+            chain.push(tag.clone());
+
+            match tag.rfind('-') {
+                Some(index) => tag.truncate(index),
+                None => break,
+            }
+        }
+    }
+
+    if !chain.iter().any(|tag| tag == DEFAULT_LOCALE) {
+        chain.push(DEFAULT_LOCALE.to_string());
+    }
+
+    chain
+}
+
+/// Detects the locale the system is set to from the POSIX locale environment variables
+#[must_use]
+pub fn system_locale() -> Option<String> {
+    LOCALE_VARIABLES
+        .iter()
+        .filter_map(crate::environment::get_environment_var)
+        .find_map(|locale| normalize(&locale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fallback_chain, normalize};
+
+    #[test]
+    fn test_fallback_chain() {
+        assert_eq!(normalize("pt_BR.UTF-8"), Some("pt-BR".to_string()));
+        assert_eq!(normalize("zh_hant_tw"), Some("zh-Hant-TW".to_string()));
+        assert_eq!(normalize("C"), None);
+        assert_eq!(normalize("POSIX"), None);
+
+        assert_eq!(fallback_chain("pt-BR"), vec!["pt-BR", "pt", "en"]);
+        assert_eq!(fallback_chain("en_US"), vec!["en-US", "en"]);
+        assert_eq!(fallback_chain("C"), vec!["en"]);
+    }
+}
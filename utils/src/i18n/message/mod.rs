@@ -0,0 +1,645 @@
+use std::{iter::Peekable, str::Chars, time::SystemTime};
+
+use super::{
+    number::{self, DateStyle, NumberStyle},
+    plural::{self, PluralCategory},
+};
+
+/// Value substituted into a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Argument {
+    /// Shown as is and matched by select messages
+    String(String),
+
+    /// Shown with the locale's separators and matched by plural messages
+    Number(f64),
+
+    /// Shown as a date or time in UTC
+    Time(SystemTime),
+}
+
+impl From<&str> for Argument {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Argument {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<SystemTime> for Argument {
+    fn from(value: SystemTime) -> Self {
+        Self::Time(value)
+    }
+}
+
+/// Implements `From` for number types which convert to `f64`
+macro_rules! impl_from_number {
+    ($($number:ty),*) => {
+        $(impl From<$number> for Argument {
+            #[allow(clippy::cast_precision_loss, clippy::cast_lossless)]
+            fn from(value: $number) -> Self {
+                Self::Number(value as f64)
+            }
+        })*
+    };
+}
+
+impl_from_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// Piece of a parsed message
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    /// Literal text
+    Text(String),
+
+    /// `{name}`
+    Argument(String),
+
+    /// `{name, number}` or `{name, number, integer|percent}`
+    Number(String, NumberStyle),
+
+    /// `{name, date}` or `{name, date, short|iso}`
+    Date(String, DateStyle),
+
+    /// `{name, time}` or `{name, time, short|iso}`
+    Time(String, DateStyle),
+
+    /// `{name, plural, offset:1 =0 {...} one {...} other {...}}`
+    Plural {
+        /// Argument the plural depends on
+        name: String,
+
+        /// Subtracted from the number before picking a category and showing it as `#`
+        offset: f64,
+
+        /// Messages for exact numbers, checked before the categories
+        exact: Vec<(f64, Vec<Part>)>,
+
+        /// Messages for each plural category
+        categories: Vec<(PluralCategory, Vec<Part>)>,
+    },
+
+    /// `{name, select, female {...} other {...}}`
+    Select {
+        /// Argument the select depends on
+        name: String,
+
+        /// Messages for each value, `other` matching the rest
+        cases: Vec<(String, Vec<Part>)>,
+    },
+
+    /// `#` inside a plural, replaced by its number
+    Pound,
+}
+
+/// Message in the ICU `MessageFormat` syntax, parsed once and formatted with arguments
+///
+/// Supports simple arguments, `number`, `date`, `time`, `plural` and `select`, with `'` quoting syntax characters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// Pieces making up the message
+    parts: Vec<Part>,
+}
+
+impl Message {
+    /// Parses a message
+    ///
+    /// # Errors
+    ///
+    /// Errors if the message isn't valid `MessageFormat` syntax
+    pub fn parse(message: &str) -> Result<Self, String> {
+        let mut parser: Parser<'_> = Parser {
+            chars: message.chars().peekable(),
+        };
+
+        Ok(Self {
+            parts: parser.parts(false, false)?,
+        })
+    }
+
+    /// Names of the arguments the message uses
+    #[must_use]
+    pub fn arguments(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        collect_arguments(&self.parts, &mut names);
+
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Formats the message for a locale, leaving missing arguments as `{name}`
+    #[must_use]
+    pub fn format(&self, locale: &str, arguments: &[(&str, Argument)]) -> String {
+        let mut result: String = String::new();
+        write_parts(&self.parts, locale, arguments, None, &mut result);
+
+        result
+    }
+}
+
+/// Parses the `MessageFormat` syntax one character at a time
+struct Parser<'a> {
+    /// Characters left to parse
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// Parses text and arguments until the end of the message, or the closing brace of a nested message
+    fn parts(&mut self, nested: bool, in_plural: bool) -> Result<Vec<Part>, String> {
+        let mut parts: Vec<Part> = Vec::new();
+        let mut text: String = String::new();
+
+        while let Some(&character) = self.chars.peek() {
+            match character {
+                '}' if nested => break,
+                '}' => return Err("Unmatched }".to_string()),
+                '{' => {
+                    self.chars.next();
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(self.argument(in_plural)?);
+                }
+                '#' if in_plural => {
+                    self.chars.next();
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Pound);
+                }
+                '\'' => {
+                    self.chars.next();
+                    self.quoted(&mut text, in_plural);
+                }
+                _ => {
+                    self.chars.next();
+                    text.push(character);
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(parts)
+    }
+
+    /// Handles an apostrophe, which escapes itself or quotes syntax characters until the next apostrophe
+    fn quoted(&mut self, text: &mut String, in_plural: bool) {
+        match self.chars.peek() {
+            Some('\'') => {
+                self.chars.next();
+                text.push('\'');
+            }
+            Some('{' | '}') => {
+                while let Some(character) = self.chars.next() {
+                    if character != '\'' {
+                        text.push(character);
+                    } else if self.chars.peek() == Some(&'\'') {
+                        self.chars.next();
+                        text.push('\'');
+                    } else {
+                        break;
+                    }
+                }
+            }
+            Some('#') if in_plural => {
+                self.chars.next();
+                text.push('#');
+                if self.chars.peek() == Some(&'\'') {
+                    self.chars.next();
+                }
+            }
+            _ => text.push('\''),
+        }
+    }
+
+    /// Parses an argument after its opening brace, up to and including its closing brace
+    fn argument(&mut self, in_plural: bool) -> Result<Part, String> {
+        let name: String = self.word();
+        if name.is_empty() {
+            return Err("Expected an argument name after {".to_string());
+        }
+
+        if self.eat('}') {
+            return Ok(Part::Argument(name));
+        }
+        if !self.eat(',') {
+            return Err(format!("Expected , or }} after argument {name}"));
+        }
+
+        let kind: String = self.word();
+        let part: Part = match kind.as_str() {
+            "number" => {
+                let style: NumberStyle = match self.style()?.as_deref() {
+                    None => NumberStyle::Decimal,
+                    Some("integer") => NumberStyle::Integer,
+                    Some("percent") => NumberStyle::Percent,
+                    Some(style) => return Err(format!("Unknown number style {style}")),
+                };
+
+                Part::Number(name, style)
+            }
+            "date" | "time" => {
+                let style: DateStyle = match self.style()?.as_deref() {
+                    None | Some("short") => DateStyle::Short,
+                    Some("iso") => DateStyle::Iso,
+                    Some(style) => return Err(format!("Unknown {kind} style {style}")),
+                };
+
+                if kind == "date" {
+                    Part::Date(name, style)
+                } else {
+                    Part::Time(name, style)
+                }
+            }
+            "plural" => self.plural(name)?,
+            "select" => self.select(name, in_plural)?,
+            _ => return Err(format!("Unknown argument type {kind} for {name}")),
+        };
+
+        if !self.eat('}') {
+            return Err(format!(
+                "Expected }} to close argument {}",
+                part_name(&part)
+            ));
+        }
+
+        Ok(part)
+    }
+
+    /// Parses the optional style following an argument type
+    fn style(&mut self) -> Result<Option<String>, String> {
+        if !self.eat(',') {
+            return Ok(None);
+        }
+
+        let style: String = self.word();
+        if style.is_empty() {
+            return Err("Expected a style after ,".to_string());
+        }
+
+        Ok(Some(style))
+    }
+
+    /// Parses the cases of a plural argument
+    fn plural(&mut self, name: String) -> Result<Part, String> {
+        if !self.eat(',') {
+            return Err(format!("Expected , before the cases of plural {name}"));
+        }
+
+        let mut offset: f64 = 0.0;
+        let mut exact: Vec<(f64, Vec<Part>)> = Vec::new();
+        let mut categories: Vec<(PluralCategory, Vec<Part>)> = Vec::new();
+
+        loop {
+            self.whitespace();
+            if self.chars.peek() == Some(&'}') {
+                break;
+            }
+
+            let selector: String = self.selector();
+            if let Some(value) = selector.strip_prefix("offset:") {
+                offset = parse_number(value, &name)?;
+                continue;
+            }
+
+            let message: Vec<Part> = self.case(&name, &selector, true)?;
+            if let Some(value) = selector.strip_prefix('=') {
+                exact.push((parse_number(value, &name)?, message));
+            } else if let Some(category) = PluralCategory::from_keyword(&selector) {
+                categories.push((category, message));
+            } else {
+                return Err(format!("Unknown plural category {selector} in {name}"));
+            }
+        }
+
+        if !categories
+            .iter()
+            .any(|(category, _)| *category == PluralCategory::Other)
+        {
+            return Err(format!("Plural {name} is missing the other case"));
+        }
+
+        Ok(Part::Plural {
+            name,
+            offset,
+            exact,
+            categories,
+        })
+    }
+
+    /// Parses the cases of a select argument
+    fn select(&mut self, name: String, in_plural: bool) -> Result<Part, String> {
+        if !self.eat(',') {
+            return Err(format!("Expected , before the cases of select {name}"));
+        }
+
+        let mut cases: Vec<(String, Vec<Part>)> = Vec::new();
+        loop {
+            self.whitespace();
+            if self.chars.peek() == Some(&'}') {
+                break;
+            }
+
+            let selector: String = self.selector();
+            let message: Vec<Part> = self.case(&name, &selector, in_plural)?;
+            cases.push((selector, message));
+        }
+
+        if !cases.iter().any(|(selector, _)| selector == "other") {
+            return Err(format!("Select {name} is missing the other case"));
+        }
+
+        Ok(Part::Select { name, cases })
+    }
+
+    /// Parses the braced message of a plural or select case
+    fn case(&mut self, name: &str, selector: &str, in_plural: bool) -> Result<Vec<Part>, String> {
+        if selector.is_empty() {
+            return Err(format!("Expected a case in {name}"));
+        }
+
+        // Whitespace inside the case is part of its message
+        self.whitespace();
+        if self.chars.next_if_eq(&'{').is_none() {
+            return Err(format!("Expected {{ after case {selector} in {name}"));
+        }
+
+        let message: Vec<Part> = self.parts(true, in_plural)?;
+        if !self.eat('}') {
+            return Err(format!("Expected }} to close case {selector} in {name}"));
+        }
+
+        Ok(message)
+    }
+
+    /// Reads an identifier, skipping the whitespace around it
+    fn word(&mut self) -> String {
+        self.whitespace();
+
+        let mut word: String = String::new();
+        while let Some(&character) = self.chars.peek() {
+            if !(character.is_alphanumeric() || matches!(character, '_' | '-' | '.')) {
+                break;
+            }
+
+            word.push(character);
+            self.chars.next();
+        }
+
+        self.whitespace();
+        word
+    }
+
+    /// Reads a case selector such as `one`, `=0` or `offset:1`
+    fn selector(&mut self) -> String {
+        let mut selector: String = String::new();
+        while let Some(&character) = self.chars.peek() {
+            if character.is_whitespace() || matches!(character, '{' | '}') {
+                break;
+            }
+
+            selector.push(character);
+            self.chars.next();
+        }
+
+        selector
+    }
+
+    /// Skips whitespace
+    fn whitespace(&mut self) {
+        while self
+            .chars
+            .next_if(|character| character.is_whitespace())
+            .is_some()
+        {}
+    }
+
+    /// Consumes a character if it is next, skipping the whitespace around it
+    fn eat(&mut self, expected: char) -> bool {
+        self.whitespace();
+        let eaten: bool = self.chars.next_if_eq(&expected).is_some();
+
+        // Whitespace after a closing brace is part of the surrounding text
+        if eaten && expected != '}' {
+            self.whitespace();
+        }
+
+        eaten
+    }
+}
+
+/// Parses the number in an exact case or offset
+fn parse_number(value: &str, name: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid number {value} in plural {name}"))
+}
+
+/// Name of the argument a part depends on
+fn part_name(part: &Part) -> &str {
+    match part {
+        Part::Argument(name)
+        | Part::Number(name, _)
+        | Part::Date(name, _)
+        | Part::Time(name, _)
+        | Part::Plural { name, .. }
+        | Part::Select { name, .. } => name,
+        Part::Text(text) => text,
+        Part::Pound => "#",
+    }
+}
+
+/// Collects the names of the arguments used by parts and the cases nested in them
+fn collect_arguments<'a>(parts: &'a [Part], names: &mut Vec<&'a str>) {
+    for part in parts {
+        match part {
+            Part::Text(_) | Part::Pound => {}
+            Part::Argument(name)
+            | Part::Number(name, _)
+            | Part::Date(name, _)
+            | Part::Time(name, _) => {
+                names.push(name);
+            }
+            Part::Plural {
+                name,
+                exact,
+                categories,
+                ..
+            } => {
+                names.push(name);
+                for (_, message) in exact {
+                    collect_arguments(message, names);
+                }
+                for (_, message) in categories {
+                    collect_arguments(message, names);
+                }
+            }
+            Part::Select { name, cases } => {
+                names.push(name);
+                for (_, message) in cases {
+                    collect_arguments(message, names);
+                }
+            }
+        }
+    }
+}
+
+/// Formats parts into a string, with `pound` being the number of the innermost plural
+fn write_parts(
+    parts: &[Part],
+    locale: &str,
+    arguments: &[(&str, Argument)],
+    pound: Option<f64>,
+    result: &mut String,
+) {
+    let find = |name: &str| -> Option<&Argument> {
+        arguments
+            .iter()
+            .find(|(argument, _)| *argument == name)
+            .map(|(_, value)| value)
+    };
+
+    for part in parts {
+        match (part, find(part_name(part))) {
+            (Part::Text(text), _) => result.push_str(text),
+            (Part::Pound, _) => match pound {
+                Some(number) => {
+                    result.push_str(&number::format_number(locale, number, NumberStyle::Decimal));
+                }
+                None => result.push('#'),
+            },
+            (Part::Argument(_), Some(Argument::String(value))) => result.push_str(value),
+            (Part::Argument(_) | Part::Number(..), Some(Argument::Number(value))) => {
+                let style: NumberStyle = match part {
+                    Part::Number(_, style) => *style,
+                    _ => NumberStyle::Decimal,
+                };
+
+                result.push_str(&number::format_number(locale, *value, style));
+            }
+            (Part::Argument(_), Some(Argument::Time(time))) => {
+                result.push_str(&number::format_date(locale, *time, DateStyle::Short));
+            }
+            (Part::Date(_, style), Some(Argument::Time(time))) => {
+                result.push_str(&number::format_date(locale, *time, *style));
+            }
+            (Part::Time(_, style), Some(Argument::Time(time))) => {
+                result.push_str(&number::format_time(locale, *time, *style));
+            }
+            (
+                Part::Plural {
+                    offset,
+                    exact,
+                    categories,
+                    ..
+                },
+                Some(Argument::Number(value)),
+            ) => {
+                let message: Option<&Vec<Part>> = exact
+                    .iter()
+                    .find(|(number, _)| (number - value).abs() < f64::EPSILON)
+                    .map(|(_, message)| message)
+                    .or_else(|| {
+                        let category: PluralCategory =
+                            plural::plural_category(locale, value - offset);
+
+                        categories
+                            .iter()
+                            .find(|(case, _)| *case == category)
+                            .or_else(|| {
+                                categories
+                                    .iter()
+                                    .find(|(case, _)| *case == PluralCategory::Other)
+                            })
+                            .map(|(_, message)| message)
+                    });
+
+                if let Some(message) = message {
+                    write_parts(message, locale, arguments, Some(value - offset), result);
+                }
+            }
+            (Part::Select { cases, .. }, value) => {
+                let selector: Option<&str> = match value {
+                    Some(Argument::String(value)) => Some(value),
+                    _ => None,
+                };
+
+                let message: Option<&Vec<Part>> = cases
+                    .iter()
+                    .find(|(case, _)| Some(case.as_str()) == selector)
+                    .or_else(|| cases.iter().find(|(case, _)| case == "other"))
+                    .map(|(_, message)| message);
+
+                if let Some(message) = message {
+                    write_parts(message, locale, arguments, pound, result);
+                }
+            }
+            // Missing or mismatched arguments are left in so they stand out
+            (_, _) => {
+                result.push('{');
+                result.push_str(part_name(part));
+                result.push('}');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Argument, Message};
+
+    #[test]
+    fn test_format() {
+        let message: Message = Message::parse(
+            "{player} found {count, plural, =0 {no apples} one {an apple} other {# apples}}",
+        )
+        .unwrap();
+        assert_eq!(message.arguments(), vec!["count", "player"]);
+        assert_eq!(
+            message.format("en", &[("player", "Alexis".into()), ("count", 0.into())]),
+            "Alexis found no apples"
+        );
+        assert_eq!(
+            message.format("en", &[("player", "Alexis".into()), ("count", 1.into())]),
+            "Alexis found an apple"
+        );
+        assert_eq!(
+            message.format("en", &[("player", "Alexis".into()), ("count", 1200.into())]),
+            "Alexis found 1,200 apples"
+        );
+        assert_eq!(message.format("en", &[]), "{player} found {count}");
+
+        let message: Message = Message::parse(
+            "{gender, select, female {She has} other {They have}} {n, number, percent} '{'done'}'",
+        )
+        .unwrap();
+        assert_eq!(
+            message.format("en", &[("gender", "female".into()), ("n", 0.5.into())]),
+            "She has 50% {done}"
+        );
+        assert_eq!(
+            message.format("de", &[("n", Argument::Number(1.0))]),
+            "They have 100% {done}"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Message::parse("{count, plural, one {# apple}}").is_err());
+        assert!(Message::parse("{count, plural, one {# apple} other {# apples}").is_err());
+        assert!(Message::parse("{name, unknown}").is_err());
+        assert!(Message::parse("unmatched }").is_err());
+        assert_eq!(
+            Message::parse("It''s '#1'").unwrap().format("en", &[]),
+            "It's '#1'"
+        );
+    }
+}
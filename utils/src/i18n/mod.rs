@@ -1,34 +1,265 @@
-use std::{collections::HashMap, path::Path};
+/// Module for parsing locales and building their fallback chains
+pub mod locale;
+
+/// Module for formatting messages in the ICU `MessageFormat` syntax
+pub mod message;
+
+/// Module for selecting the plural category of a number
+pub mod plural;
+
+/// Module for formatting numbers, dates and times per locale
+pub mod number;
+
+/// Module for reading translations from each layer of resources
+pub mod loader;
+
+/// Module for checking the coverage of every locale against the default locale
+pub mod check;
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    path::PathBuf,
+    sync::OnceLock,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+pub use locale::DEFAULT_LOCALE;
+pub use message::{Argument, Message};
+
 // English should always be embedded in the binary for the logger to work,
 //   translations should be loaded via the resource loader system,
 //   translations should be overridable by the user via the resource loader system,
 //   mods should be able to provide custom translation keys which do not override the internal keys if they exist
 
+/// Virtual path of the directory holding a `<locale>.toml` file per locale
+pub const LOCALES_PATH: &str = "resources/locales";
+
+/// Virtual path of the directory holding the user's `<locale>.toml` overrides
+pub const USER_LOCALES_PATH: &str = "resources/locales/overrides";
+
+/// Translations used by the client and server
+static TRANSLATIONS: OnceLock<I18N> = OnceLock::new();
+
+/// Where a translation was loaded from, in increasing order of precedence
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TranslationSource {
+    /// Locales embedded in the binary
+    Embedded,
+
+    /// Locales in the resources directory
+    Vanilla,
+
+    /// Locales provided by the mod with this id, whose keys are prefixed with the id
+    Mod(String),
+
+    /// Translations the user overrode
+    User,
+}
+
+impl Display for TranslationSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Embedded => write!(f, "embedded locales"),
+            Self::Vanilla => write!(f, "vanilla locales"),
+            Self::Mod(id) => write!(f, "mod {id}"),
+            Self::User => write!(f, "user overrides"),
+        }
+    }
+}
+
+/// Translated text along with where it came from
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translation {
+    /// Message in the ICU `MessageFormat` syntax
+    pub text: String,
+
+    /// Layer the translation was loaded from
+    pub source: TranslationSource,
+}
+
+/// Problem found while loading translations
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslationDiagnostic {
+    /// A locale file could not be read or parsed
+    Invalid {
+        /// Layer the file belongs to
+        source: TranslationSource,
+
+        /// Path of the file
+        path: PathBuf,
+
+        /// Why the file could not be used
+        error: String,
+    },
+
+    /// A mod provided a key which was already translated, so it was ignored
+    Conflict {
+        /// Locale the key was translated in
+        locale: String,
+
+        /// Translation key
+        key: String,
+
+        /// Layer whose translation was kept
+        kept: TranslationSource,
+
+        /// Layer whose translation was ignored
+        ignored: TranslationSource,
+    },
+
+    /// Keys of the default locale which neither a locale nor its fallbacks translate
+    Missing {
+        /// Locale missing the keys
+        locale: String,
+
+        /// Untranslated keys, sorted
+        keys: Vec<String>,
+    },
+}
+
+impl Display for TranslationDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid {
+                source,
+                path,
+                error,
+            } => write!(
+                f,
+                "Could not load {} from {source}: {error}",
+                path.display()
+            ),
+            Self::Conflict {
+                locale,
+                key,
+                kept,
+                ignored,
+            } => write!(
+                f,
+                "{ignored} translates {key} for locale {locale}, which {kept} already translates, ignoring it"
+            ),
+            Self::Missing { locale, keys } => write!(
+                f,
+                "Locale {locale} is missing {} translations: {}",
+                keys.len(),
+                keys.join(", ")
+            ),
+        }
+    }
+}
+
 /// Structure containing translations
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Default, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct I18N {
-    /// `HashMap`<`Locale`, `HashMap`<`TranslationKey`, `TranslationValue`>>
-    trs: HashMap<String, HashMap<String, String>>,
+    /// `HashMap`<`Locale`, `HashMap`<`TranslationKey`, `Translation`>>
+    trs: HashMap<String, HashMap<String, Translation>>,
+
+    /// Locales keys are looked up in, from the selected locale to the default locale
+    fallbacks: Vec<String>,
+
+    /// Problems found while adding translations
+    diagnostics: Vec<TranslationDiagnostic>,
+}
+
+impl Default for I18N {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl I18N {
-    /// Create's new I18N Struct
+    /// Create's new I18N Struct without any translations, with the default locale selected
     #[must_use]
-    pub fn new(_locales_path: &Path) -> Self {
-        // crate::resources::get_resource_string(locales_path);
-        // let _locales_contents: String = fs::read_to_string(locales_path).unwrap();
+    pub fn new() -> Self {
+        Self {
+            trs: HashMap::new(),
+            fallbacks: locale::fallback_chain(DEFAULT_LOCALE),
+            diagnostics: Vec::new(),
+        }
+    }
 
-        // Populate translation struct
-        // let trs: HashMap<String, HashMap<String, String>> = toml::from_str(locales_contents.as_str()).unwrap();
-        let trs: HashMap<String, HashMap<String, String>> =
-            HashMap::<String, HashMap<String, String>>::new();
+    /// Reads the translations of every layer through the virtual filesystem and the loaded mods
+    ///
+    /// See [`loader::load_layers`] for the order of precedence
+    #[must_use]
+    pub fn load() -> Self {
+        let mut i18n: Self = Self::new();
+        loader::load_layers(&mut i18n);
 
-        Self { trs }
+        i18n
+    }
+
+    /// Adds the translations of a locale from a layer
+    ///
+    /// Layers should be added in order of precedence, later layers override earlier ones,
+    /// except for mods which only add keys under their id and never override existing keys
+    pub fn add_translations(
+        &mut self,
+        source: &TranslationSource,
+        locale: &str,
+        translations: HashMap<String, String>,
+    ) {
+        let existing: &mut HashMap<String, Translation> =
+            self.trs.entry(locale.to_string()).or_default();
+
+        for (key, text) in translations {
+            let key: String = match source {
+                TranslationSource::Mod(id) => format!("{id}.{key}"),
+                _ => key,
+            };
+
+            if let (TranslationSource::Mod(_), Some(kept)) = (source, existing.get(&key)) {
+                self.diagnostics.push(TranslationDiagnostic::Conflict {
+                    locale: locale.to_string(),
+                    key,
+                    kept: kept.source.clone(),
+                    ignored: source.clone(),
+                });
+                continue;
+            }
+
+            existing.insert(
+                key,
+                Translation {
+                    text,
+                    source: source.clone(),
+                },
+            );
+        }
+    }
+
+    /// Selects the locale keys are translated into
+    ///
+    /// Keys missing from the locale are looked up in its fallbacks (e.g. `pt-BR` falls back to `pt` and then `en`)
+    pub fn set_locale(&mut self, locale: &str) {
+        self.fallbacks = locale::fallback_chain(locale);
+
+        // Regional variants are expected to fall back to their language
+        let language: &str = self.locale().split('-').next().unwrap_or_default();
+        if language != DEFAULT_LOCALE && !self.trs.contains_key(language) {
+            warn!("No translations for locale {locale}, falling back to {DEFAULT_LOCALE}");
+        }
+    }
+
+    /// Get the selected locale
+    #[must_use]
+    pub fn locale(&self) -> &str {
+        self.fallbacks
+            .first()
+            .map_or(DEFAULT_LOCALE, String::as_str)
+    }
+
+    /// Get the locales keys are looked up in, from the selected locale to the default locale
+    #[must_use]
+    pub fn fallback_chain(&self) -> &[String] {
+        &self.fallbacks
     }
 
     /// Get available locales
@@ -37,12 +268,284 @@ impl I18N {
         self.trs.keys().map(std::string::String::as_str).collect()
     }
 
-    /// Get translated string
+    /// Get string translated into the selected locale or one of its fallbacks
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lookup(&self.fallbacks, key)
+            .map(|(_, message)| message)
+    }
+
+    /// Get string translated into a locale or one of its fallbacks
     #[must_use]
     pub fn translate(&self, locale: &str, key: &str) -> Option<&str> {
-        self.trs
-            .get(locale)?
-            .get(key)
-            .map(std::string::String::as_str)
+        self.lookup(&locale::fallback_chain(locale), key)
+            .map(|(_, message)| message)
+    }
+
+    /// Get message translated into the selected locale or one of its fallbacks, with the arguments substituted
+    ///
+    /// Plurals, numbers and dates follow the rules of the locale the message was found in
+    #[must_use]
+    pub fn format(&self, key: &str, arguments: &[(&str, Argument)]) -> Option<String> {
+        let (locale, message): (&str, &str) = self.lookup(&self.fallbacks, key)?;
+
+        match Message::parse(message) {
+            Ok(message) => Some(message.format(locale, arguments)),
+            Err(error) => {
+                warn!("Could not parse translation {key} for locale {locale}: {error}");
+                Some(message.to_string())
+            }
+        }
+    }
+
+    /// Get the translation of a key in a single locale, along with where it came from
+    #[must_use]
+    pub fn translation(&self, locale: &str, key: &str) -> Option<&Translation> {
+        self.trs.get(locale)?.get(key)
+    }
+
+    /// Get the keys translated in a single locale, sorted
+    #[must_use]
+    pub fn keys(&self, locale: &str) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .trs
+            .get(locale)
+            .map(|translations| translations.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Get the keys of the default locale which neither a locale nor its fallbacks translate, sorted
+    #[must_use]
+    pub fn missing_keys(&self, locale: &str) -> Vec<&str> {
+        let fallbacks: Vec<String> = locale::fallback_chain(locale);
+        let translated = |key: &str| -> bool {
+            fallbacks
+                .iter()
+                .filter(|fallback| *fallback != DEFAULT_LOCALE)
+                .any(|fallback| self.translation(fallback, key).is_some())
+        };
+
+        self.keys(DEFAULT_LOCALE)
+            .into_iter()
+            .filter(|key| !translated(key))
+            .collect()
+    }
+
+    /// Problems found while adding translations, followed by the keys each locale is missing
+    #[must_use]
+    pub fn diagnostics(&self) -> Vec<TranslationDiagnostic> {
+        let mut diagnostics: Vec<TranslationDiagnostic> = self.diagnostics.clone();
+
+        let mut locales: Vec<&str> = self.available_locales();
+        locales.sort_unstable();
+        for locale in locales
+            .into_iter()
+            .filter(|locale| *locale != DEFAULT_LOCALE)
+        {
+            let keys: Vec<&str> = self.missing_keys(locale);
+
+            if !keys.is_empty() {
+                diagnostics.push(TranslationDiagnostic::Missing {
+                    locale: locale.to_string(),
+                    keys: keys.into_iter().map(ToString::to_string).collect(),
+                });
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Looks a key up in each locale until one has it, returning the locale and its translation
+    fn lookup(&self, fallbacks: &[String], key: &str) -> Option<(&str, &str)> {
+        fallbacks.iter().find_map(|locale| {
+            let (locale, translations): (&String, &HashMap<String, Translation>) =
+                self.trs.get_key_value(locale)?;
+
+            Some((locale.as_str(), translations.get(key)?.text.as_str()))
+        })
+    }
+}
+
+/// Loads the translations from the resources and selects a locale
+///
+/// Without a locale, the system's locale is used if it can be detected
+pub fn load_translations(locale: Option<&str>) {
+    let mut i18n: I18N = I18N::load();
+
+    let locale: String = locale
+        .map(ToString::to_string)
+        .or_else(locale::system_locale)
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+    i18n.set_locale(&locale);
+
+    for diagnostic in i18n.diagnostics() {
+        if let TranslationDiagnostic::Missing { .. } = diagnostic {
+            debug!("{diagnostic}");
+        } else {
+            warn!("{diagnostic}");
+        }
+    }
+
+    debug!(
+        "Translating into {} with fallbacks {:?}...",
+        i18n.locale(),
+        i18n.fallback_chain()
+    );
+    store_i18n(i18n);
+}
+
+/// Stores the translations used by the client and server
+pub fn store_i18n(i18n: I18N) {
+    let _ = TRANSLATIONS.set(i18n);
+}
+
+/// Get the translations used by the client and server
+pub fn get_i18n() -> Option<&'static I18N> {
+    TRANSLATIONS.get()
+}
+
+/// Translates a key into the selected locale, returning the key itself if it has no translation
+#[must_use]
+pub fn translate(key: &str) -> String {
+    get_i18n()
+        .and_then(|i18n| i18n.get(key))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// Translates a key into the selected locale with the arguments substituted, returning the key itself if it has no translation
+#[must_use]
+pub fn translate_with(key: &str, arguments: &[(&str, Argument)]) -> String {
+    get_i18n()
+        .and_then(|i18n| i18n.format(key, arguments))
+        .unwrap_or_else(|| key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path, sync::Arc};
+
+    use crate::vfs::{Layer, MemoryMount, Vfs};
+
+    use super::{loader, TranslationDiagnostic, TranslationSource, I18N};
+
+    /// Parses translations written as TOML
+    fn parse(contents: &str) -> HashMap<String, String> {
+        loader::parse_locale(contents).unwrap()
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        let mut i18n: I18N = I18N::new();
+        for (locale, contents) in [
+            (
+                "en",
+                "greeting = \"Hello\"\nfarewell = \"Bye\"\nonly_en = \"en\"\n[chat]\njoined = \"{player} joined\"",
+            ),
+            ("pt", "greeting = \"Olá\"\nfarewell = \"Tchau\""),
+            ("pt-BR", "greeting = \"Oi\""),
+        ] {
+            i18n.add_translations(&TranslationSource::Vanilla, locale, parse(contents));
+        }
+        assert_eq!(i18n.get("greeting"), Some("Hello"));
+
+        i18n.set_locale("pt_BR.UTF-8");
+        assert_eq!(i18n.locale(), "pt-BR");
+        assert_eq!(i18n.get("greeting"), Some("Oi"));
+        assert_eq!(i18n.get("farewell"), Some("Tchau"));
+        assert_eq!(i18n.get("only_en"), Some("en"));
+        assert_eq!(i18n.get("missing"), None);
+
+        assert_eq!(i18n.translate("pt", "greeting"), Some("Olá"));
+        assert_eq!(i18n.translate("fr", "greeting"), Some("Hello"));
+        assert_eq!(
+            i18n.format("chat.joined", &[("player", "Alexis".into())]),
+            Some("Alexis joined".to_string())
+        );
+        assert_eq!(i18n.missing_keys("pt-BR"), vec!["chat.joined", "only_en"]);
+    }
+
+    #[test]
+    fn test_layers() {
+        let mut vfs: Vfs = Vfs::new();
+        for (layer, path, contents) in [
+            (
+                Layer::Embedded,
+                "en.toml",
+                "a = \"embedded\"\nb = \"embedded\"",
+            ),
+            (Layer::User, "en.toml", "b = \"vanilla\"\nc = \"vanilla\""),
+            (
+                Layer::User,
+                "overrides/en.toml",
+                "c = \"user\"\nmod.d = \"user\"",
+            ),
+            (Layer::User, "overrides/xx_invalid.toml", "a = "),
+        ] {
+            let mount: MemoryMount = MemoryMount::new();
+            mount
+                .insert(Path::new(path), contents.as_bytes().to_vec())
+                .unwrap();
+            vfs.mount(Path::new("locales"), layer, Arc::new(mount))
+                .unwrap();
+        }
+
+        let mut i18n: I18N = I18N::new();
+        let locales: &Path = Path::new("locales");
+        loader::load_directory(
+            &mut i18n,
+            &vfs,
+            &TranslationSource::Embedded,
+            Layer::Embedded,
+            locales,
+        );
+        loader::load_directory(
+            &mut i18n,
+            &vfs,
+            &TranslationSource::Vanilla,
+            Layer::User,
+            locales,
+        );
+        i18n.add_translations(
+            &TranslationSource::Mod("mod".to_string()),
+            "en",
+            parse("d = \"mod\"\ne = \"mod\""),
+        );
+        loader::load_directory(
+            &mut i18n,
+            &vfs,
+            &TranslationSource::User,
+            Layer::User,
+            &locales.join("overrides"),
+        );
+
+        assert_eq!(i18n.get("a"), Some("embedded"));
+        assert_eq!(i18n.get("b"), Some("vanilla"));
+        assert_eq!(i18n.get("c"), Some("user"));
+        assert_eq!(i18n.get("d"), None);
+        assert_eq!(i18n.get("mod.d"), Some("user"));
+        assert_eq!(i18n.get("mod.e"), Some("mod"));
+
+        let diagnostics: Vec<TranslationDiagnostic> = i18n.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            diagnostics[0],
+            TranslationDiagnostic::Invalid { .. }
+        ));
+
+        // Mods never override keys which were already translated
+        i18n.add_translations(
+            &TranslationSource::Mod("mod".to_string()),
+            "en",
+            parse("e = \"again\""),
+        );
+        assert_eq!(i18n.get("mod.e"), Some("mod"));
+        assert!(matches!(
+            i18n.diagnostics().last(),
+            Some(TranslationDiagnostic::Conflict { .. })
+        ));
     }
 }
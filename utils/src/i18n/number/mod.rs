@@ -0,0 +1,174 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How a number argument is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberStyle {
+    /// Up to three fraction digits
+    #[default]
+    Decimal,
+
+    /// Rounded to a whole number
+    Integer,
+
+    /// Multiplied by a hundred and followed by a percent sign
+    Percent,
+}
+
+/// How a date or time argument is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateStyle {
+    /// Numeric, in the order the locale writes dates and times
+    #[default]
+    Short,
+
+    /// ISO 8601 regardless of the locale
+    Iso,
+}
+
+/// Formats a number with the digit grouping and decimal separator of a locale
+#[must_use]
+pub fn format_number(locale: &str, number: f64, style: NumberStyle) -> String {
+    let (group, decimal): (&str, &str) = separators(locale);
+
+    let (number, suffix): (f64, &str) = match style {
+        NumberStyle::Decimal => (number, ""),
+        NumberStyle::Integer => (number.round(), ""),
+        NumberStyle::Percent => ((number * 100.0).round(), "%"),
+    };
+
+    let formatted: String = format!("{:.3}", number.abs());
+    let (integer, fraction): (&str, &str) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let fraction: &str = fraction.trim_end_matches('0');
+
+    let mut result: String = String::new();
+    if number < 0.0 && (integer != "0" || !fraction.is_empty()) {
+        result.push('-');
+    }
+
+    // Groups the integer digits by thousands
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            result.push_str(group);
+        }
+        result.push(digit);
+    }
+
+    if !fraction.is_empty() {
+        result.push_str(decimal);
+        result.push_str(fraction);
+    }
+
+    result.push_str(suffix);
+    result
+}
+
+/// Formats a date in UTC as a locale writes it
+#[must_use]
+pub fn format_date(locale: &str, time: SystemTime, style: DateStyle) -> String {
+    let (year, month, day): (i64, u32, u32) = civil_date(time);
+
+    let language: &str = locale.split('-').next().unwrap_or_default();
+    match (style, language) {
+        (DateStyle::Iso, _) | (_, "ja" | "zh" | "ko" | "hu" | "lt" | "sv") => {
+            format!("{year:04}-{month:02}-{day:02}")
+        }
+        (DateStyle::Short, "en") if locale == "en" || locale == "en-US" => {
+            format!("{month}/{day}/{year}")
+        }
+        (DateStyle::Short, "de" | "ru" | "uk" | "pl" | "cs" | "fi" | "nb" | "tr") => {
+            format!("{day:02}.{month:02}.{year}")
+        }
+        (DateStyle::Short, _) => format!("{day:02}/{month:02}/{year}"),
+    }
+}
+
+/// Formats a time of day in UTC as a locale writes it
+#[must_use]
+pub fn format_time(locale: &str, time: SystemTime, style: DateStyle) -> String {
+    let seconds: u64 = since_epoch(time).as_secs() % 86_400;
+    let (hour, minute): (u64, u64) = (seconds / 3600, seconds % 3600 / 60);
+
+    match style {
+        DateStyle::Short if locale == "en" || locale == "en-US" => {
+            let period: &str = if hour < 12 { "AM" } else { "PM" };
+            let hour: u64 = if hour % 12 == 0 { 12 } else { hour % 12 };
+
+            format!("{hour}:{minute:02} {period}")
+        }
+        _ => format!("{hour:02}:{minute:02}"),
+    }
+}
+
+/// Group and decimal separators of a locale
+fn separators(locale: &str) -> (&'static str, &'static str) {
+    let language: &str = locale.split('-').next().unwrap_or_default();
+
+    match language {
+        "de" | "pt" | "es" | "it" | "nl" | "id" | "tr" | "da" | "el" => (".", ","),
+        "fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "hu" => ("\u{a0}", ","),
+        _ => (",", "."),
+    }
+}
+
+/// Time elapsed since the Unix epoch, or zero for earlier times
+fn since_epoch(time: SystemTime) -> Duration {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+/// Converts a time to a year, month and day in UTC
+fn civil_date(time: SystemTime) -> (i64, u32, u32) {
+    #[allow(clippy::cast_possible_wrap)]
+    let days: i64 = (since_epoch(time).as_secs() / 86_400) as i64;
+
+    // Howard Hinnant's days to civil algorithm, counting from the 1st of March 0000
+    let days: i64 = days + 719_468;
+    let era: i64 = days.div_euclid(146_097);
+    let day_of_era: i64 = days.rem_euclid(146_097);
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month: i64 = (5 * day_of_year + 2) / 153;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let day: u32 = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let month: u32 = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year: i64 = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::{format_date, format_number, format_time, DateStyle, NumberStyle};
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(
+            format_number("en", 1_234_567.5, NumberStyle::Decimal),
+            "1,234,567.5"
+        );
+        assert_eq!(
+            format_number("de", 1_234.25, NumberStyle::Decimal),
+            "1.234,25"
+        );
+        assert_eq!(format_number("en", -0.0001, NumberStyle::Decimal), "0");
+        assert_eq!(format_number("en", 0.256, NumberStyle::Percent), "26%");
+        assert_eq!(format_number("en", 2.5, NumberStyle::Integer), "3");
+
+        // 2024-02-29 13:05:09 UTC
+        let time: SystemTime = UNIX_EPOCH + Duration::from_secs(1_709_211_909);
+        assert_eq!(format_date("en", time, DateStyle::Short), "2/29/2024");
+        assert_eq!(format_date("pt-BR", time, DateStyle::Short), "29/02/2024");
+        assert_eq!(format_date("de", time, DateStyle::Short), "29.02.2024");
+        assert_eq!(format_date("en", time, DateStyle::Iso), "2024-02-29");
+        assert_eq!(format_time("en", time, DateStyle::Short), "1:05 PM");
+        assert_eq!(format_time("fr", time, DateStyle::Short), "13:05");
+    }
+}
@@ -0,0 +1,160 @@
+/// Plural category of a number, as defined by the Unicode CLDR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PluralCategory {
+    /// Used by languages such as Arabic for zero
+    Zero,
+
+    /// Usually singular
+    One,
+
+    /// Used by languages with a dual form
+    Two,
+
+    /// Used by languages with paucal forms, such as Russian for 2 to 4
+    Few,
+
+    /// Used by languages with a separate form for larger numbers
+    Many,
+
+    /// Every other number, always present in a message
+    Other,
+}
+
+impl PluralCategory {
+    /// Parses the keyword naming a category in a plural message
+    #[must_use]
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "zero" => Some(Self::Zero),
+            "one" => Some(Self::One),
+            "two" => Some(Self::Two),
+            "few" => Some(Self::Few),
+            "many" => Some(Self::Many),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Operands the CLDR plural rules are written in terms of
+#[derive(Debug, Clone, Copy)]
+struct Operands {
+    /// Absolute value of the number
+    n: f64,
+
+    /// Integer digits of the number
+    i: u64,
+
+    /// Number of visible fraction digits
+    v: u32,
+}
+
+impl Operands {
+    /// Computes the operands of a number shown with up to three fraction digits
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn new(number: f64) -> Self {
+        let n: f64 = number.abs();
+        let i: u64 = n.trunc() as u64;
+
+        let fraction: String = format!("{:.3}", n - i as f64);
+        let v: u32 = fraction
+            .trim_start_matches("0.")
+            .trim_end_matches('0')
+            .len() as u32;
+
+        Self { n, i, v }
+    }
+}
+
+/// Selects the cardinal plural category of a number in a locale
+///
+/// Covers the rules of the most common languages, other languages use the English rules
+#[must_use]
+pub fn plural_category(locale: &str, number: f64) -> PluralCategory {
+    let Operands { n, i, v } = Operands::new(number);
+    let integer: bool = v == 0;
+
+    // European Portuguese follows the English rules rather than the Brazilian ones
+    let language: &str = if locale == "pt-PT" {
+        locale
+    } else {
+        locale.split('-').next().unwrap_or_default()
+    };
+
+    match language {
+        "es" | "el" | "hu" | "tr" | "bg" if (n - 1.0).abs() < f64::EPSILON => PluralCategory::One,
+        "pt" | "fr" if i <= 1 => PluralCategory::One,
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "lo" | "my" | "es" | "el" | "hu"
+        | "tr" | "bg" | "pt" | "fr" => PluralCategory::Other,
+        "ru" | "uk" | "be" => slavic(i, integer),
+        "pl" => match slavic(i, integer) {
+            PluralCategory::One if i != 1 => PluralCategory::Many,
+            category => category,
+        },
+        "cs" | "sk" => match i {
+            _ if !integer => PluralCategory::Many,
+            1 => PluralCategory::One,
+            2..=4 => PluralCategory::Few,
+            _ => PluralCategory::Other,
+        },
+        "ar" => arabic(n, i, integer),
+        "he" if integer && i == 2 => PluralCategory::Two,
+        _ if integer && i == 1 => PluralCategory::One,
+        _ => PluralCategory::Other,
+    }
+}
+
+/// Plural rules shared by Russian, Ukrainian and Belarusian
+fn slavic(i: u64, integer: bool) -> PluralCategory {
+    if !integer {
+        return PluralCategory::Other;
+    }
+
+    match (i % 10, i % 100) {
+        (1, hundreds) if hundreds != 11 => PluralCategory::One,
+        (2..=4, hundreds) if !(12..=14).contains(&hundreds) => PluralCategory::Few,
+        _ => PluralCategory::Many,
+    }
+}
+
+/// Plural rules of Arabic
+fn arabic(n: f64, i: u64, integer: bool) -> PluralCategory {
+    if !integer {
+        return PluralCategory::Other;
+    }
+
+    match (i, i % 100) {
+        _ if n == 0.0 => PluralCategory::Zero,
+        (1, _) => PluralCategory::One,
+        (2, _) => PluralCategory::Two,
+        (_, 3..=10) => PluralCategory::Few,
+        (_, 11..=99) => PluralCategory::Many,
+        _ => PluralCategory::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plural_category, PluralCategory};
+
+    #[test]
+    fn test_plural_category() {
+        assert_eq!(plural_category("en", 1.0), PluralCategory::One);
+        assert_eq!(plural_category("en-US", 1.5), PluralCategory::Other);
+        assert_eq!(plural_category("en", 0.0), PluralCategory::Other);
+        assert_eq!(plural_category("pt-BR", 0.0), PluralCategory::One);
+        assert_eq!(plural_category("pt-PT", 0.0), PluralCategory::Other);
+        assert_eq!(plural_category("ja", 1.0), PluralCategory::Other);
+
+        assert_eq!(plural_category("ru", 21.0), PluralCategory::One);
+        assert_eq!(plural_category("ru", 23.0), PluralCategory::Few);
+        assert_eq!(plural_category("ru", 12.0), PluralCategory::Many);
+        assert_eq!(plural_category("pl", 21.0), PluralCategory::Many);
+        assert_eq!(plural_category("ar", 0.0), PluralCategory::Zero);
+        assert_eq!(plural_category("ar", 105.0), PluralCategory::Few);
+    }
+}
@@ -20,17 +20,38 @@ pub mod i18n;
 /// Module for command line arguments
 pub mod args;
 
+/// Module for settings layered from the config file, environment and command line
+pub mod settings;
+
 /// Module for storing and using resources
 pub mod resources;
 
+/// Module for discovering mods which override resources
+pub mod mods;
+
+/// Module for reading files from directories, archives and memory as a single tree
+pub mod vfs;
+
 /// Module for storing and using build data
 pub mod build;
 
+/// Module for the notices of third party software bundled into the engine
+pub mod licenses;
+
 /// Module for reading the environment variables
 pub mod environment;
 
 /// Module for handling the exit state of the engine
 pub mod exit;
 
+/// Module for writing reports when the engine crashes
+pub mod crash;
+
+/// Module for changing which logs are shown while the engine runs
+pub mod logging;
+
 /// Module for handling strings
 pub mod string;
+
+/// Module for sending messages between the client and server
+pub mod network;
@@ -0,0 +1,247 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+    path::Path,
+    sync::OnceLock,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Virtual path of the directory holding a `<SPDX identifier>.txt` file per license and exception
+pub const LICENSES_PATH: &str = "resources/licenses";
+
+/// Heading of the software which doesn't declare a license
+const UNKNOWN_LICENSE: &str = "Unknown";
+
+/// Third party software bundled into the engine
+static NOTICES: OnceLock<Vec<Notice>> = OnceLock::new();
+
+/// Software bundled into the engine, such as a crate or a font
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Notice {
+    /// Name of the software
+    pub name: String,
+
+    /// Version of the software
+    pub version: String,
+
+    /// SPDX license expression, if the software declares one
+    pub license: Option<String>,
+
+    /// Holders of the copyright, usually the authors
+    pub copyright: Vec<String>,
+}
+
+/// Notices grouped by license along with the text of every license they use
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ThirdPartyNotices {
+    /// Software bundled under each license expression
+    pub groups: BTreeMap<String, Vec<Notice>>,
+
+    /// Text of each license and exception by SPDX identifier, if it is bundled
+    pub texts: BTreeMap<String, Option<String>>,
+}
+
+impl ThirdPartyNotices {
+    /// Groups the notices by license, reading the license texts from the licenses directory
+    #[must_use]
+    pub fn new(notices: &[Notice]) -> Self {
+        Self::with_texts(notices, |id: &str| {
+            crate::vfs::read_string(&Path::new(LICENSES_PATH).join(format!("{id}.txt"))).ok()
+        })
+    }
+
+    /// Groups the notices by license, looking up the text of each license with `text`
+    #[must_use]
+    pub fn with_texts(notices: &[Notice], text: impl Fn(&str) -> Option<String>) -> Self {
+        let mut groups: BTreeMap<String, Vec<Notice>> = BTreeMap::new();
+        for notice in notices {
+            let license: &str = notice.license.as_deref().unwrap_or(UNKNOWN_LICENSE);
+            groups
+                .entry(license.to_string())
+                .or_default()
+                .push(notice.clone());
+        }
+
+        for group in groups.values_mut() {
+            group.sort();
+        }
+
+        let ids: BTreeSet<String> = notices
+            .iter()
+            .filter_map(|notice| notice.license.as_deref())
+            .flat_map(license_ids)
+            .collect();
+        let texts: BTreeMap<String, Option<String>> = ids
+            .into_iter()
+            .map(|id| {
+                let license_text: Option<String> = text(&id);
+                (id, license_text)
+            })
+            .collect();
+
+        Self { groups, texts }
+    }
+
+    /// Licenses and exceptions which have no text in the licenses directory
+    #[must_use]
+    pub fn missing_texts(&self) -> Vec<&str> {
+        self.texts
+            .iter()
+            .filter(|(_, text)| text.is_none())
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+}
+
+impl Display for ThirdPartyNotices {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Third Party Notices")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "The following software is bundled into this game, grouped by license."
+        )?;
+
+        for (license, notices) in &self.groups {
+            writeln!(f)?;
+            writeln!(f, "## {license}")?;
+            writeln!(f)?;
+
+            for notice in notices {
+                if notice.copyright.is_empty() {
+                    writeln!(f, "* {} v{}", notice.name, notice.version)?;
+                } else {
+                    writeln!(
+                        f,
+                        "* {} v{} - Copyright (c) {}",
+                        notice.name,
+                        notice.version,
+                        notice.copyright.join(", ")
+                    )?;
+                }
+            }
+        }
+
+        writeln!(f)?;
+        write!(f, "# License Texts")?;
+
+        for (id, text) in &self.texts {
+            writeln!(f)?;
+            writeln!(f)?;
+            writeln!(f, "## {id}")?;
+            writeln!(f)?;
+
+            match text {
+                Some(text) => write!(f, "{}", text.trim_end())?,
+                None => write!(f, "See https://spdx.org/licenses/{id}.html")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// SPDX identifiers of the licenses and exceptions in a license expression
+///
+/// The legacy `MIT/Apache-2.0` syntax and the `+` suffix are accepted
+#[must_use]
+pub fn license_ids(expression: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+
+    for token in expression
+        .split(|character: char| character.is_whitespace() || matches!(character, '(' | ')' | '/'))
+    {
+        let id: &str = token.trim_end_matches('+');
+
+        if id.is_empty() || matches!(id, "AND" | "OR" | "WITH") {
+            continue;
+        }
+
+        if !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
+        }
+    }
+
+    ids
+}
+
+/// Stores the third party software bundled into the engine for the licenses screen
+pub fn store_notices(notices: Vec<Notice>) {
+    let _notices: &Vec<Notice> = NOTICES.get_or_init(|| notices);
+}
+
+/// Get the third party software bundled into the engine, empty if never stored
+#[must_use]
+pub fn get_notices() -> Vec<Notice> {
+    NOTICES.get().cloned().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::resources::{Compression, EmbeddedFile, EmbeddedFiles};
+
+    use super::{license_ids, Notice, ThirdPartyNotices};
+
+    /// Describes a crate for the tests
+    fn notice(name: &str, license: Option<&str>) -> Notice {
+        Notice {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            license: license.map(ToString::to_string),
+            copyright: vec![format!("The {name} Authors")],
+        }
+    }
+
+    #[test]
+    fn test_third_party_notices() {
+        assert_eq!(
+            license_ids("(MIT OR Apache-2.0 WITH LLVM-exception) AND MPL-2.0+ OR MIT/Zlib"),
+            vec!["MIT", "Apache-2.0", "LLVM-exception", "MPL-2.0", "Zlib"]
+        );
+
+        let notices: ThirdPartyNotices = ThirdPartyNotices::with_texts(
+            &[
+                notice("serde", Some("MIT OR Apache-2.0")),
+                notice("bytemuck", Some("Zlib OR Apache-2.0 OR MIT")),
+                notice("anyhow", Some("MIT OR Apache-2.0")),
+                notice("mystery", None),
+            ],
+            |id: &str| (id != "Zlib").then(|| format!("{id} license text")),
+        );
+
+        assert_eq!(notices.groups.len(), 3);
+        assert_eq!(notices.groups["MIT OR Apache-2.0"][0].name, "anyhow");
+        assert_eq!(notices.missing_texts(), vec!["Zlib"]);
+
+        let document: String = notices.to_string();
+        assert!(document.contains("* serde v1.0.0 - Copyright (c) The serde Authors"));
+        assert!(document.contains("## Unknown\n\n* mystery"));
+        assert!(document.contains("## MIT\n\nMIT license text"));
+        assert!(document.ends_with("See https://spdx.org/licenses/Zlib.html"));
+    }
+
+    #[test]
+    fn test_embedded_license_texts() {
+        /// License text embedded the way the resources macro embeds it
+        const MIT: &[u8] = include_bytes!("../../../resources/licenses/MIT.txt");
+        static FILES: [EmbeddedFile; 1] = [EmbeddedFile {
+            path: "resources/licenses/MIT.txt",
+            contents: MIT,
+            compression: Compression::None,
+            size: MIT.len() as u64,
+        }];
+
+        // Without a resources directory, the texts come from the embedded layer
+        crate::resources::store_embedded_resources(EmbeddedFiles::new(&FILES));
+
+        let notices: ThirdPartyNotices = ThirdPartyNotices::new(&[notice("serde", Some("MIT"))]);
+        assert!(notices.missing_texts().is_empty());
+        assert!(notices.texts["MIT"]
+            .as_deref()
+            .is_some_and(|text| text.starts_with("MIT License")));
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::{OnceLock, RwLock};
+
+/// Replaces the filter of the installed logger, failing if the filter is invalid
+type FilterReloader = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Changes the filter of the logger installed by the engine
+static FILTER_RELOADER: OnceLock<FilterReloader> = OnceLock::new();
+
+/// Filter the logger is currently using
+static LOG_FILTER: RwLock<Option<String>> = RwLock::new(None);
+
+/// Stores how to change the filter of the installed logger, along with the filter it started with
+///
+/// # Panics
+///
+/// May panic if the filter's lock is poisoned
+pub fn store_filter_reloader(
+    filter: &str,
+    reloader: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+) {
+    if FILTER_RELOADER.set(Box::new(reloader)).is_ok() {
+        *LOG_FILTER.write().unwrap() = Some(filter.to_string());
+    }
+}
+
+/// Changes which logs are shown without restarting (e.g. from a debug console or admin command)
+///
+/// The filter uses `EnvFilter` syntax (e.g. `info,catgirl_engine_server=trace`)
+///
+/// # Errors
+///
+/// Errors if the engine didn't install a logger or the filter is invalid
+///
+/// # Panics
+///
+/// May panic if the filter's lock is poisoned
+pub fn set_log_filter(filter: &str) -> Result<(), String> {
+    let reloader: &FilterReloader = FILTER_RELOADER.get().ok_or_else(|| {
+        "The log filter cannot be changed without the engine's logger".to_string()
+    })?;
+
+    reloader(filter)?;
+    *LOG_FILTER.write().unwrap() = Some(filter.to_string());
+    info!("Changed the log filter to {filter}");
+
+    Ok(())
+}
+
+/// Get the filter the logger is currently using, if the engine installed one
+///
+/// # Panics
+///
+/// May panic if the filter's lock is poisoned
+#[must_use]
+pub fn get_log_filter() -> Option<String> {
+    LOG_FILTER.read().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_set_log_filter() {
+        assert!(super::set_log_filter("debug").is_err());
+
+        super::store_filter_reloader("info", |filter: &str| {
+            if filter.contains("loud") {
+                Err(format!("Unknown log level in {filter}"))
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(super::get_log_filter().as_deref(), Some("info"));
+
+        super::set_log_filter("catgirl_engine=debug").unwrap();
+        assert!(super::set_log_filter("catgirl_engine=loud").is_err());
+        assert_eq!(
+            super::get_log_filter().as_deref(),
+            Some("catgirl_engine=debug")
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+
+use semver::{Version, VersionReq};
+use toml::{Table, Value};
+
+/// Name of the manifest file at the root of every mod
+pub const MANIFEST_FILE: &str = "mod.toml";
+
+/// Describes a mod and how it fits in with other mods
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModManifest {
+    /// Unique id other mods refer to this mod by
+    pub id: String,
+
+    /// Human readable name
+    pub name: String,
+
+    /// Version of the mod
+    pub version: Version,
+
+    /// Mods which must be loaded before this one, keyed by id
+    pub dependencies: BTreeMap<String, VersionReq>,
+
+    /// Mods which should be loaded before this one if installed
+    pub load_after: Vec<String>,
+
+    /// Mods which should be loaded after this one if installed
+    pub load_before: Vec<String>,
+}
+
+impl ModManifest {
+    /// Parses a manifest
+    ///
+    /// ```toml
+    /// id = "example"
+    /// name = "Example Mod"
+    /// version = "1.0.0"
+    ///
+    /// [dependencies]
+    /// library = "^2.1"
+    ///
+    /// [load_order]
+    /// after = ["textures"]
+    /// before = ["sounds"]
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors if the manifest isn't valid TOML or a field is missing or invalid
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let table: Table = source
+            .parse::<Table>()
+            .map_err(|error| format!("Invalid TOML: {error}"))?;
+
+        let id: String = get_string(&table, "id")?
+            .ok_or_else(|| "Missing `id`".to_string())?
+            .to_string();
+        validate_id(&id)?;
+
+        let name: String = get_string(&table, "name")?.unwrap_or(&id).to_string();
+
+        let version: &str =
+            get_string(&table, "version")?.ok_or_else(|| "Missing `version`".to_string())?;
+        let version: Version =
+            Version::parse(version).map_err(|error| format!("Invalid `version`: {error}"))?;
+
+        let mut dependencies: BTreeMap<String, VersionReq> = BTreeMap::new();
+        if let Some(table) = get_table(&table, "dependencies")? {
+            for (dependency, requirement) in table {
+                let Value::String(requirement) = requirement else {
+                    return Err(format!(
+                        "Dependency `{dependency}` must be a version requirement string"
+                    ));
+                };
+
+                let requirement: VersionReq = VersionReq::parse(requirement).map_err(|error| {
+                    format!("Invalid version requirement for dependency `{dependency}`: {error}")
+                })?;
+                dependencies.insert(dependency.clone(), requirement);
+            }
+        }
+
+        let (load_after, load_before) = match get_table(&table, "load_order")? {
+            Some(table) => (get_ids(table, "after")?, get_ids(table, "before")?),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Ok(Self {
+            id,
+            name,
+            version,
+            dependencies,
+            load_after,
+            load_before,
+        })
+    }
+}
+
+/// Checks a mod id only uses lowercase letters, digits, `_` and `-`
+///
+/// # Errors
+///
+/// Errors if the id is empty or contains other characters
+fn validate_id(id: &str) -> Result<(), String> {
+    let valid: bool = !id.is_empty()
+        && id.chars().all(|character| {
+            character.is_ascii_lowercase()
+                || character.is_ascii_digit()
+                || character == '_'
+                || character == '-'
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid `id` {id:?}, only lowercase letters, digits, `_` and `-` are allowed"
+        ))
+    }
+}
+
+/// Retrieves an optional string field
+///
+/// # Errors
+///
+/// Errors if the field isn't a string
+fn get_string<'a>(table: &'a Table, key: &str) -> Result<Option<&'a str>, String> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(format!("`{key}` must be a string")),
+    }
+}
+
+/// Retrieves an optional table field
+///
+/// # Errors
+///
+/// Errors if the field isn't a table
+fn get_table<'a>(table: &'a Table, key: &str) -> Result<Option<&'a Table>, String> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(Value::Table(value)) => Ok(Some(value)),
+        Some(_) => Err(format!("`{key}` must be a table")),
+    }
+}
+
+/// Retrieves an optional list of mod ids
+///
+/// # Errors
+///
+/// Errors if the field isn't a list of strings
+fn get_ids(table: &Table, key: &str) -> Result<Vec<String>, String> {
+    let Some(value) = table.get(key) else {
+        return Ok(Vec::new());
+    };
+
+    let error = || format!("`load_order.{key}` must be a list of mod ids");
+    let Value::Array(values) = value else {
+        return Err(error());
+    };
+
+    values
+        .iter()
+        .map(|value| value.as_str().map(str::to_string).ok_or_else(error))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::{Version, VersionReq};
+
+    use super::ModManifest;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: ModManifest = ModManifest::parse(
+            r#"
+            id = "example"
+            version = "1.2.3"
+
+            [dependencies]
+            library = "^2.1"
+
+            [load_order]
+            after = ["textures"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name, "example");
+        assert_eq!(manifest.version, Version::new(1, 2, 3));
+        assert_eq!(
+            manifest.dependencies.get("library"),
+            Some(&VersionReq::parse("^2.1").unwrap())
+        );
+        assert_eq!(manifest.load_after, vec!["textures".to_string()]);
+        assert!(manifest.load_before.is_empty());
+
+        assert!(ModManifest::parse("id = \"Bad Id\"\nversion = \"1.0.0\"").is_err());
+        assert!(ModManifest::parse("id = \"example\"").is_err());
+    }
+}
@@ -0,0 +1,394 @@
+/// Module for parsing mod manifests
+pub mod manifest;
+
+/// Module for reading files from mod directories and archives
+pub mod package;
+
+/// Module for ordering mods by their dependencies
+pub mod resolver;
+
+/// Module for packing mod directories into archives
+pub mod pack;
+
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use semver::{Version, VersionReq};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::vfs::{Layer, MountSource};
+use manifest::{ModManifest, MANIFEST_FILE};
+use package::{ModFiles, ModSource};
+
+/// Name of the directory mods are installed to within the resources directory
+pub const MODS_DIRECTORY: &str = "mods";
+
+/// Mods which override resources in load order, stored once they have been resolved
+static MODS: OnceLock<Vec<Mod>> = OnceLock::new();
+
+/// An installed mod
+#[derive(Debug, Clone)]
+pub struct Mod {
+    /// Describes the mod
+    pub manifest: ModManifest,
+
+    /// Where the mod's files are stored
+    pub source: ModSource,
+
+    /// Files the mod provides, relative to the resources directory
+    pub files: BTreeSet<PathBuf>,
+
+    /// Opened files of the mod
+    pub mount: Arc<dyn MountSource>,
+}
+
+impl Mod {
+    /// Reads the manifest and lists the files of a mod
+    ///
+    /// # Errors
+    ///
+    /// Errors if the manifest is missing or invalid, or the files cannot be listed
+    pub fn open(source: ModSource) -> Result<Self, String> {
+        let mount: Arc<dyn MountSource> = source.open()?;
+
+        let manifest: String =
+            String::from_utf8(mount.read(Path::new(MANIFEST_FILE))?.into_owned())
+                .map_err(|_| format!("{MANIFEST_FILE} is not valid UTF-8"))?;
+        let manifest: ModManifest = ModManifest::parse(&manifest)?;
+
+        let mut files: BTreeSet<PathBuf> = crate::vfs::walk(mount.as_ref(), Path::new(""))
+            .into_iter()
+            .collect();
+        files.remove(Path::new(MANIFEST_FILE));
+
+        Ok(Self {
+            manifest,
+            source,
+            files,
+            mount,
+        })
+    }
+
+    /// Unique id of the mod
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.manifest.id
+    }
+
+    /// Reads a file the mod provides
+    ///
+    /// # Errors
+    ///
+    /// Errors if the mod does not provide the file or it cannot be read
+    pub fn read(&self, relative: &Path) -> Result<Cow<'static, [u8]>, String> {
+        if !self.files.contains(relative) {
+            return Err(format!(
+                "Mod {} does not provide {}",
+                self.id(),
+                relative.display()
+            ));
+        }
+
+        self.mount.read(relative)
+    }
+
+    /// Whether this mod declared it loads after another mod
+    fn expects(&self, id: &str) -> bool {
+        self.manifest.dependencies.contains_key(id)
+            || self.manifest.load_after.iter().any(|other| other == id)
+    }
+
+    /// Whether this mod declared it loads before another mod
+    fn expects_before(&self, id: &str) -> bool {
+        self.manifest.load_before.iter().any(|other| other == id)
+    }
+}
+
+/// Installed mod as shown when listing mods
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModSummary {
+    /// Unique id of the mod
+    pub id: String,
+
+    /// Human readable name
+    pub name: String,
+
+    /// Version of the mod
+    pub version: String,
+
+    /// Directory or archive of the mod
+    pub path: PathBuf,
+
+    /// Whether the mod loads, mods are disabled by missing dependencies, duplicate ids and cycles
+    pub loaded: bool,
+}
+
+impl From<(&Mod, bool)> for ModSummary {
+    fn from((listed, loaded): (&Mod, bool)) -> Self {
+        Self {
+            id: listed.id().to_string(),
+            name: listed.manifest.name.clone(),
+            version: listed.manifest.version.to_string(),
+            path: listed.source.path().to_path_buf(),
+            loaded,
+        }
+    }
+}
+
+/// Problem found while discovering or resolving mods
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModDiagnostic {
+    /// A mod could not be read
+    Invalid {
+        /// Directory or archive of the mod
+        path: PathBuf,
+
+        /// Why the mod could not be read
+        error: String,
+    },
+
+    /// Two mods share an id
+    DuplicateId {
+        /// Shared id
+        id: String,
+
+        /// Mod which is loaded
+        kept: PathBuf,
+
+        /// Mod which is ignored
+        ignored: PathBuf,
+    },
+
+    /// A dependency isn't installed or was disabled
+    MissingDependency {
+        /// Mod which is disabled
+        id: String,
+
+        /// Id of the dependency
+        dependency: String,
+
+        /// Versions of the dependency the mod accepts
+        requirement: VersionReq,
+    },
+
+    /// A dependency is installed with a version the mod does not accept
+    IncompatibleDependency {
+        /// Mod which is disabled
+        id: String,
+
+        /// Id of the dependency
+        dependency: String,
+
+        /// Versions of the dependency the mod accepts
+        requirement: VersionReq,
+
+        /// Version of the dependency which is installed
+        found: Version,
+    },
+
+    /// Mods which need to load after each other
+    Cycle {
+        /// Mods which are disabled
+        ids: Vec<String>,
+    },
+
+    /// A mod needs to load after mods which are disabled for needing to load after each other
+    BlockedByCycle {
+        /// Mod which is disabled
+        id: String,
+
+        /// Mods of the cycle it waits on
+        cycle: Vec<String>,
+    },
+
+    /// Several mods provide the same file without declaring an order between them
+    Conflict {
+        /// File relative to the resources directory
+        path: PathBuf,
+
+        /// Mod whose file is used
+        winner: String,
+
+        /// Mods whose file is ignored
+        overridden: Vec<String>,
+    },
+}
+
+impl ModDiagnostic {
+    /// Whether the diagnostic stopped a mod from loading
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Conflict { .. })
+    }
+}
+
+impl Display for ModDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid { path, error } => write!(f, "Could not load mod {}: {error}", path.display()),
+            Self::DuplicateId { id, kept, ignored } => write!(
+                f,
+                "Mods {} and {} both use the id {id}, ignoring {}",
+                kept.display(),
+                ignored.display(),
+                ignored.display()
+            ),
+            Self::MissingDependency {
+                id,
+                dependency,
+                requirement,
+            } => write!(
+                f,
+                "Mod {id} requires {dependency} {requirement}, which is not installed or was disabled"
+            ),
+            Self::IncompatibleDependency {
+                id,
+                dependency,
+                requirement,
+                found,
+            } => write!(
+                f,
+                "Mod {id} requires {dependency} {requirement}, but version {found} is installed"
+            ),
+            Self::Cycle { ids } => write!(
+                f,
+                "Mods {} must each load after one another, disabling all of them",
+                ids.join(", ")
+            ),
+            Self::BlockedByCycle { id, cycle } => write!(
+                f,
+                "Mod {id} must load after mods {}, which must each load after one another",
+                cycle.join(", ")
+            ),
+            Self::Conflict {
+                path,
+                winner,
+                overridden,
+            } => write!(
+                f,
+                "Mod {winner} overrides {} from {} without declaring a load order",
+                path.display(),
+                overridden.join(", ")
+            ),
+        }
+    }
+}
+
+/// Finds every mod installed in a directory
+///
+/// Mods are directories or zip archives with a manifest at their root
+#[must_use]
+pub fn discover_mods(directory: &Path) -> (Vec<Mod>, Vec<ModDiagnostic>) {
+    let mut mods: Vec<Mod> = Vec::new();
+    let mut diagnostics: Vec<ModDiagnostic> = Vec::new();
+
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        trace!("No mods directory found at {}...", directory.display());
+        return (mods, diagnostics);
+    };
+
+    // Sorting makes it predictable which duplicate wins
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+
+    for path in paths {
+        let source: ModSource = if path.join(MANIFEST_FILE).is_file() {
+            ModSource::Directory(path)
+        } else if path.extension().is_some_and(|extension| extension == "zip") {
+            ModSource::Archive(path)
+        } else {
+            trace!("Skipping {} as it is not a mod...", path.display());
+            continue;
+        };
+
+        match Mod::open(source.clone()) {
+            Ok(found) => mods.push(found),
+            Err(error) => diagnostics.push(ModDiagnostic::Invalid {
+                path: source.path().to_path_buf(),
+                error,
+            }),
+        }
+    }
+
+    (mods, diagnostics)
+}
+
+/// Lists the mods installed in the resources directory without loading them
+///
+/// Mods which load come first in load order, followed by the disabled mods
+#[must_use]
+pub fn list_mods(resources_path: &Path) -> (Vec<ModSummary>, Vec<ModDiagnostic>) {
+    let (mods, mut diagnostics) = discover_mods(&resources_path.join(MODS_DIRECTORY));
+    let (loaded, resolve_diagnostics) = resolver::resolve(mods.clone());
+    diagnostics.extend(resolve_diagnostics);
+
+    let mut summaries: Vec<ModSummary> = loaded
+        .iter()
+        .map(|listed| ModSummary::from((listed, true)))
+        .collect();
+    for listed in &mods {
+        if !loaded
+            .iter()
+            .any(|other| other.source.path() == listed.source.path())
+        {
+            summaries.push(ModSummary::from((listed, false)));
+        }
+    }
+
+    (summaries, diagnostics)
+}
+
+/// Discovers, resolves and stores the mods installed in the resources directory
+///
+/// Each mod is mounted over the resources in load order and every diagnostic is logged and returned
+pub fn load_mods(resources_path: &Path) -> Vec<ModDiagnostic> {
+    let (mods, mut diagnostics) = discover_mods(&resources_path.join(MODS_DIRECTORY));
+    let (mods, resolve_diagnostics) = resolver::resolve(mods);
+    diagnostics.extend(resolve_diagnostics);
+
+    for diagnostic in &diagnostics {
+        if diagnostic.is_error() {
+            error!("{diagnostic}");
+        } else {
+            warn!("{diagnostic}");
+        }
+    }
+
+    for loaded in &mods {
+        let mount_point: &Path = Path::new(crate::resources::RESOURCES_MOUNT_POINT);
+        let files: Arc<ModFiles> = Arc::new(ModFiles::new(loaded.mount.clone()));
+        if let Err(error) = crate::vfs::mount(mount_point, Layer::Mod, files) {
+            error!("Could not mount mod {}: {error}", loaded.id());
+            continue;
+        }
+
+        info!(
+            "Loaded mod {} {} ({})...",
+            loaded.manifest.name,
+            loaded.manifest.version,
+            loaded.id()
+        );
+    }
+
+    store_mods(mods);
+    diagnostics
+}
+
+/// Stores mods in load order
+pub fn store_mods(mods: Vec<Mod>) {
+    let _ = MODS.set(mods);
+}
+
+/// Get the stored mods in load order
+#[must_use]
+pub fn get_mods() -> &'static [Mod] {
+    MODS.get().map_or(&[], Vec::as_slice)
+}
@@ -0,0 +1,118 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use zip::{write::SimpleFileOptions, CompressionMethod, DateTime, ZipWriter};
+
+use super::{manifest::MANIFEST_FILE, package::ModSource, Mod};
+
+/// Packs a mod directory into a zip archive which can be installed in the mods directory
+///
+/// Files are stored in sorted order with a fixed timestamp, so packing the same files gives the same archive
+///
+/// Returns the path of the archive, `<id>-<version>.zip` next to the directory if no output is given
+///
+/// # Errors
+///
+/// Errors if the mod's manifest is missing or invalid, or the archive cannot be written
+pub fn pack_mod(directory: &Path, output: Option<&Path>) -> Result<PathBuf, String> {
+    let packed: Mod = Mod::open(ModSource::Directory(directory.to_path_buf()))?;
+
+    let output: PathBuf = output.map_or_else(
+        || {
+            directory
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(format!("{}-{}.zip", packed.id(), packed.manifest.version))
+        },
+        Path::to_path_buf,
+    );
+
+    // Packing a mod inside itself would add the archive to its own files the next time
+    let parent: &Path = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let inside: bool = parent
+        .canonicalize()
+        .ok()
+        .zip(directory.canonicalize().ok())
+        .is_some_and(|(parent, directory)| parent.starts_with(directory));
+    if inside {
+        return Err(format!(
+            "Cannot write {} inside the mod it packs",
+            output.display()
+        ));
+    }
+
+    let file: File = File::create(&output)
+        .map_err(|error| format!("Could not create {}: {error}", output.display()))?;
+    let mut archive: ZipWriter<File> = ZipWriter::new(file);
+    let options: SimpleFileOptions = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+
+    let manifest: PathBuf = PathBuf::from(MANIFEST_FILE);
+    for path in std::iter::once(&manifest).chain(&packed.files) {
+        // Zip archives always separate directories with forward slashes
+        let name: String = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        archive
+            .start_file(name, options)
+            .map_err(|error| error.to_string())?;
+        archive
+            .write_all(&packed.mount.read(path)?)
+            .map_err(|error| error.to_string())?;
+    }
+
+    archive.finish().map_err(|error| error.to_string())?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::mods::{package::ModSource, Mod};
+
+    use super::pack_mod;
+
+    #[test]
+    fn test_pack_mod() {
+        let root: PathBuf =
+            std::env::temp_dir().join(format!("catgirl-engine-pack-{}", std::process::id()));
+        let directory: PathBuf = root.join("example");
+        std::fs::create_dir_all(directory.join("locales")).unwrap();
+        std::fs::write(
+            directory.join("mod.toml"),
+            "id = \"example\"\nname = \"Example\"\nversion = \"1.2.3\"\n",
+        )
+        .unwrap();
+        std::fs::write(directory.join("locales").join("en.toml"), "quit = \"Quit\"").unwrap();
+
+        let archive: PathBuf = pack_mod(&directory, None).unwrap();
+        assert_eq!(archive, root.join("example-1.2.3.zip"));
+
+        let packed: Mod = Mod::open(ModSource::Archive(archive.clone())).unwrap();
+        assert_eq!(packed.id(), "example");
+        assert_eq!(
+            packed.read(Path::new("locales/en.toml")).unwrap().as_ref(),
+            b"quit = \"Quit\""
+        );
+
+        // Packing again gives the same bytes
+        let first: Vec<u8> = std::fs::read(&archive).unwrap();
+        pack_mod(&directory, Some(&archive)).unwrap();
+        assert_eq!(std::fs::read(&archive).unwrap(), first);
+
+        assert!(pack_mod(&directory, Some(&directory.join("self.zip"))).is_err());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::mods::manifest::MANIFEST_FILE;
+use crate::vfs::{ArchiveMount, DirEntry, DirectoryMount, Metadata, MountSource};
+
+/// Where a mod's files are stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModSource {
+    /// Directory with the manifest at its root
+    Directory(PathBuf),
+
+    /// Zip archive with the manifest at its root
+    Archive(PathBuf),
+}
+
+impl ModSource {
+    /// Path of the directory or archive
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Directory(path) | Self::Archive(path) => path,
+        }
+    }
+
+    /// Opens the mod's files so they can be mounted into the virtual filesystem
+    ///
+    /// # Errors
+    ///
+    /// Errors if the archive cannot be opened
+    pub fn open(&self) -> Result<Arc<dyn MountSource>, String> {
+        Ok(match self {
+            Self::Directory(path) => Arc::new(DirectoryMount::new(path.clone())),
+            Self::Archive(path) => Arc::new(ArchiveMount::open(path)?),
+        })
+    }
+}
+
+/// Mod's files as mounted over the resources, hiding the manifest
+#[derive(Debug)]
+pub(crate) struct ModFiles {
+    /// Every file of the mod
+    inner: Arc<dyn MountSource>,
+}
+
+impl ModFiles {
+    /// Hides the manifest of a mod's files
+    pub(crate) fn new(inner: Arc<dyn MountSource>) -> Self {
+        Self { inner }
+    }
+}
+
+impl MountSource for ModFiles {
+    fn read(&self, path: &Path) -> Result<Cow<'static, [u8]>, String> {
+        if path == Path::new(MANIFEST_FILE) {
+            return Err(format!("{MANIFEST_FILE} is not a resource"));
+        }
+
+        self.inner.read(path)
+    }
+
+    fn metadata(&self, path: &Path) -> Option<Metadata> {
+        if path == Path::new(MANIFEST_FILE) {
+            return None;
+        }
+
+        self.inner.metadata(path)
+    }
+
+    fn list(&self, path: &Path) -> Option<Vec<DirEntry>> {
+        let mut entries: Vec<DirEntry> = self.inner.list(path)?;
+        if path.as_os_str().is_empty() {
+            entries.retain(|entry| entry.name != MANIFEST_FILE);
+        }
+
+        Some(entries)
+    }
+}
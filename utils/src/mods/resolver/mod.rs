@@ -0,0 +1,364 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+use crate::mods::{Mod, ModDiagnostic};
+
+/// Orders mods so each one loads after its dependencies and load order hints
+///
+/// Mods with missing or incompatible dependencies, duplicate ids, or which are part of or wait on a cycle are left out.
+/// Ties are broken by id so the order is the same on every machine.
+#[must_use]
+pub fn resolve(mods: Vec<Mod>) -> (Vec<Mod>, Vec<ModDiagnostic>) {
+    let mut diagnostics: Vec<ModDiagnostic> = Vec::new();
+
+    // The first mod with an id wins, the rest are ignored
+    let mut enabled: BTreeMap<String, Mod> = BTreeMap::new();
+    for current in mods {
+        if let Some(kept) = enabled.get(current.id()) {
+            diagnostics.push(ModDiagnostic::DuplicateId {
+                id: current.id().to_string(),
+                kept: kept.source.path().to_path_buf(),
+                ignored: current.source.path().to_path_buf(),
+            });
+            continue;
+        }
+
+        enabled.insert(current.id().to_string(), current);
+    }
+
+    disable_unsatisfied(&mut enabled, &mut diagnostics);
+    let ordered: Vec<Mod> = order(enabled, &mut diagnostics);
+    find_conflicts(&ordered, &mut diagnostics);
+
+    (ordered, diagnostics)
+}
+
+/// Disables mods whose dependencies are missing or incompatible
+///
+/// Repeats until nothing changes, as disabling a mod can break the mods depending on it
+fn disable_unsatisfied(enabled: &mut BTreeMap<String, Mod>, diagnostics: &mut Vec<ModDiagnostic>) {
+    loop {
+        let mut disabled: Vec<String> = Vec::new();
+
+        for current in enabled.values() {
+            for (dependency, requirement) in &current.manifest.dependencies {
+                let diagnostic: ModDiagnostic = match enabled.get(dependency) {
+                    Some(found) if requirement.matches(&found.manifest.version) => continue,
+                    Some(found) => ModDiagnostic::IncompatibleDependency {
+                        id: current.id().to_string(),
+                        dependency: dependency.clone(),
+                        requirement: requirement.clone(),
+                        found: found.manifest.version.clone(),
+                    },
+                    None => ModDiagnostic::MissingDependency {
+                        id: current.id().to_string(),
+                        dependency: dependency.clone(),
+                        requirement: requirement.clone(),
+                    },
+                };
+
+                diagnostics.push(diagnostic);
+                disabled.push(current.id().to_string());
+                break;
+            }
+        }
+
+        if disabled.is_empty() {
+            return;
+        }
+
+        for id in disabled {
+            enabled.remove(&id);
+        }
+    }
+}
+
+/// Sorts mods so every mod comes after the mods it must load after
+fn order(mut enabled: BTreeMap<String, Mod>, diagnostics: &mut Vec<ModDiagnostic>) -> Vec<Mod> {
+    // Edges point from a mod to the mods which must load after it
+    let mut successors: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut predecessors: BTreeMap<String, usize> = BTreeMap::new();
+
+    for id in enabled.keys() {
+        successors.insert(id.clone(), BTreeSet::new());
+        predecessors.insert(id.clone(), 0);
+    }
+
+    for current in enabled.values() {
+        let id: &String = &current.manifest.id;
+        let after = current
+            .manifest
+            .dependencies
+            .keys()
+            .chain(&current.manifest.load_after)
+            .map(|other| (other, id));
+        let before = current.manifest.load_before.iter().map(|other| (id, other));
+
+        for (first, second) in after.chain(before) {
+            // Load order hints only apply to installed mods
+            if first == second || !enabled.contains_key(first) || !enabled.contains_key(second) {
+                continue;
+            }
+
+            if successors.get_mut(first).unwrap().insert(second.clone()) {
+                *predecessors.get_mut(second).unwrap() += 1;
+            }
+        }
+    }
+
+    let mut ready: BTreeSet<String> = predecessors
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(id, _)| id.clone())
+        .collect();
+    let mut ordered: Vec<Mod> = Vec::with_capacity(enabled.len());
+
+    while let Some(id) = ready.pop_first() {
+        for next in &successors[&id] {
+            let count: &mut usize = predecessors.get_mut(next).unwrap();
+            *count -= 1;
+
+            if *count == 0 {
+                ready.insert(next.clone());
+            }
+        }
+
+        ordered.push(enabled.remove(&id).unwrap());
+    }
+
+    // Anything left either is part of a cycle or loads after one
+    let reachable: BTreeMap<&String, BTreeSet<&String>> = enabled
+        .keys()
+        .map(|id| (id, reachable_from(&successors, id)))
+        .collect();
+
+    // A cycle consists of the mods which can reach each other, sorted by the ids within them
+    let cycles: BTreeSet<Vec<String>> = reachable
+        .iter()
+        .filter(|(id, after)| after.contains(**id))
+        .map(|(id, after)| {
+            after
+                .iter()
+                .filter(|other| reachable[*other].contains(id))
+                .map(|other| (*other).clone())
+                .collect()
+        })
+        .collect();
+
+    for ids in &cycles {
+        diagnostics.push(ModDiagnostic::Cycle { ids: ids.clone() });
+    }
+
+    for id in enabled.keys() {
+        if reachable[id].contains(id) {
+            continue;
+        }
+
+        // Waiting on several cycles only reports the first
+        let Some(cycle) = cycles
+            .iter()
+            .find(|cycle| reachable[&cycle[0]].contains(id))
+        else {
+            continue;
+        };
+
+        diagnostics.push(ModDiagnostic::BlockedByCycle {
+            id: id.clone(),
+            cycle: cycle.clone(),
+        });
+    }
+
+    ordered
+}
+
+/// Finds the mods which load after a mod, directly or through other mods
+fn reachable_from<'a>(
+    successors: &'a BTreeMap<String, BTreeSet<String>>,
+    id: &String,
+) -> BTreeSet<&'a String> {
+    let mut reachable: BTreeSet<&'a String> = BTreeSet::new();
+    let mut stack: Vec<&'a String> = successors[id].iter().collect();
+
+    while let Some(next) = stack.pop() {
+        if reachable.insert(next) {
+            stack.extend(&successors[next]);
+        }
+    }
+
+    reachable
+}
+
+/// Reports files provided by several mods which don't declare an order between each other
+fn find_conflicts(ordered: &[Mod], diagnostics: &mut Vec<ModDiagnostic>) {
+    let mut providers: BTreeMap<&PathBuf, Vec<&Mod>> = BTreeMap::new();
+    for current in ordered {
+        for file in &current.files {
+            providers.entry(file).or_default().push(current);
+        }
+    }
+
+    for (file, mods) in providers {
+        let Some((winner, others)) = mods.split_last() else {
+            continue;
+        };
+
+        // Overriding a file from a mod the winner knows about is intentional
+        let overridden: Vec<String> = others
+            .iter()
+            .filter(|other| !winner.expects(other.id()) && !other.expects_before(winner.id()))
+            .map(|other| other.id().to_string())
+            .collect();
+
+        if !overridden.is_empty() {
+            diagnostics.push(ModDiagnostic::Conflict {
+                path: file.clone(),
+                winner: winner.id().to_string(),
+                overridden,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, path::PathBuf, sync::Arc};
+
+    use crate::mods::{manifest::ModManifest, package::ModSource, Mod, ModDiagnostic};
+    use crate::vfs::MemoryMount;
+
+    use super::resolve;
+
+    /// Creates a mod from its manifest and the files it provides
+    fn create_mod(manifest: &str, files: &[&str]) -> Mod {
+        let manifest: ModManifest = ModManifest::parse(manifest).unwrap();
+
+        Mod {
+            source: ModSource::Directory(PathBuf::from(&manifest.id)),
+            manifest,
+            files: files.iter().map(PathBuf::from).collect::<BTreeSet<_>>(),
+            mount: Arc::new(MemoryMount::new()),
+        }
+    }
+
+    /// Ids of the mods in order
+    fn ids(mods: &[Mod]) -> Vec<&str> {
+        mods.iter().map(Mod::id).collect()
+    }
+
+    #[test]
+    fn test_dependencies_and_hints_order_mods() {
+        let (ordered, diagnostics) = resolve(vec![
+            create_mod(
+                "id = \"a\"\nversion = \"1.0.0\"\n[dependencies]\nc = \"^1\"",
+                &[],
+            ),
+            create_mod(
+                "id = \"b\"\nversion = \"1.0.0\"\n[load_order]\nbefore = [\"c\"]",
+                &[],
+            ),
+            create_mod("id = \"c\"\nversion = \"1.4.0\"", &[]),
+        ]);
+
+        assert_eq!(ids(&ordered), vec!["b", "c", "a"]);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_unsatisfied_dependencies_disable_mods() {
+        let (ordered, diagnostics) = resolve(vec![
+            create_mod(
+                "id = \"a\"\nversion = \"1.0.0\"\n[dependencies]\nb = \"^2\"",
+                &[],
+            ),
+            create_mod(
+                "id = \"b\"\nversion = \"1.0.0\"\n[dependencies]\nmissing = \"*\"",
+                &[],
+            ),
+            create_mod(
+                "id = \"c\"\nversion = \"1.0.0\"\n[dependencies]\nd = \"*\"",
+                &[],
+            ),
+            create_mod(
+                "id = \"d\"\nversion = \"1.0.0\"\n[dependencies]\nc = \"*\"",
+                &[],
+            ),
+        ]);
+
+        assert!(ordered.is_empty());
+        assert!(matches!(
+            &diagnostics[0],
+            ModDiagnostic::IncompatibleDependency { id, .. } if id == "a"
+        ));
+        assert!(matches!(
+            &diagnostics[1],
+            ModDiagnostic::MissingDependency { id, .. } if id == "b"
+        ));
+        assert!(matches!(
+            &diagnostics[2],
+            ModDiagnostic::Cycle { ids } if ids == &["c", "d"]
+        ));
+    }
+
+    #[test]
+    fn test_cycles_only_report_their_own_mods() {
+        let (ordered, diagnostics) = resolve(vec![
+            create_mod(
+                "id = \"a\"\nversion = \"1.0.0\"\n[load_order]\nafter = [\"b\"]",
+                &[],
+            ),
+            create_mod(
+                "id = \"b\"\nversion = \"1.0.0\"\n[load_order]\nafter = [\"a\"]",
+                &[],
+            ),
+            create_mod(
+                "id = \"c\"\nversion = \"1.0.0\"\n[dependencies]\nb = \"*\"",
+                &[],
+            ),
+            create_mod(
+                "id = \"d\"\nversion = \"1.0.0\"\n[load_order]\nafter = [\"c\"]",
+                &[],
+            ),
+            create_mod("id = \"e\"\nversion = \"1.0.0\"", &[]),
+        ]);
+
+        assert_eq!(ids(&ordered), ["e"]);
+        assert_eq!(
+            diagnostics,
+            [
+                ModDiagnostic::Cycle {
+                    ids: vec!["a".to_string(), "b".to_string()],
+                },
+                // Mods loading after a cycle only wait on it
+                ModDiagnostic::BlockedByCycle {
+                    id: "c".to_string(),
+                    cycle: vec!["a".to_string(), "b".to_string()],
+                },
+                ModDiagnostic::BlockedByCycle {
+                    id: "d".to_string(),
+                    cycle: vec!["a".to_string(), "b".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conflicts_ignore_declared_overrides() {
+        let (ordered, diagnostics) = resolve(vec![
+            create_mod("id = \"a\"\nversion = \"1.0.0\"", &["shared.png", "a.png"]),
+            create_mod("id = \"b\"\nversion = \"1.0.0\"", &["shared.png"]),
+            create_mod(
+                "id = \"c\"\nversion = \"1.0.0\"\n[load_order]\nafter = [\"a\"]",
+                &["a.png"],
+            ),
+        ]);
+
+        assert_eq!(ids(&ordered), vec!["a", "b", "c"]);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            &diagnostics[0],
+            ModDiagnostic::Conflict { winner, overridden, .. } if winner == "b" && overridden == &["a"]
+        ));
+    }
+}
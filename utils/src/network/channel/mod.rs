@@ -0,0 +1,291 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::network::packet::PacketMessage;
+
+/// How far ahead of the next expected message a reliable message may be before it is dropped
+///
+/// The sender will resend it later, this only bounds how much is buffered per channel
+const RECEIVE_WINDOW: u32 = 1024;
+
+/// Delivery guarantees a message is sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Channel {
+    /// Sent once, may be lost, duplicated or arrive out of order
+    Unreliable,
+
+    /// Resent until acknowledged, delivered once in any order
+    ReliableUnordered,
+
+    /// Resent until acknowledged, delivered once in the order it was sent
+    ReliableOrdered,
+}
+
+impl Channel {
+    /// Every channel in the order they are stored in a connection
+    pub const ALL: [Self; 3] = [
+        Self::Unreliable,
+        Self::ReliableUnordered,
+        Self::ReliableOrdered,
+    ];
+
+    /// Identifier used on the wire
+    pub(crate) fn code(self) -> u8 {
+        match self {
+            Self::Unreliable => 0,
+            Self::ReliableUnordered => 1,
+            Self::ReliableOrdered => 2,
+        }
+    }
+
+    /// Parses the identifier used on the wire
+    pub(crate) fn from_code(code: u8) -> Result<Self, String> {
+        match code {
+            0 => Ok(Self::Unreliable),
+            1 => Ok(Self::ReliableUnordered),
+            2 => Ok(Self::ReliableOrdered),
+            _ => Err(format!("Unknown channel {code}...")),
+        }
+    }
+
+    /// Index of the channel within [`Channel::ALL`]
+    pub(crate) fn index(self) -> usize {
+        usize::from(self.code())
+    }
+
+    /// Whether messages on this channel are resent until acknowledged
+    #[must_use]
+    pub fn is_reliable(self) -> bool {
+        !matches!(self, Self::Unreliable)
+    }
+}
+
+/// Reliable message waiting to be acknowledged
+#[derive(Debug, Clone)]
+struct PendingMessage {
+    /// Contents of the message
+    payload: Vec<u8>,
+
+    /// When the message was last sent, if ever
+    last_sent: Option<Instant>,
+}
+
+/// Outgoing half of a channel
+#[derive(Debug, Clone)]
+pub(crate) struct SendChannel {
+    /// Channel messages are sent on
+    channel: Channel,
+
+    /// Id given to the next message
+    next_id: u32,
+
+    /// Unreliable messages waiting to be sent
+    queued: VecDeque<(u32, Vec<u8>)>,
+
+    /// Reliable messages waiting to be acknowledged
+    pending: BTreeMap<u32, PendingMessage>,
+}
+
+impl SendChannel {
+    /// Creates the outgoing half of a channel
+    pub(crate) fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            next_id: 0,
+            queued: VecDeque::new(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Queues a message for sending
+    pub(crate) fn push(&mut self, payload: Vec<u8>) {
+        let id: u32 = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        if self.channel.is_reliable() {
+            self.pending.insert(
+                id,
+                PendingMessage {
+                    payload,
+                    last_sent: None,
+                },
+            );
+        } else {
+            self.queued.push_back((id, payload));
+        }
+    }
+
+    /// Marks a reliable message as received by the remote endpoint
+    pub(crate) fn acknowledge(&mut self, id: u32) {
+        self.pending.remove(&id);
+    }
+
+    /// Number of reliable messages which have not been acknowledged yet
+    pub(crate) fn unacknowledged(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Takes every message which should be sent now
+    ///
+    /// Unreliable messages are drained while reliable messages are kept until acknowledged
+    /// and only returned again once `resend_interval` has passed
+    pub(crate) fn take_due(
+        &mut self,
+        now: Instant,
+        resend_interval: Duration,
+    ) -> Vec<PacketMessage> {
+        let channel: Channel = self.channel;
+        let mut messages: Vec<PacketMessage> = self
+            .queued
+            .drain(..)
+            .map(|(id, payload)| PacketMessage {
+                channel,
+                id,
+                payload,
+            })
+            .collect();
+
+        for (id, message) in &mut self.pending {
+            let due: bool = message.last_sent.is_none_or(|last_sent| {
+                now.saturating_duration_since(last_sent) >= resend_interval
+            });
+
+            if due {
+                message.last_sent = Some(now);
+                messages.push(PacketMessage {
+                    channel,
+                    id: *id,
+                    payload: message.payload.clone(),
+                });
+            }
+        }
+
+        messages
+    }
+}
+
+/// Incoming half of a channel
+#[derive(Debug, Clone)]
+pub(crate) struct ReceiveChannel {
+    /// Channel messages are received on
+    channel: Channel,
+
+    /// Every reliable message before this id has been received
+    next_expected: u32,
+
+    /// Reliable messages received ahead of `next_expected`
+    ///
+    /// Ordered channels keep the payload until it can be delivered,
+    /// unordered channels only keep the id to filter out duplicates
+    received_ahead: BTreeMap<u32, Vec<u8>>,
+}
+
+impl ReceiveChannel {
+    /// Creates the incoming half of a channel
+    pub(crate) fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            next_expected: 0,
+            received_ahead: BTreeMap::new(),
+        }
+    }
+
+    /// Processes a received message and returns every payload which is ready for delivery
+    pub(crate) fn receive(&mut self, id: u32, payload: Vec<u8>) -> Vec<Vec<u8>> {
+        if !self.channel.is_reliable() {
+            return vec![payload];
+        }
+
+        // Wrapping distance handles ids rolling over
+        let distance: u32 = id.wrapping_sub(self.next_expected);
+        if distance >= RECEIVE_WINDOW || self.received_ahead.contains_key(&id) {
+            // Either a duplicate of something already delivered or too far ahead
+            return Vec::new();
+        }
+
+        let mut delivered: Vec<Vec<u8>> = Vec::new();
+        match self.channel {
+            Channel::ReliableOrdered => {
+                if distance != 0 {
+                    self.received_ahead.insert(id, payload);
+                    return delivered;
+                }
+
+                delivered.push(payload);
+                self.next_expected = self.next_expected.wrapping_add(1);
+                while let Some(payload) = self.received_ahead.remove(&self.next_expected) {
+                    delivered.push(payload);
+                    self.next_expected = self.next_expected.wrapping_add(1);
+                }
+            }
+            Channel::ReliableUnordered => {
+                delivered.push(payload);
+
+                if distance != 0 {
+                    self.received_ahead.insert(id, Vec::new());
+                    return delivered;
+                }
+
+                self.next_expected = self.next_expected.wrapping_add(1);
+                while self.received_ahead.remove(&self.next_expected).is_some() {
+                    self.next_expected = self.next_expected.wrapping_add(1);
+                }
+            }
+            Channel::Unreliable => unreachable!("Unreliable messages are delivered immediately"),
+        }
+
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Channel, ReceiveChannel, SendChannel};
+
+    #[test]
+    fn test_ordered_delivery() {
+        let mut channel: ReceiveChannel = ReceiveChannel::new(Channel::ReliableOrdered);
+
+        assert!(channel.receive(1, vec![1]).is_empty());
+        assert!(channel.receive(2, vec![2]).is_empty());
+        assert_eq!(channel.receive(0, vec![0]), vec![vec![0], vec![1], vec![2]]);
+
+        // Duplicates are dropped
+        assert!(channel.receive(1, vec![1]).is_empty());
+        assert!(channel.receive(2, vec![2]).is_empty());
+    }
+
+    #[test]
+    fn test_unordered_delivery() {
+        let mut channel: ReceiveChannel = ReceiveChannel::new(Channel::ReliableUnordered);
+
+        assert_eq!(channel.receive(2, vec![2]), vec![vec![2]]);
+        assert_eq!(channel.receive(0, vec![0]), vec![vec![0]]);
+        assert!(channel.receive(2, vec![2]).is_empty());
+        assert_eq!(channel.receive(1, vec![1]), vec![vec![1]]);
+        assert!(channel.receive(0, vec![0]).is_empty());
+    }
+
+    #[test]
+    fn test_resend() {
+        let start: Instant = Instant::now();
+        let interval: Duration = Duration::from_millis(100);
+        let mut channel: SendChannel = SendChannel::new(Channel::ReliableOrdered);
+
+        channel.push(vec![0]);
+        channel.push(vec![1]);
+        assert_eq!(channel.take_due(start, interval).len(), 2);
+        assert!(channel.take_due(start + interval / 2, interval).is_empty());
+
+        // Only the unacknowledged message is resent
+        channel.acknowledge(0);
+        let resent = channel.take_due(start + interval, interval);
+        assert_eq!(resent.len(), 1);
+        assert_eq!(resent[0].id, 1);
+        assert_eq!(channel.unacknowledged(), 1);
+    }
+}
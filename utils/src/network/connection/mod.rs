@@ -0,0 +1,351 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    time::{Duration, Instant},
+};
+
+use crate::network::channel::{Channel, ReceiveChannel, SendChannel};
+use crate::network::packet::{Packet, PacketMessage, MAX_PACKET_SIZE};
+
+/// Maximum number of sent packets remembered while waiting for acknowledgements
+///
+/// Reliable messages in forgotten packets are simply resent
+const SENT_PACKETS_LIMIT: usize = 1024;
+
+/// Why a connection was closed or refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DisconnectReason {
+    /// The remote endpoint asked to disconnect
+    Requested,
+
+    /// Nothing was received from the remote endpoint for too long
+    TimedOut,
+
+    /// Both endpoints are running different versions of the engine
+    VersionMismatch,
+
+    /// The server cannot accept any more connections
+    ServerFull,
+
+    /// The server removed the client
+    Kicked,
+
+    /// The remote endpoint is shutting down
+    Shutdown,
+
+    /// The remote endpoint sent a reason this version does not know about
+    Unknown,
+}
+
+impl DisconnectReason {
+    /// Identifier used on the wire
+    pub(crate) fn code(self) -> u8 {
+        match self {
+            Self::Requested => 0,
+            Self::TimedOut => 1,
+            Self::VersionMismatch => 2,
+            Self::ServerFull => 3,
+            Self::Kicked => 4,
+            Self::Shutdown => 5,
+            Self::Unknown => u8::MAX,
+        }
+    }
+
+    /// Parses the identifier used on the wire
+    pub(crate) fn from_code(code: u8) -> Self {
+        match code {
+            0 => Self::Requested,
+            1 => Self::TimedOut,
+            2 => Self::VersionMismatch,
+            3 => Self::ServerFull,
+            4 => Self::Kicked,
+            5 => Self::Shutdown,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl Display for DisconnectReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason: &str = match self {
+            Self::Requested => "disconnect was requested",
+            Self::TimedOut => "connection timed out",
+            Self::VersionMismatch => "engine versions do not match",
+            Self::ServerFull => "server is full",
+            Self::Kicked => "kicked from server",
+            Self::Shutdown => "remote endpoint shut down",
+            Self::Unknown => "unknown reason",
+        };
+
+        write!(f, "{reason}")
+    }
+}
+
+/// Where a connection is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConnectionState {
+    /// Waiting for the remote endpoint to accept the connection
+    Connecting,
+
+    /// Both endpoints can exchange messages
+    Connected,
+}
+
+/// State of a single connection with a remote endpoint
+#[derive(Debug, Clone)]
+pub(crate) struct Connection {
+    /// Where the connection is in its lifecycle
+    pub(crate) state: ConnectionState,
+
+    /// When a packet was last received from the remote endpoint
+    pub(crate) last_received: Instant,
+
+    /// When a packet was last sent to the remote endpoint
+    pub(crate) last_sent: Option<Instant>,
+
+    /// Sequence number given to the next payload packet
+    local_sequence: u32,
+
+    /// Most recent sequence number received from the remote endpoint
+    remote_sequence: Option<u32>,
+
+    /// Bitfield of the 32 sequence numbers received before `remote_sequence`
+    received_bits: u32,
+
+    /// Whether a packet was received which has not been acknowledged yet
+    needs_ack: bool,
+
+    /// Outgoing halves of each channel
+    send_channels: [SendChannel; 3],
+
+    /// Incoming halves of each channel
+    receive_channels: [ReceiveChannel; 3],
+
+    /// Reliable messages contained in each sent packet which has not been acknowledged
+    sent_packets: BTreeMap<u32, Vec<(Channel, u32)>>,
+}
+
+impl Connection {
+    /// Creates a new connection
+    pub(crate) fn new(state: ConnectionState, now: Instant) -> Self {
+        Self {
+            state,
+            last_received: now,
+            last_sent: None,
+            local_sequence: 0,
+            remote_sequence: None,
+            received_bits: 0,
+            needs_ack: false,
+            send_channels: Channel::ALL.map(SendChannel::new),
+            receive_channels: Channel::ALL.map(ReceiveChannel::new),
+            sent_packets: BTreeMap::new(),
+        }
+    }
+
+    /// Queues a message for sending on the requested channel
+    pub(crate) fn send(&mut self, channel: Channel, payload: Vec<u8>) {
+        self.send_channels[channel.index()].push(payload);
+    }
+
+    /// Number of reliable messages which have not been acknowledged yet
+    pub(crate) fn unacknowledged(&self) -> usize {
+        self.send_channels
+            .iter()
+            .map(SendChannel::unacknowledged)
+            .sum()
+    }
+
+    /// Processes a received payload packet and returns the messages ready for delivery
+    ///
+    /// Returns nothing if the packet is a duplicate or too old to track
+    pub(crate) fn receive_payload(
+        &mut self,
+        sequence: u32,
+        ack: u32,
+        ack_bits: u32,
+        messages: Vec<PacketMessage>,
+        now: Instant,
+    ) -> Vec<(Channel, Vec<u8>)> {
+        if !self.track_received(sequence) {
+            return Vec::new();
+        }
+
+        self.last_received = now;
+        self.needs_ack = true;
+        self.process_acks(ack, ack_bits);
+
+        let mut delivered: Vec<(Channel, Vec<u8>)> = Vec::new();
+        for message in messages {
+            let channel: Channel = message.channel;
+
+            for payload in
+                self.receive_channels[channel.index()].receive(message.id, message.payload)
+            {
+                delivered.push((channel, payload));
+            }
+        }
+
+        delivered
+    }
+
+    /// Records a received sequence number and returns false if it was already received
+    fn track_received(&mut self, sequence: u32) -> bool {
+        let Some(remote_sequence) = self.remote_sequence else {
+            self.remote_sequence = Some(sequence);
+            return true;
+        };
+
+        // Reinterpreting as signed handles sequence numbers rolling over
+        #[allow(clippy::cast_possible_wrap)]
+        let distance: i32 = sequence.wrapping_sub(remote_sequence) as i32;
+
+        match distance {
+            0 => false,
+            1.. => {
+                let shift: u32 = distance.unsigned_abs();
+
+                self.received_bits = self.received_bits.checked_shl(shift).unwrap_or(0)
+                    | 1_u32.checked_shl(shift - 1).unwrap_or(0);
+                self.remote_sequence = Some(sequence);
+
+                true
+            }
+            _ => {
+                let Some(bit) = 1_u32.checked_shl(distance.unsigned_abs() - 1) else {
+                    // Too old to know whether it was already received
+                    return false;
+                };
+
+                if self.received_bits & bit != 0 {
+                    return false;
+                }
+
+                self.received_bits |= bit;
+                true
+            }
+        }
+    }
+
+    /// Acknowledges every reliable message in the packets the remote endpoint received
+    fn process_acks(&mut self, ack: u32, ack_bits: u32) {
+        self.acknowledge_packet(ack);
+
+        for bit in 0..32 {
+            if ack_bits & (1 << bit) != 0 {
+                self.acknowledge_packet(ack.wrapping_sub(bit + 1));
+            }
+        }
+    }
+
+    /// Acknowledges every reliable message in a single sent packet
+    fn acknowledge_packet(&mut self, sequence: u32) {
+        if let Some(messages) = self.sent_packets.remove(&sequence) {
+            for (channel, id) in messages {
+                self.send_channels[channel.index()].acknowledge(id);
+            }
+        }
+    }
+
+    /// Builds the payload packets which should be sent now
+    ///
+    /// Sends a packet without messages if the remote endpoint is owed an acknowledgement
+    /// or nothing was sent for `keep_alive_interval`
+    pub(crate) fn take_packets(
+        &mut self,
+        now: Instant,
+        resend_interval: Duration,
+        keep_alive_interval: Duration,
+    ) -> Vec<Packet> {
+        let mut messages: Vec<PacketMessage> = Vec::new();
+        for channel in &mut self.send_channels {
+            messages.extend(channel.take_due(now, resend_interval));
+        }
+
+        let mut packets: Vec<Packet> = Vec::new();
+        let mut current: Vec<PacketMessage> = Vec::new();
+        let mut size: usize = Packet::payload_header_size();
+        for message in messages {
+            let full: bool = size + message.encoded_size() > MAX_PACKET_SIZE
+                || current.len() == usize::from(u8::MAX);
+
+            if full && !current.is_empty() {
+                let messages: Vec<PacketMessage> = std::mem::take(&mut current);
+                packets.push(self.payload_packet(messages));
+                size = Packet::payload_header_size();
+            }
+
+            size += message.encoded_size();
+            current.push(message);
+        }
+
+        let idle: bool = self.last_sent.is_none_or(|last_sent| {
+            now.saturating_duration_since(last_sent) >= keep_alive_interval
+        });
+
+        if !current.is_empty() || (packets.is_empty() && (self.needs_ack || idle)) {
+            packets.push(self.payload_packet(current));
+        }
+
+        if !packets.is_empty() {
+            self.last_sent = Some(now);
+            self.needs_ack = false;
+        }
+
+        packets
+    }
+
+    /// Wraps messages in a payload packet and remembers which reliable messages it contains
+    fn payload_packet(&mut self, messages: Vec<PacketMessage>) -> Packet {
+        let sequence: u32 = self.local_sequence;
+        self.local_sequence = self.local_sequence.wrapping_add(1);
+
+        let reliable: Vec<(Channel, u32)> = messages
+            .iter()
+            .filter(|message| message.channel.is_reliable())
+            .map(|message| (message.channel, message.id))
+            .collect();
+
+        if !reliable.is_empty() {
+            self.sent_packets.insert(sequence, reliable);
+
+            while self.sent_packets.len() > SENT_PACKETS_LIMIT {
+                self.sent_packets.pop_first();
+            }
+        }
+
+        Packet::Payload {
+            sequence,
+            // Nothing has been received yet, so acknowledge a sequence number which was never sent
+            ack: self.remote_sequence.unwrap_or(u32::MAX),
+            ack_bits: self.received_bits,
+            messages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::{Connection, ConnectionState};
+
+    #[test]
+    fn test_track_received() {
+        let mut connection: Connection =
+            Connection::new(ConnectionState::Connected, Instant::now());
+
+        assert!(connection.track_received(5));
+        assert!(!connection.track_received(5));
+        assert!(connection.track_received(7));
+        assert_eq!(connection.received_bits, 0b10);
+
+        // Late packets are accepted once
+        assert!(connection.track_received(6));
+        assert!(!connection.track_received(6));
+        assert_eq!(connection.received_bits, 0b11);
+
+        // Packets older than the ack window are dropped
+        assert!(connection.track_received(100));
+        assert!(!connection.track_received(7));
+    }
+}
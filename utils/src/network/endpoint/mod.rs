@@ -0,0 +1,733 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::ErrorKind,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use crate::network::channel::Channel;
+use crate::network::connection::{Connection, ConnectionState, DisconnectReason};
+use crate::network::packet::{Packet, MAX_MESSAGE_SIZE, MAX_PACKET_SIZE};
+
+/// Number of times a disconnect packet is sent since it is never acknowledged
+const DISCONNECT_REDUNDANCY: usize = 3;
+
+/// Something which can send and receive datagrams
+pub trait Socket {
+    /// Sends a datagram to the requested address
+    ///
+    /// # Errors
+    ///
+    /// Errors if the datagram could not be sent
+    fn send_to(&mut self, bytes: &[u8], address: SocketAddr) -> std::io::Result<()>;
+
+    /// Receives a single datagram without blocking
+    ///
+    /// Returns `None` if no datagram is waiting
+    ///
+    /// # Errors
+    ///
+    /// Errors if receiving failed
+    fn receive_from(&mut self, buffer: &mut [u8]) -> std::io::Result<Option<(usize, SocketAddr)>>;
+
+    /// Address the socket is bound to
+    ///
+    /// # Errors
+    ///
+    /// Errors if the address cannot be determined
+    fn local_address(&self) -> std::io::Result<SocketAddr>;
+}
+
+impl Socket for UdpSocket {
+    fn send_to(&mut self, bytes: &[u8], address: SocketAddr) -> std::io::Result<()> {
+        UdpSocket::send_to(self, bytes, address).map(|_| ())
+    }
+
+    fn receive_from(&mut self, buffer: &mut [u8]) -> std::io::Result<Option<(usize, SocketAddr)>> {
+        match self.recv_from(buffer) {
+            Ok(received) => Ok(Some(received)),
+            Err(error) if error.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn local_address(&self) -> std::io::Result<SocketAddr> {
+        self.local_addr()
+    }
+}
+
+impl<S: Socket + ?Sized> Socket for Box<S> {
+    fn send_to(&mut self, bytes: &[u8], address: SocketAddr) -> std::io::Result<()> {
+        (**self).send_to(bytes, address)
+    }
+
+    fn receive_from(&mut self, buffer: &mut [u8]) -> std::io::Result<Option<(usize, SocketAddr)>> {
+        (**self).receive_from(buffer)
+    }
+
+    fn local_address(&self) -> std::io::Result<SocketAddr> {
+        (**self).local_address()
+    }
+}
+
+/// Settings for an endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointConfig {
+    /// Engine version which must match the remote endpoint's version
+    pub version: String,
+
+    /// Whether connection requests from remote endpoints are accepted
+    pub accept_connections: bool,
+
+    /// Maximum number of connections accepted at once
+    pub max_connections: usize,
+
+    /// How long to wait without receiving anything before dropping a connection
+    pub timeout: Duration,
+
+    /// How long to wait for an acknowledgement before resending
+    pub resend_interval: Duration,
+
+    /// How long to wait before sending an empty packet to keep the connection alive
+    pub keep_alive_interval: Duration,
+}
+
+impl Default for EndpointConfig {
+    fn default() -> Self {
+        Self {
+            version: crate::build::build_info().crate_info.version.to_string(),
+            accept_connections: false,
+            max_connections: 32,
+            timeout: Duration::from_secs(10),
+            resend_interval: Duration::from_millis(100),
+            keep_alive_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Events produced by an endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkEvent {
+    /// A connection was established
+    Connected(SocketAddr),
+
+    /// A connection was closed or refused
+    Disconnected(SocketAddr, DisconnectReason),
+
+    /// A message was received
+    Message {
+        /// Address the message was received from
+        address: SocketAddr,
+
+        /// Channel the message was sent on
+        channel: Channel,
+
+        /// Contents of the message
+        payload: Vec<u8>,
+    },
+}
+
+/// Sends and receives messages for any number of connections over a socket
+#[derive(Debug)]
+pub struct Endpoint<S: Socket = UdpSocket> {
+    /// Socket datagrams are sent and received on
+    socket: S,
+
+    /// Settings for the endpoint
+    config: EndpointConfig,
+
+    /// Connections keyed by the remote address
+    connections: HashMap<SocketAddr, Connection>,
+
+    /// Events waiting to be polled
+    events: VecDeque<NetworkEvent>,
+}
+
+impl Endpoint<UdpSocket> {
+    /// Binds a non-blocking UDP socket to the requested address
+    ///
+    /// # Errors
+    ///
+    /// Errors if the socket cannot be bound
+    pub fn bind<A: ToSocketAddrs>(address: A, config: EndpointConfig) -> Result<Self, String> {
+        let socket: UdpSocket =
+            UdpSocket::bind(address).map_err(|error| format!("Could not bind socket: {error}"))?;
+
+        socket
+            .set_nonblocking(true)
+            .map_err(|error| format!("Could not make socket non-blocking: {error}"))?;
+
+        Ok(Self::new(socket, config))
+    }
+}
+
+impl<S: Socket> Endpoint<S> {
+    /// Creates an endpoint over an existing socket
+    ///
+    /// The socket must not block when receiving
+    pub fn new(socket: S, config: EndpointConfig) -> Self {
+        Self {
+            socket,
+            config,
+            connections: HashMap::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Address the endpoint is bound to
+    ///
+    /// # Errors
+    ///
+    /// Errors if the address cannot be determined
+    pub fn local_address(&self) -> Result<SocketAddr, String> {
+        self.socket
+            .local_address()
+            .map_err(|error| format!("Could not get local address: {error}"))
+    }
+
+    /// Starts connecting to a remote endpoint
+    ///
+    /// A [`NetworkEvent::Connected`] or [`NetworkEvent::Disconnected`] event is produced once the handshake finishes
+    pub fn connect(&mut self, address: SocketAddr, now: Instant) {
+        if self.connections.contains_key(&address) {
+            return;
+        }
+
+        debug!("Connecting to {address}...");
+        self.connections
+            .insert(address, Connection::new(ConnectionState::Connecting, now));
+    }
+
+    /// Queues a message for a connected remote endpoint
+    ///
+    /// # Errors
+    ///
+    /// Errors if the remote endpoint is not connected or the message is larger than [`MAX_MESSAGE_SIZE`]
+    pub fn send(
+        &mut self,
+        address: SocketAddr,
+        channel: Channel,
+        payload: Vec<u8>,
+    ) -> Result<(), String> {
+        if payload.len() > MAX_MESSAGE_SIZE {
+            return Err(format!(
+                "Message of {} bytes is larger than the maximum of {MAX_MESSAGE_SIZE} bytes...",
+                payload.len()
+            ));
+        }
+
+        match self.connections.get_mut(&address) {
+            Some(connection) if connection.state == ConnectionState::Connected => {
+                connection.send(channel, payload);
+                Ok(())
+            }
+            _ => Err(format!("Not connected to {address}...")),
+        }
+    }
+
+    /// Queues a message for every connected remote endpoint
+    ///
+    /// # Errors
+    ///
+    /// Errors if the message is larger than [`MAX_MESSAGE_SIZE`]
+    pub fn broadcast(&mut self, channel: Channel, payload: &[u8]) -> Result<(), String> {
+        for address in self.connected_addresses() {
+            self.send(address, channel, payload.to_vec())?;
+        }
+
+        Ok(())
+    }
+
+    /// Closes a connection and tells the remote endpoint why
+    pub fn disconnect(&mut self, address: SocketAddr, reason: DisconnectReason) {
+        if self.connections.remove(&address).is_none() {
+            return;
+        }
+
+        debug!("Disconnecting from {address} ({reason})...");
+        for _ in 0..DISCONNECT_REDUNDANCY {
+            self.send_packet(address, &Packet::Disconnect { reason });
+        }
+
+        self.events
+            .push_back(NetworkEvent::Disconnected(address, reason));
+    }
+
+    /// Closes every connection
+    pub fn disconnect_all(&mut self, reason: DisconnectReason) {
+        let addresses: Vec<SocketAddr> = self.connections.keys().copied().collect();
+
+        for address in addresses {
+            self.disconnect(address, reason);
+        }
+    }
+
+    /// Addresses of every established connection
+    #[must_use]
+    pub fn connected_addresses(&self) -> Vec<SocketAddr> {
+        self.connections
+            .iter()
+            .filter(|(_, connection)| connection.state == ConnectionState::Connected)
+            .map(|(address, _)| *address)
+            .collect()
+    }
+
+    /// Whether a connection to the remote endpoint is established
+    #[must_use]
+    pub fn is_connected(&self, address: SocketAddr) -> bool {
+        self.connections
+            .get(&address)
+            .is_some_and(|connection| connection.state == ConnectionState::Connected)
+    }
+
+    /// Number of reliable messages to the remote endpoint which have not been acknowledged yet
+    #[must_use]
+    pub fn unacknowledged(&self, address: SocketAddr) -> usize {
+        self.connections
+            .get(&address)
+            .map_or(0, Connection::unacknowledged)
+    }
+
+    /// Retrieves the next event if any
+    pub fn poll_event(&mut self) -> Option<NetworkEvent> {
+        self.events.pop_front()
+    }
+
+    /// Receives waiting datagrams, then sends handshakes, messages, acknowledgements and resends
+    ///
+    /// Should be called regularly, such as once per tick
+    pub fn update(&mut self, now: Instant) {
+        self.receive_packets(now);
+        self.drop_timed_out_connections(now);
+        self.send_packets(now);
+    }
+
+    /// Sends queued messages straight away without receiving anything
+    ///
+    /// Used to get messages out before closing a connection, which drops anything still queued
+    pub fn flush(&mut self, now: Instant) {
+        self.send_packets(now);
+    }
+
+    /// Processes every datagram waiting on the socket
+    fn receive_packets(&mut self, now: Instant) {
+        let mut buffer: [u8; MAX_PACKET_SIZE] = [0; MAX_PACKET_SIZE];
+
+        loop {
+            match self.socket.receive_from(&mut buffer) {
+                Ok(Some((length, address))) => match Packet::decode(&buffer[..length]) {
+                    Ok(packet) => self.handle_packet(address, packet, now),
+                    Err(error) => trace!("Dropping invalid packet from {address}: {error}"),
+                },
+                Ok(None) => break,
+                // Windows reports ICMP port unreachable messages as a reset on UDP sockets
+                Err(error) if error.kind() == ErrorKind::ConnectionReset => {}
+                Err(error) => {
+                    warn!("Could not receive packet: {error}");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Processes a single received packet
+    fn handle_packet(&mut self, address: SocketAddr, packet: Packet, now: Instant) {
+        match packet {
+            Packet::ConnectRequest { version } => {
+                self.handle_connect_request(address, &version, now);
+            }
+            Packet::ConnectAccepted => {
+                if let Some(connection) = self.connections.get_mut(&address) {
+                    connection.last_received = now;
+
+                    if connection.state == ConnectionState::Connecting {
+                        connection.state = ConnectionState::Connected;
+                        debug!("Connected to {address}...");
+                        self.events.push_back(NetworkEvent::Connected(address));
+                    }
+                }
+            }
+            Packet::ConnectDenied { reason } => {
+                let connecting: bool = self
+                    .connections
+                    .get(&address)
+                    .is_some_and(|connection| connection.state == ConnectionState::Connecting);
+
+                if connecting {
+                    self.connections.remove(&address);
+                    warn!("Connection to {address} was denied ({reason})...");
+                    self.events
+                        .push_back(NetworkEvent::Disconnected(address, reason));
+                }
+            }
+            Packet::Payload {
+                sequence,
+                ack,
+                ack_bits,
+                messages,
+            } => {
+                let Some(connection) = self.connections.get_mut(&address) else {
+                    return;
+                };
+
+                // The accept packet was lost, but the remote endpoint is already sending
+                if connection.state == ConnectionState::Connecting {
+                    connection.state = ConnectionState::Connected;
+                    debug!("Connected to {address}...");
+                    self.events.push_back(NetworkEvent::Connected(address));
+                }
+
+                for (channel, payload) in
+                    connection.receive_payload(sequence, ack, ack_bits, messages, now)
+                {
+                    self.events.push_back(NetworkEvent::Message {
+                        address,
+                        channel,
+                        payload,
+                    });
+                }
+            }
+            Packet::Disconnect { reason } => {
+                if self.connections.remove(&address).is_some() {
+                    debug!("{address} disconnected ({reason})...");
+                    self.events
+                        .push_back(NetworkEvent::Disconnected(address, reason));
+                }
+            }
+        }
+    }
+
+    /// Accepts or denies a connection request
+    fn handle_connect_request(&mut self, address: SocketAddr, version: &str, now: Instant) {
+        if let Some(connection) = self.connections.get(&address) {
+            // The accept packet was lost, so send it again
+            if connection.state == ConnectionState::Connected {
+                self.send_packet(address, &Packet::ConnectAccepted);
+            }
+
+            return;
+        }
+
+        if !self.config.accept_connections {
+            return;
+        }
+
+        if version != self.config.version {
+            warn!(
+                "Denying connection from {address} running version {version} instead of {}...",
+                self.config.version
+            );
+
+            let reason: DisconnectReason = DisconnectReason::VersionMismatch;
+            self.send_packet(address, &Packet::ConnectDenied { reason });
+            return;
+        }
+
+        if self.connections.len() >= self.config.max_connections {
+            warn!("Denying connection from {address} since the server is full...");
+
+            let reason: DisconnectReason = DisconnectReason::ServerFull;
+            self.send_packet(address, &Packet::ConnectDenied { reason });
+            return;
+        }
+
+        debug!("Accepted connection from {address}...");
+        self.connections
+            .insert(address, Connection::new(ConnectionState::Connected, now));
+        self.send_packet(address, &Packet::ConnectAccepted);
+        self.events.push_back(NetworkEvent::Connected(address));
+    }
+
+    /// Removes connections which have not received anything for too long
+    fn drop_timed_out_connections(&mut self, now: Instant) {
+        let timeout: Duration = self.config.timeout;
+        let timed_out: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(_, connection)| {
+                now.saturating_duration_since(connection.last_received) >= timeout
+            })
+            .map(|(address, _)| *address)
+            .collect();
+
+        for address in timed_out {
+            self.connections.remove(&address);
+
+            let reason: DisconnectReason = DisconnectReason::TimedOut;
+            warn!("Connection to {address} timed out...");
+            self.events
+                .push_back(NetworkEvent::Disconnected(address, reason));
+        }
+    }
+
+    /// Sends connection requests and payload packets for every connection
+    fn send_packets(&mut self, now: Instant) {
+        let mut outgoing: Vec<(SocketAddr, Packet)> = Vec::new();
+
+        for (address, connection) in &mut self.connections {
+            match connection.state {
+                ConnectionState::Connecting => {
+                    let due: bool = connection.last_sent.is_none_or(|last_sent| {
+                        now.saturating_duration_since(last_sent) >= self.config.resend_interval
+                    });
+
+                    if due {
+                        connection.last_sent = Some(now);
+                        outgoing.push((
+                            *address,
+                            Packet::ConnectRequest {
+                                version: self.config.version.clone(),
+                            },
+                        ));
+                    }
+                }
+                ConnectionState::Connected => {
+                    for packet in connection.take_packets(
+                        now,
+                        self.config.resend_interval,
+                        self.config.keep_alive_interval,
+                    ) {
+                        outgoing.push((*address, packet));
+                    }
+                }
+            }
+        }
+
+        for (address, packet) in outgoing {
+            self.send_packet(address, &packet);
+        }
+    }
+
+    /// Encodes and sends a single packet
+    fn send_packet(&mut self, address: SocketAddr, packet: &Packet) {
+        let bytes: Vec<u8> = match packet.encode() {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                error!("Could not encode packet for {address}: {error}");
+                return;
+            }
+        };
+
+        if let Err(error) = self.socket.send_to(&bytes, address) {
+            trace!("Could not send packet to {address}: {error}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{SocketAddr, UdpSocket},
+        time::{Duration, Instant},
+    };
+
+    use super::{Endpoint, EndpointConfig, NetworkEvent, Socket};
+    use crate::network::{Channel, DisconnectReason};
+
+    /// Socket which drops every other datagram it sends
+    struct LossySocket {
+        /// Socket which actually sends the datagrams
+        inner: UdpSocket,
+
+        /// Number of datagrams sent so far
+        sent: usize,
+    }
+
+    impl Socket for LossySocket {
+        fn send_to(&mut self, bytes: &[u8], address: SocketAddr) -> std::io::Result<()> {
+            self.sent += 1;
+            if self.sent.is_multiple_of(2) {
+                return Ok(());
+            }
+
+            Socket::send_to(&mut self.inner, bytes, address)
+        }
+
+        fn receive_from(
+            &mut self,
+            buffer: &mut [u8],
+        ) -> std::io::Result<Option<(usize, SocketAddr)>> {
+            self.inner.receive_from(buffer)
+        }
+
+        fn local_address(&self) -> std::io::Result<SocketAddr> {
+            self.inner.local_address()
+        }
+    }
+
+    /// Creates a config which does not depend on the build info
+    fn config(version: &str, accept_connections: bool) -> EndpointConfig {
+        EndpointConfig {
+            version: version.to_string(),
+            accept_connections,
+            max_connections: 1,
+            timeout: Duration::from_secs(10),
+            resend_interval: Duration::from_millis(10),
+            keep_alive_interval: Duration::from_millis(50),
+        }
+    }
+
+    /// Whether both endpoints reported the connection
+    fn connected(events: &[NetworkEvent]) -> bool {
+        events
+            .iter()
+            .filter(|event| matches!(event, NetworkEvent::Connected(_)))
+            .count()
+            == 2
+    }
+
+    /// Updates both endpoints until the condition is met, collecting every event
+    fn pump<A: Socket, B: Socket>(
+        server: &mut Endpoint<A>,
+        client: &mut Endpoint<B>,
+        events: &mut Vec<NetworkEvent>,
+        condition: impl Fn(&[NetworkEvent]) -> bool,
+    ) {
+        let deadline: Instant = Instant::now() + Duration::from_secs(5);
+
+        while !condition(events) {
+            assert!(Instant::now() < deadline, "Timed out, events: {events:?}");
+
+            server.update(Instant::now());
+            client.update(Instant::now());
+            while let Some(event) = server.poll_event() {
+                events.push(event);
+            }
+            while let Some(event) = client.poll_event() {
+                events.push(event);
+            }
+
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_loopback_channels() {
+        let mut server: Endpoint = Endpoint::bind("127.0.0.1:0", config("1.0.0", true)).unwrap();
+        let mut client: Endpoint = Endpoint::bind("127.0.0.1:0", config("1.0.0", false)).unwrap();
+        let server_address: SocketAddr = server.local_address().unwrap();
+        let client_address: SocketAddr = client.local_address().unwrap();
+
+        let mut events: Vec<NetworkEvent> = Vec::new();
+        client.connect(server_address, Instant::now());
+        pump(&mut server, &mut client, &mut events, connected);
+        assert!(client.is_connected(server_address));
+        assert!(server.is_connected(client_address));
+
+        for index in 0..10_u8 {
+            client
+                .send(server_address, Channel::ReliableOrdered, vec![index])
+                .unwrap();
+            client
+                .send(server_address, Channel::ReliableUnordered, vec![index])
+                .unwrap();
+            client
+                .send(server_address, Channel::Unreliable, vec![index])
+                .unwrap();
+        }
+
+        events.clear();
+        pump(&mut server, &mut client, &mut events, |events| {
+            events.len() >= 30
+        });
+
+        let ordered: Vec<u8> = events
+            .iter()
+            .filter_map(|event| match event {
+                NetworkEvent::Message {
+                    channel: Channel::ReliableOrdered,
+                    payload,
+                    ..
+                } => Some(payload[0]),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ordered, (0..10).collect::<Vec<u8>>());
+
+        client.disconnect(server_address, DisconnectReason::Requested);
+        events.clear();
+        pump(&mut server, &mut client, &mut events, |events| {
+            events.contains(&NetworkEvent::Disconnected(
+                client_address,
+                DisconnectReason::Requested,
+            ))
+        });
+        assert!(!server.is_connected(client_address));
+    }
+
+    #[test]
+    fn test_reliable_over_lossy_socket() {
+        let mut server: Endpoint = Endpoint::bind("127.0.0.1:0", config("1.0.0", true)).unwrap();
+        let inner: UdpSocket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        inner.set_nonblocking(true).unwrap();
+        let mut client: Endpoint<LossySocket> =
+            Endpoint::new(LossySocket { inner, sent: 0 }, config("1.0.0", false));
+        let server_address: SocketAddr = server.local_address().unwrap();
+
+        let mut events: Vec<NetworkEvent> = Vec::new();
+        client.connect(server_address, Instant::now());
+        pump(&mut server, &mut client, &mut events, connected);
+
+        for index in 0..20_u8 {
+            client
+                .send(server_address, Channel::ReliableOrdered, vec![index])
+                .unwrap();
+        }
+
+        events.clear();
+        pump(&mut server, &mut client, &mut events, |events| {
+            events.len() >= 20
+        });
+
+        let received: Vec<u8> = events
+            .iter()
+            .filter_map(|event| match event {
+                NetworkEvent::Message { payload, .. } => Some(payload[0]),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(received, (0..20).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        let mut server: Endpoint = Endpoint::bind("127.0.0.1:0", config("1.0.0", true)).unwrap();
+        let mut client: Endpoint = Endpoint::bind("127.0.0.1:0", config("2.0.0", false)).unwrap();
+        let server_address: SocketAddr = server.local_address().unwrap();
+
+        let mut events: Vec<NetworkEvent> = Vec::new();
+        client.connect(server_address, Instant::now());
+        pump(&mut server, &mut client, &mut events, |events| {
+            !events.is_empty()
+        });
+
+        assert_eq!(
+            events,
+            vec![NetworkEvent::Disconnected(
+                server_address,
+                DisconnectReason::VersionMismatch
+            )]
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut client: Endpoint = Endpoint::bind("127.0.0.1:0", config("1.0.0", false)).unwrap();
+
+        // Nothing is listening on this address
+        let address: SocketAddr = "127.0.0.1:9".parse().unwrap();
+        let start: Instant = Instant::now();
+        client.connect(address, start);
+        client.update(start);
+        assert_eq!(client.poll_event(), None);
+
+        client.update(start + Duration::from_secs(10));
+        assert_eq!(
+            client.poll_event(),
+            Some(NetworkEvent::Disconnected(
+                address,
+                DisconnectReason::TimedOut
+            ))
+        );
+    }
+}
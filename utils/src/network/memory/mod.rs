@@ -0,0 +1,148 @@
+use std::{
+    io::ErrorKind,
+    net::{Ipv4Addr, SocketAddr},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+};
+
+use crate::network::endpoint::Socket;
+
+/// Made up address of the server half of a memory socket pair
+const SERVER_ADDRESS: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::LOCALHOST), 1);
+
+/// Made up address of the client half of a memory socket pair
+const CLIENT_ADDRESS: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::LOCALHOST), 2);
+
+/// Socket which passes datagrams to its peer over an in-process channel
+///
+/// Used to connect the client to a server running on another thread of the same process
+#[derive(Debug)]
+pub struct MemorySocket {
+    /// Address this half pretends to be bound to
+    local_address: SocketAddr,
+
+    /// Address of the other half
+    peer_address: SocketAddr,
+
+    /// Sends datagrams to the other half
+    sender: Sender<Vec<u8>>,
+
+    /// Receives datagrams from the other half
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl MemorySocket {
+    /// Creates a connected pair of sockets as (server, client)
+    #[must_use]
+    pub fn pair() -> (Self, Self) {
+        let (server_sender, client_receiver) = mpsc::channel();
+        let (client_sender, server_receiver) = mpsc::channel();
+
+        let server: Self = Self {
+            local_address: SERVER_ADDRESS,
+            peer_address: CLIENT_ADDRESS,
+            sender: server_sender,
+            receiver: server_receiver,
+        };
+
+        let client: Self = Self {
+            local_address: CLIENT_ADDRESS,
+            peer_address: SERVER_ADDRESS,
+            sender: client_sender,
+            receiver: client_receiver,
+        };
+
+        (server, client)
+    }
+
+    /// Address of the other half of the pair
+    #[must_use]
+    pub fn peer_address(&self) -> SocketAddr {
+        self.peer_address
+    }
+}
+
+impl Socket for MemorySocket {
+    fn send_to(&mut self, bytes: &[u8], address: SocketAddr) -> std::io::Result<()> {
+        if address != self.peer_address {
+            return Err(std::io::Error::new(
+                ErrorKind::AddrNotAvailable,
+                format!("Memory sockets can only send to {}", self.peer_address),
+            ));
+        }
+
+        self.sender
+            .send(bytes.to_vec())
+            .map_err(|_| std::io::Error::from(ErrorKind::BrokenPipe))
+    }
+
+    fn receive_from(&mut self, buffer: &mut [u8]) -> std::io::Result<Option<(usize, SocketAddr)>> {
+        match self.receiver.try_recv() {
+            Ok(bytes) => {
+                // Oversized datagrams are truncated just like with UDP
+                let length: usize = bytes.len().min(buffer.len());
+                buffer[..length].copy_from_slice(&bytes[..length]);
+
+                Ok(Some((length, self.peer_address)))
+            }
+            // A closed peer is treated as silent, so the connection times out normally
+            Err(TryRecvError::Empty | TryRecvError::Disconnected) => Ok(None),
+        }
+    }
+
+    fn local_address(&self) -> std::io::Result<SocketAddr> {
+        Ok(self.local_address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::MemorySocket;
+    use crate::network::{Channel, Endpoint, EndpointConfig, NetworkEvent};
+
+    #[test]
+    fn test_memory_endpoints() {
+        let (server_socket, client_socket) = MemorySocket::pair();
+        let server_address = client_socket.peer_address();
+        let config: EndpointConfig = EndpointConfig {
+            version: "1.0.0".to_string(),
+            accept_connections: false,
+            max_connections: 1,
+            timeout: Duration::from_secs(10),
+            resend_interval: Duration::from_millis(10),
+            keep_alive_interval: Duration::from_millis(50),
+        };
+
+        let mut server: Endpoint<MemorySocket> = Endpoint::new(
+            server_socket,
+            EndpointConfig {
+                accept_connections: true,
+                ..config.clone()
+            },
+        );
+        let mut client: Endpoint<MemorySocket> = Endpoint::new(client_socket, config);
+
+        let now: Instant = Instant::now();
+        client.connect(server_address, now);
+        client.update(now);
+        server.update(now);
+        client.update(now);
+        assert!(client.is_connected(server_address));
+
+        client
+            .send(server_address, Channel::ReliableOrdered, b"meow".to_vec())
+            .unwrap();
+        client.update(now);
+        server.update(now);
+
+        assert!(matches!(
+            server.poll_event(),
+            Some(NetworkEvent::Connected(_))
+        ));
+        assert!(matches!(
+            server.poll_event(),
+            Some(NetworkEvent::Message { payload, .. }) if payload == b"meow"
+        ));
+    }
+}
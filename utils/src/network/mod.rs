@@ -0,0 +1,23 @@
+/// Channels and their delivery guarantees
+mod channel;
+
+/// State of a single connection
+mod connection;
+
+/// Sends and receives messages over a socket
+mod endpoint;
+
+/// Sockets connecting endpoints within the same process
+mod memory;
+
+/// Encoding of packets sent over the wire
+mod packet;
+
+pub use channel::Channel;
+pub use connection::DisconnectReason;
+pub use endpoint::{Endpoint, EndpointConfig, NetworkEvent, Socket};
+pub use memory::MemorySocket;
+pub use packet::{MAX_MESSAGE_SIZE, MAX_PACKET_SIZE};
+
+/// Default port the server listens on
+pub const DEFAULT_PORT: u16 = 7777;
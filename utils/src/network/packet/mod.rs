@@ -0,0 +1,339 @@
+use crate::network::channel::Channel;
+use crate::network::connection::DisconnectReason;
+
+/// Identifies packets as belonging to the engine
+///
+/// Anything received without this prefix is discarded
+const PROTOCOL_MAGIC: [u8; 4] = *b"CGE\0";
+
+/// Maximum size of a single datagram
+///
+/// Kept below the common internet MTU to avoid IP fragmentation
+pub const MAX_PACKET_SIZE: usize = 1200;
+
+/// Size of the payload packet header (magic, kind, sequence, ack, ack bits, message count)
+const PAYLOAD_HEADER_SIZE: usize = 4 + 1 + 4 + 4 + 4 + 1;
+
+/// Size of the header in front of every message (channel, id, length)
+pub(crate) const MESSAGE_HEADER_SIZE: usize = 1 + 4 + 2;
+
+/// Largest message which fits inside a single packet
+pub const MAX_MESSAGE_SIZE: usize = MAX_PACKET_SIZE - PAYLOAD_HEADER_SIZE - MESSAGE_HEADER_SIZE;
+
+/// Message carried by a payload packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PacketMessage {
+    /// Channel the message was sent on
+    pub(crate) channel: Channel,
+
+    /// Id of the message within the channel
+    pub(crate) id: u32,
+
+    /// Contents of the message
+    pub(crate) payload: Vec<u8>,
+}
+
+impl PacketMessage {
+    /// Number of bytes this message takes up inside a packet
+    pub(crate) fn encoded_size(&self) -> usize {
+        MESSAGE_HEADER_SIZE + self.payload.len()
+    }
+}
+
+/// Packets which are sent over the wire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Packet {
+    /// Asks the remote endpoint to open a connection
+    ConnectRequest {
+        /// Version of the engine requesting the connection
+        version: String,
+    },
+
+    /// The connection request was accepted
+    ConnectAccepted,
+
+    /// The connection request was denied
+    ConnectDenied {
+        /// Why the connection was denied
+        reason: DisconnectReason,
+    },
+
+    /// Carries messages and acknowledgements for an open connection
+    Payload {
+        /// Sequence number of this packet
+        sequence: u32,
+
+        /// Most recent sequence number received from the remote endpoint
+        ack: u32,
+
+        /// Bitfield of the 32 sequence numbers received before `ack`
+        ack_bits: u32,
+
+        /// Messages contained in this packet
+        messages: Vec<PacketMessage>,
+    },
+
+    /// The connection was closed
+    Disconnect {
+        /// Why the connection was closed
+        reason: DisconnectReason,
+    },
+}
+
+impl Packet {
+    /// Kind identifier used in the packet header
+    fn kind(&self) -> u8 {
+        match self {
+            Self::ConnectRequest { .. } => 0,
+            Self::ConnectAccepted => 1,
+            Self::ConnectDenied { .. } => 2,
+            Self::Payload { .. } => 3,
+            Self::Disconnect { .. } => 4,
+        }
+    }
+
+    /// Size of a payload packet header
+    pub(crate) fn payload_header_size() -> usize {
+        PAYLOAD_HEADER_SIZE
+    }
+
+    /// Encodes the packet into bytes for sending
+    ///
+    /// # Errors
+    ///
+    /// Errors if the packet does not fit within [`MAX_PACKET_SIZE`]
+    pub(crate) fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(MAX_PACKET_SIZE);
+        bytes.extend_from_slice(&PROTOCOL_MAGIC);
+        bytes.push(self.kind());
+
+        match self {
+            Self::ConnectRequest { version } => {
+                let length: u16 = u16::try_from(version.len())
+                    .map_err(|_| "Version string is too long...".to_string())?;
+
+                bytes.extend_from_slice(&length.to_le_bytes());
+                bytes.extend_from_slice(version.as_bytes());
+            }
+            Self::ConnectAccepted => {}
+            Self::ConnectDenied { reason } | Self::Disconnect { reason } => {
+                bytes.push(reason.code());
+            }
+            Self::Payload {
+                sequence,
+                ack,
+                ack_bits,
+                messages,
+            } => {
+                let count: u8 = u8::try_from(messages.len())
+                    .map_err(|_| "Too many messages in a single packet...".to_string())?;
+
+                bytes.extend_from_slice(&sequence.to_le_bytes());
+                bytes.extend_from_slice(&ack.to_le_bytes());
+                bytes.extend_from_slice(&ack_bits.to_le_bytes());
+                bytes.push(count);
+
+                for message in messages {
+                    let length: u16 = u16::try_from(message.payload.len())
+                        .map_err(|_| "Message is too long...".to_string())?;
+
+                    bytes.push(message.channel.code());
+                    bytes.extend_from_slice(&message.id.to_le_bytes());
+                    bytes.extend_from_slice(&length.to_le_bytes());
+                    bytes.extend_from_slice(&message.payload);
+                }
+            }
+        }
+
+        if bytes.len() > MAX_PACKET_SIZE {
+            return Err(format!(
+                "Packet of {} bytes is larger than the maximum of {MAX_PACKET_SIZE} bytes...",
+                bytes.len()
+            ));
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes a packet from received bytes
+    ///
+    /// # Errors
+    ///
+    /// Errors if the bytes are not a valid packet
+    pub(crate) fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader: ByteReader<'_> = ByteReader { bytes, position: 0 };
+
+        if reader.read_bytes(PROTOCOL_MAGIC.len())? != PROTOCOL_MAGIC {
+            return Err("Packet does not belong to this protocol...".to_string());
+        }
+
+        let packet: Self = match reader.read_u8()? {
+            0 => {
+                let length: usize = usize::from(reader.read_u16()?);
+                let version: String = String::from_utf8(reader.read_bytes(length)?.to_vec())
+                    .map_err(|_| "Version string is not valid UTF-8...".to_string())?;
+
+                Self::ConnectRequest { version }
+            }
+            1 => Self::ConnectAccepted,
+            2 => Self::ConnectDenied {
+                reason: DisconnectReason::from_code(reader.read_u8()?),
+            },
+            3 => {
+                let sequence: u32 = reader.read_u32()?;
+                let ack: u32 = reader.read_u32()?;
+                let ack_bits: u32 = reader.read_u32()?;
+                let count: u8 = reader.read_u8()?;
+
+                let mut messages: Vec<PacketMessage> = Vec::with_capacity(usize::from(count));
+                for _ in 0..count {
+                    let channel: Channel = Channel::from_code(reader.read_u8()?)?;
+                    let id: u32 = reader.read_u32()?;
+                    let length: usize = usize::from(reader.read_u16()?);
+                    let payload: Vec<u8> = reader.read_bytes(length)?.to_vec();
+
+                    messages.push(PacketMessage {
+                        channel,
+                        id,
+                        payload,
+                    });
+                }
+
+                Self::Payload {
+                    sequence,
+                    ack,
+                    ack_bits,
+                    messages,
+                }
+            }
+            4 => Self::Disconnect {
+                reason: DisconnectReason::from_code(reader.read_u8()?),
+            },
+            kind => return Err(format!("Unknown packet kind {kind}...")),
+        };
+
+        if reader.position != bytes.len() {
+            return Err("Packet has trailing bytes...".to_string());
+        }
+
+        Ok(packet)
+    }
+}
+
+/// Reads little endian values out of a byte slice
+struct ByteReader<'a> {
+    /// Bytes being read
+    bytes: &'a [u8],
+
+    /// Current position within the bytes
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Reads the requested number of bytes
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end: usize = self.position + length;
+        if end > self.bytes.len() {
+            return Err("Packet ended unexpectedly...".to_string());
+        }
+
+        let slice: &'a [u8] = &self.bytes[self.position..end];
+        self.position = end;
+
+        Ok(slice)
+    }
+
+    /// Reads a single byte
+    fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Reads a little endian u16
+    fn read_u16(&mut self) -> Result<u16, String> {
+        let bytes: &[u8] = self.read_bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Reads a little endian u32
+    fn read_u32(&mut self) -> Result<u32, String> {
+        let bytes: &[u8] = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Packet, PacketMessage, MAX_MESSAGE_SIZE, MAX_PACKET_SIZE};
+    use crate::network::channel::Channel;
+    use crate::network::connection::DisconnectReason;
+
+    #[test]
+    fn test_packet_round_trip() {
+        let packets: Vec<Packet> = vec![
+            Packet::ConnectRequest {
+                version: "0.14.44".to_string(),
+            },
+            Packet::ConnectAccepted,
+            Packet::ConnectDenied {
+                reason: DisconnectReason::VersionMismatch,
+            },
+            Packet::Payload {
+                sequence: 7,
+                ack: 3,
+                ack_bits: 0b101,
+                messages: vec![
+                    PacketMessage {
+                        channel: Channel::ReliableOrdered,
+                        id: 1,
+                        payload: b"hello".to_vec(),
+                    },
+                    PacketMessage {
+                        channel: Channel::Unreliable,
+                        id: 2,
+                        payload: Vec::new(),
+                    },
+                ],
+            },
+            Packet::Disconnect {
+                reason: DisconnectReason::Shutdown,
+            },
+        ];
+
+        for packet in packets {
+            let bytes: Vec<u8> = packet.encode().unwrap();
+            assert_eq!(Packet::decode(&bytes).unwrap(), packet);
+        }
+    }
+
+    #[test]
+    fn test_packet_limits() {
+        let largest: Packet = Packet::Payload {
+            sequence: 0,
+            ack: 0,
+            ack_bits: 0,
+            messages: vec![PacketMessage {
+                channel: Channel::ReliableUnordered,
+                id: 0,
+                payload: vec![0; MAX_MESSAGE_SIZE],
+            }],
+        };
+        assert_eq!(largest.encode().unwrap().len(), MAX_PACKET_SIZE);
+
+        let too_large: Packet = Packet::Payload {
+            sequence: 0,
+            ack: 0,
+            ack_bits: 0,
+            messages: vec![PacketMessage {
+                channel: Channel::ReliableUnordered,
+                id: 0,
+                payload: vec![0; MAX_MESSAGE_SIZE + 1],
+            }],
+        };
+        assert!(too_large.encode().is_err());
+
+        // Truncated and foreign packets are rejected
+        let bytes: Vec<u8> = largest.encode().unwrap();
+        assert!(Packet::decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Packet::decode(b"HTTP/1.1 200 OK").is_err());
+    }
+}
@@ -1,70 +1,127 @@
-use std::{io::Error, path::Path, sync::OnceLock};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
 
-use common::resources::{EmbeddedFile, EmbeddedFiles};
+use common::resources::{Compression, EmbeddedFile, EmbeddedFiles};
+
+use crate::vfs::{DirectoryMount, EmbeddedMount, Layer};
+
+/// Virtual path resources are mounted at
+pub const RESOURCES_MOUNT_POINT: &str = "resources";
 
 /// This is where resources are embedded if embedding is compiled in
-static EMBEDDED_RESOURCES: OnceLock<EmbeddedFiles> = OnceLock::new();
+static EMBEDDED_RESOURCES: OnceLock<EmbeddedMount> = OnceLock::new();
 
-/// This resource loader attempts to locate resources in this order:
-/// * Locate and read a file from the filesystem
+/// This resource loader reads resources through the virtual filesystem, which looks in this order:
+/// * Locate and read a file from the loaded mods, last loaded first
+/// * Locate and read a file from the resources directory on the filesystem
 /// * Locate and read a file from within this binary
 /// * Return an error
 ///
 /// # Errors
 ///
-/// May error if it cannot load the resource from the filesystem and from within the binary in that order
+/// May error if it cannot load the resource from the mods, the filesystem and from within the binary in that order
 pub fn get_resource_bytes(path: &Path) -> Result<Vec<u8>, String> {
-    if let Ok(file) = get_resource_file_from_filesystem(path) {
-        return Ok(file);
-    }
-
-    get_embedded_resource_file(path)
+    crate::vfs::read(path).map(Cow::into_owned)
 }
 
-/// This resource loader attempts to locate resources in this order:
-/// * Locate and read a file from the filesystem
+/// This resource loader reads resources through the virtual filesystem, which looks in this order:
+/// * Locate and read a file from the loaded mods, last loaded first
+/// * Locate and read a file from the resources directory on the filesystem
 /// * Locate and read a file from within this binary
 /// * Return an error
 ///
 /// # Errors
 ///
-/// May error if it cannot load the resource from the filesystem and from within the binary in that order
-///
-/// # Panics
-///
-/// May panic if cannot unwrap bytes as UTF-8 string
+/// May error if it cannot load the resource from the mods, the filesystem and from within the binary in that order,
+/// or the resource isn't valid UTF-8
 pub fn get_resource_string(path: &Path) -> Result<String, String> {
-    let resource_bytes_option: Result<Vec<u8>, String> = get_resource_bytes(path);
-
-    // Checks if an error and passes up
-    resource_bytes_option.as_ref()?;
-
-    Ok(String::from_utf8(resource_bytes_option.unwrap()).unwrap())
+    crate::vfs::read_string(path)
 }
 
-/// Attempts to retrieve a resource file from within the filesystem
+/// Retrieves a resource embedded in the binary, only copying it if it has to be decompressed
 ///
-/// TODO: Sanitize the File Path
-fn get_resource_file_from_filesystem(path: &Path) -> Result<Vec<u8>, Error> {
-    std::fs::read(path)
+/// Unlike [`get_resource_bytes`], this ignores mods and the resources directory
+#[must_use]
+pub fn get_embedded_resource(path: &Path) -> Option<Cow<'static, [u8]>> {
+    let file: &'static EmbeddedFile = EMBEDDED_RESOURCES.get()?.get(path)?;
+
+    decompress(file).ok()
 }
 
-/// Attempts to retrieve a resource file from within the binary
-fn get_embedded_resource_file(path: &Path) -> Result<Vec<u8>, String> {
-    let embedded_files: &Vec<EmbeddedFile> = &EMBEDDED_RESOURCES.get().unwrap().inner;
+/// Retrieves the contents of an embedded file, decompressing them if they were compressed at build time
+///
+/// # Errors
+///
+/// Errors if the compressed contents are corrupt or don't match the recorded size
+pub(crate) fn decompress(file: &'static EmbeddedFile) -> Result<Cow<'static, [u8]>, String> {
+    match file.compression {
+        Compression::None => Ok(Cow::Borrowed(file.contents)),
+        Compression::Deflate => {
+            let size: usize = usize::try_from(file.size).map_err(|error| error.to_string())?;
+            let contents: Vec<u8> =
+                miniz_oxide::inflate::decompress_to_vec_with_limit(file.contents, size)
+                    .map_err(|error| format!("Could not decompress {}: {error}", file.path))?;
 
-    for file in embedded_files {
-        if Path::new(&file.path).eq(path) {
-            return Ok(file.contents.clone());
+            if contents.len() == size {
+                Ok(Cow::Owned(contents))
+            } else {
+                Err(format!(
+                    "{} decompressed to {} bytes instead of {size}",
+                    file.path,
+                    contents.len()
+                ))
+            }
         }
     }
-
-    Err(format!(
-        "Asset Not Found - Unable To Load Embedded Asset: {path:?}"
-    ))
 }
 
 /// Allows using embedded resources from this utility crate
 pub fn store_embedded_resources(embedded_resources: EmbeddedFiles) {
-    let _ = EMBEDDED_RESOURCES.set(embedded_resources);
+    let mount: EmbeddedMount = EmbeddedMount::new(embedded_resources);
+
+    // Embedded paths already start with the resources directory
+    if EMBEDDED_RESOURCES.set(mount).is_ok() {
+        let _ = crate::vfs::mount(Path::new(""), Layer::Embedded, Arc::new(mount));
+    }
+}
+
+/// Mounts the resources directory on the filesystem so the user can add and override resources
+///
+/// # Errors
+///
+/// Errors if the directory cannot be mounted
+pub fn mount_resources_directory(path: PathBuf) -> Result<(), String> {
+    crate::vfs::mount(
+        Path::new(RESOURCES_MOUNT_POINT),
+        Layer::User,
+        Arc::new(DirectoryMount::new(path)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use common::resources::{Compression, EmbeddedFile};
+
+    use super::decompress;
+
+    #[test]
+    fn test_decompress() {
+        let contents: &[u8] = b"catgirl catgirl catgirl catgirl catgirl";
+        let compressed: Vec<u8> = miniz_oxide::deflate::compress_to_vec(contents, 10);
+        let file: &'static EmbeddedFile = Box::leak(Box::new(EmbeddedFile {
+            path: "resources/test.txt",
+            contents: compressed.leak(),
+            compression: Compression::Deflate,
+            size: contents.len() as u64,
+        }));
+
+        assert_eq!(decompress(file).as_deref(), Ok(contents));
+
+        let truncated: &'static EmbeddedFile =
+            Box::leak(Box::new(EmbeddedFile { size: 4, ..*file }));
+        assert!(decompress(truncated).is_err());
+    }
 }
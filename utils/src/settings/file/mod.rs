@@ -0,0 +1,248 @@
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
+
+/// Directory inside the platform config and data directories holding the engine's files
+const CONFIG_DIRECTORY: &str = "catgirl-engine";
+
+/// Name of the config file
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Directory the platform keeps per user configuration in
+///
+/// `$XDG_CONFIG_HOME` or `~/.config` on Linux and the BSDs,
+/// `~/Library/Application Support` on macOS and `%APPDATA%` on Windows,
+/// platforms without a user writable filesystem (e.g. web browsers and Android) have none
+#[must_use]
+pub fn config_directory() -> Option<PathBuf> {
+    use crate::environment::get_environment_var;
+
+    if cfg!(any(
+        target_family = "wasm",
+        target_os = "android",
+        target_os = "ios"
+    )) {
+        None
+    } else if cfg!(target_family = "windows") {
+        get_environment_var("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        get_environment_var("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    } else {
+        // Relative paths in XDG_CONFIG_HOME are invalid and must be ignored
+        get_environment_var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|directory| directory.is_absolute())
+            .or_else(|| get_environment_var("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+}
+
+/// Directory the platform keeps per user data in, such as crash reports
+///
+/// `$XDG_DATA_HOME` or `~/.local/share` on Linux and the BSDs,
+/// `~/Library/Application Support` on macOS and `%LOCALAPPDATA%` on Windows,
+/// platforms without a user writable filesystem (e.g. web browsers and Android) have none
+#[must_use]
+pub fn data_directory() -> Option<PathBuf> {
+    use crate::environment::get_environment_var;
+
+    if cfg!(any(
+        target_family = "wasm",
+        target_os = "android",
+        target_os = "ios"
+    )) {
+        None
+    } else if cfg!(target_family = "windows") {
+        get_environment_var("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        config_directory()
+    } else {
+        // Relative paths in XDG_DATA_HOME are invalid and must be ignored
+        get_environment_var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|directory| directory.is_absolute())
+            .or_else(|| {
+                get_environment_var("HOME")
+                    .map(|home| PathBuf::from(home).join(".local").join("share"))
+            })
+    }
+}
+
+/// Directory the engine keeps its data in, such as crash reports and screenshots
+#[must_use]
+pub fn engine_data_directory() -> Option<PathBuf> {
+    Some(data_directory()?.join(CONFIG_DIRECTORY))
+}
+
+/// Location of the config file if not set by `--config`
+#[must_use]
+pub fn default_config_path() -> Option<PathBuf> {
+    Some(config_directory()?.join(CONFIG_DIRECTORY).join(CONFIG_FILE))
+}
+
+/// TOML config file, keys the engine doesn't know are kept so writing it back doesn't lose them
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfigFile {
+    /// Where the file is read from and written to, if the platform has a config directory
+    pub path: Option<PathBuf>,
+
+    /// Contents of the file
+    table: toml::Table,
+}
+
+impl ConfigFile {
+    /// Creates an empty config file, which isn't written until [`ConfigFile::write`] is called
+    #[must_use]
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            table: toml::Table::new(),
+        }
+    }
+
+    /// Reads a config file, a file which doesn't exist yet is empty
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file exists but cannot be read or isn't valid TOML
+    pub fn read(path: Option<PathBuf>) -> Result<Self, String> {
+        let Some(file_path) = &path else {
+            return Ok(Self::new(path));
+        };
+
+        match std::fs::read_to_string(file_path) {
+            Ok(contents) => Self::parse(path, &contents),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::new(path)),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    /// Parses the contents of a config file
+    ///
+    /// # Errors
+    ///
+    /// Errors if the contents aren't valid TOML
+    pub fn parse(path: Option<PathBuf>, contents: &str) -> Result<Self, String> {
+        let table: toml::Table = contents
+            .parse::<toml::Table>()
+            .map_err(|error| error.to_string())?;
+
+        Ok(Self { path, table })
+    }
+
+    /// Gets the value of a dotted key (e.g. `window.width`)
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&toml::Value> {
+        let (tables, name): (Vec<&str>, &str) = split_key(key);
+
+        let mut table: &toml::Table = &self.table;
+        for part in tables {
+            table = table.get(part)?.as_table()?;
+        }
+
+        table.get(name)
+    }
+
+    /// Sets the value of a dotted key, creating the tables leading to it
+    ///
+    /// # Errors
+    ///
+    /// Errors if a part of the key already holds something other than a table
+    pub fn set(&mut self, key: &str, value: toml::Value) -> Result<(), String> {
+        let (tables, name): (Vec<&str>, &str) = split_key(key);
+
+        let mut table: &mut toml::Table = &mut self.table;
+        for part in tables {
+            table = table
+                .entry(part)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| format!("Cannot set {key}, {part} isn't a table"))?;
+        }
+
+        table.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Writes the config file, replacing it only once the new contents are fully written
+    ///
+    /// # Errors
+    ///
+    /// Errors if the platform has no config directory or the file cannot be written
+    pub fn write(&self) -> Result<(), String> {
+        let path: &PathBuf = self
+            .path
+            .as_ref()
+            .ok_or_else(|| "This platform has no config directory".to_string())?;
+
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+        }
+
+        let temporary: PathBuf = path.with_extension("toml.tmp");
+        std::fs::write(&temporary, self.to_string()).map_err(|error| error.to_string())?;
+        std::fs::rename(&temporary, path).map_err(|error| error.to_string())
+    }
+}
+
+impl Display for ConfigFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let contents: String = toml::to_string(&self.table).map_err(|_| std::fmt::Error)?;
+
+        f.write_str(&contents)
+    }
+}
+
+/// Splits a dotted key into the tables leading to it and its name
+fn split_key(key: &str) -> (Vec<&str>, &str) {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let name: &str = parts.pop().unwrap_or_default();
+
+    (parts, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigFile;
+
+    #[test]
+    fn test_round_trip() {
+        let contents: &str = "
+            resources = \"C:\\\\Games\\\\resources\"
+            extra = [1, 2.5, \"three\"]
+
+            [window]
+            width = 1920
+
+            [mods.\"my mod\"]
+            enabled = true
+        ";
+
+        let mut file: ConfigFile = ConfigFile::parse(None, contents).unwrap();
+        file.set("window.height", toml::Value::Integer(1080))
+            .unwrap();
+        file.set(
+            "log.filter",
+            toml::Value::String("info\n\"quoted\"".to_string()),
+        )
+        .unwrap();
+        assert!(file
+            .set("resources.path", toml::Value::Boolean(true))
+            .is_err());
+
+        let written: ConfigFile = ConfigFile::parse(None, &file.to_string()).unwrap();
+        assert_eq!(written, file);
+        assert_eq!(
+            written.get("window.height"),
+            Some(&toml::Value::Integer(1080))
+        );
+        assert_eq!(
+            written.get("mods.my mod.enabled"),
+            Some(&toml::Value::Boolean(true))
+        );
+    }
+}
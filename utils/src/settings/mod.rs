@@ -0,0 +1,792 @@
+/// Module for reading and writing the config file
+pub mod file;
+
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+    sync::RwLock,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::args::{Args, Command, OutputFormat, Vsync};
+
+pub use file::ConfigFile;
+
+/// Width of the window in logical pixels if not set
+pub const DEFAULT_WIDTH: u32 = 1280;
+
+/// Height of the window in logical pixels if not set
+pub const DEFAULT_HEIGHT: u32 = 720;
+
+/// Largest width or height of the window accepted
+pub const MAX_WINDOW_SIZE: u32 = 16_384;
+
+/// Log filter if not set, in `EnvFilter` syntax (e.g. `info,catgirl_engine=debug`)
+pub const DEFAULT_LOG_FILTER: &str = "main=info,catgirl_engine=info,catgirl_engine_client=info,catgirl_engine_server=info,catgirl_engine_utils=info";
+
+/// Resources directory if not set, relative to the working directory
+pub const DEFAULT_RESOURCES_PATH: &str = "resources";
+
+/// Levels a log filter directive may use
+const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// Key of the window width in the config file
+const WIDTH_KEY: &str = "window.width";
+
+/// Key of the window height in the config file
+const HEIGHT_KEY: &str = "window.height";
+
+/// Key of the vsync mode in the config file
+const VSYNC_KEY: &str = "window.vsync";
+
+/// Key of the server port in the config file
+const PORT_KEY: &str = "server.port";
+
+/// Key of the log filter in the config file
+const LOG_FILTER_KEY: &str = "log.filter";
+
+/// Key of the log format in the config file
+const LOG_FORMAT_KEY: &str = "log.format";
+
+/// Key of the log directory in the config file
+const LOG_DIRECTORY_KEY: &str = "log.directory";
+
+/// Key of whether span timings are logged in the config file
+const LOG_SPAN_TIMINGS_KEY: &str = "log.span_timings";
+
+/// Key of the locale in the config file
+const LOCALE_KEY: &str = "locale";
+
+/// Key of the resources path in the config file
+const RESOURCES_KEY: &str = "resources";
+
+/// Settings in effect and the layers they were resolved from
+static SETTINGS: RwLock<Option<Store>> = RwLock::new(None);
+
+/// Where a setting was set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsSource {
+    /// A command line flag or the environment variable backing it
+    CommandLine,
+
+    /// The config file at this path
+    File(Option<PathBuf>),
+}
+
+impl Display for SettingsSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CommandLine => write!(f, "command line"),
+            Self::File(Some(path)) => write!(f, "{}", path.display()),
+            Self::File(None) => write!(f, "config file"),
+        }
+    }
+}
+
+/// Problem found while resolving the settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsDiagnostic {
+    /// The config file could not be read, so only the defaults and command line are used
+    Unreadable {
+        /// Location of the config file
+        path: PathBuf,
+
+        /// Why the file could not be read
+        error: String,
+    },
+
+    /// A setting has an invalid value, so the value of a lower precedence layer is used
+    Invalid {
+        /// Where the value was set
+        source: SettingsSource,
+
+        /// Key of the setting in the config file
+        key: String,
+
+        /// Why the value is invalid
+        error: String,
+    },
+}
+
+impl Display for SettingsDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreadable { path, error } => {
+                write!(f, "Could not read config file {}: {error}", path.display())
+            }
+            Self::Invalid { source, key, error } => {
+                write!(f, "Ignoring {key} set by {source}: {error}")
+            }
+        }
+    }
+}
+
+/// Settings set by a single layer, unset values fall through to the next layer
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PartialSettings {
+    /// Width of the window in logical pixels
+    pub width: Option<u32>,
+
+    /// Height of the window in logical pixels
+    pub height: Option<u32>,
+
+    /// How frames are synchronized with the display
+    pub vsync: Option<Vsync>,
+
+    /// Port the server listens on
+    pub port: Option<u16>,
+
+    /// Which logs are shown, in `EnvFilter` syntax
+    pub log_filter: Option<String>,
+
+    /// Format the logs are written in
+    pub log_format: Option<OutputFormat>,
+
+    /// Directory the logs are also written to, rotated daily
+    pub log_directory: Option<PathBuf>,
+
+    /// Whether how long each span took is logged when it closes
+    pub log_span_timings: Option<bool>,
+
+    /// Language to translate into
+    pub locale: Option<String>,
+
+    /// Path to the resources directory
+    pub resources: Option<PathBuf>,
+}
+
+impl PartialSettings {
+    /// Settings set by command line flags or the environment variables backing them
+    #[must_use]
+    pub fn from_args(args: &Args) -> Self {
+        let command: Command = args.command();
+        let (width, height, vsync): (Option<u32>, Option<u32>, Option<Vsync>) = match &command {
+            Command::Run(run) => (run.width, run.height, run.vsync),
+            _ => (None, None, None),
+        };
+        let port: Option<u16> = match &command {
+            Command::Serve(serve) => serve.port,
+            _ => None,
+        };
+
+        Self {
+            width,
+            height,
+            vsync,
+            port,
+            log_filter: args.global.log_filter.clone(),
+            log_format: args.global.log_format,
+            log_directory: args.global.log_directory.clone(),
+            // The flag can only turn span timings on, so the config file can too
+            log_span_timings: args.global.log_span_timings.then_some(true),
+            locale: args.global.locale.clone(),
+            resources: args.global.resources.clone(),
+        }
+    }
+
+    /// Settings set by a config file, values of the wrong type are reported and left unset
+    #[must_use]
+    pub fn from_file(file: &ConfigFile) -> (Self, Vec<SettingsDiagnostic>) {
+        let source: SettingsSource = SettingsSource::File(file.path.clone());
+        let mut diagnostics: Vec<SettingsDiagnostic> = Vec::new();
+
+        let settings: Self = Self {
+            width: read_integer(file, WIDTH_KEY, &source, &mut diagnostics),
+            height: read_integer(file, HEIGHT_KEY, &source, &mut diagnostics),
+            vsync: read_choice(file, VSYNC_KEY, &source, &mut diagnostics),
+            port: read_integer(file, PORT_KEY, &source, &mut diagnostics),
+            log_filter: read_string(file, LOG_FILTER_KEY, &source, &mut diagnostics),
+            log_format: read_choice(file, LOG_FORMAT_KEY, &source, &mut diagnostics),
+            log_directory: read_string(file, LOG_DIRECTORY_KEY, &source, &mut diagnostics)
+                .map(PathBuf::from),
+            log_span_timings: read_bool(file, LOG_SPAN_TIMINGS_KEY, &source, &mut diagnostics),
+            locale: read_string(file, LOCALE_KEY, &source, &mut diagnostics),
+            resources: read_string(file, RESOURCES_KEY, &source, &mut diagnostics)
+                .map(PathBuf::from),
+        };
+
+        let (settings, invalid) = settings.validate(&source);
+        diagnostics.extend(invalid);
+
+        (settings, diagnostics)
+    }
+
+    /// Unsets invalid values and normalizes the rest, reporting each invalid value
+    #[must_use]
+    pub fn validate(self, source: &SettingsSource) -> (Self, Vec<SettingsDiagnostic>) {
+        let mut diagnostics: Vec<SettingsDiagnostic> = Vec::new();
+
+        let settings: Self = Self {
+            width: check(
+                self.width,
+                WIDTH_KEY,
+                source,
+                &mut diagnostics,
+                validate_window_size,
+            ),
+            height: check(
+                self.height,
+                HEIGHT_KEY,
+                source,
+                &mut diagnostics,
+                validate_window_size,
+            ),
+            vsync: self.vsync,
+            port: check(self.port, PORT_KEY, source, &mut diagnostics, validate_port),
+            log_filter: check(
+                self.log_filter,
+                LOG_FILTER_KEY,
+                source,
+                &mut diagnostics,
+                validate_log_filter,
+            ),
+            log_format: self.log_format,
+            log_directory: check(
+                self.log_directory,
+                LOG_DIRECTORY_KEY,
+                source,
+                &mut diagnostics,
+                validate_log_directory,
+            ),
+            log_span_timings: self.log_span_timings,
+            locale: check(
+                self.locale,
+                LOCALE_KEY,
+                source,
+                &mut diagnostics,
+                validate_locale,
+            ),
+            resources: check(
+                self.resources,
+                RESOURCES_KEY,
+                source,
+                &mut diagnostics,
+                validate_resources,
+            ),
+        };
+
+        (settings, diagnostics)
+    }
+
+    /// Fills the values this layer doesn't set from a lower precedence layer
+    #[must_use]
+    pub fn or(self, lower: Self) -> Self {
+        Self {
+            width: self.width.or(lower.width),
+            height: self.height.or(lower.height),
+            vsync: self.vsync.or(lower.vsync),
+            port: self.port.or(lower.port),
+            log_filter: self.log_filter.or(lower.log_filter),
+            log_format: self.log_format.or(lower.log_format),
+            log_directory: self.log_directory.or(lower.log_directory),
+            log_span_timings: self.log_span_timings.or(lower.log_span_timings),
+            locale: self.locale.or(lower.locale),
+            resources: self.resources.or(lower.resources),
+        }
+    }
+
+    /// Writes the values this layer sets into a config file
+    ///
+    /// # Errors
+    ///
+    /// Errors if a key cannot be set, such as when the file uses its table for something else
+    pub fn write_to(&self, file: &mut ConfigFile) -> Result<(), String> {
+        if let Some(width) = self.width {
+            file.set(WIDTH_KEY, toml::Value::Integer(i64::from(width)))?;
+        }
+        if let Some(height) = self.height {
+            file.set(HEIGHT_KEY, toml::Value::Integer(i64::from(height)))?;
+        }
+        if let Some(vsync) = self.vsync {
+            file.set(VSYNC_KEY, toml::Value::String(choice_name(vsync)))?;
+        }
+        if let Some(port) = self.port {
+            file.set(PORT_KEY, toml::Value::Integer(i64::from(port)))?;
+        }
+        if let Some(log_filter) = &self.log_filter {
+            file.set(LOG_FILTER_KEY, toml::Value::String(log_filter.clone()))?;
+        }
+        if let Some(log_format) = self.log_format {
+            file.set(LOG_FORMAT_KEY, toml::Value::String(choice_name(log_format)))?;
+        }
+        if let Some(log_directory) = &self.log_directory {
+            file.set(
+                LOG_DIRECTORY_KEY,
+                toml::Value::String(log_directory.to_string_lossy().to_string()),
+            )?;
+        }
+        if let Some(log_span_timings) = self.log_span_timings {
+            file.set(LOG_SPAN_TIMINGS_KEY, toml::Value::Boolean(log_span_timings))?;
+        }
+        if let Some(locale) = &self.locale {
+            file.set(LOCALE_KEY, toml::Value::String(locale.clone()))?;
+        }
+        if let Some(resources) = &self.resources {
+            file.set(
+                RESOURCES_KEY,
+                toml::Value::String(resources.to_string_lossy().to_string()),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Settings in effect, resolved from the command line, environment variables, config file and defaults
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// Width of the window in logical pixels
+    pub width: u32,
+
+    /// Height of the window in logical pixels
+    pub height: u32,
+
+    /// How frames are synchronized with the display
+    pub vsync: Vsync,
+
+    /// Port the server listens on
+    pub port: u16,
+
+    /// Which logs are shown, in `EnvFilter` syntax
+    pub log_filter: String,
+
+    /// Format the logs are written in
+    pub log_format: OutputFormat,
+
+    /// Directory the logs are also written to, rotated daily, if set
+    pub log_directory: Option<PathBuf>,
+
+    /// Whether how long each span took is logged when it closes
+    pub log_span_timings: bool,
+
+    /// Language to translate into, detected from the system if not set
+    pub locale: Option<String>,
+
+    /// Path to the resources directory
+    pub resources: PathBuf,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            vsync: Vsync::On,
+            port: crate::network::DEFAULT_PORT,
+            log_filter: DEFAULT_LOG_FILTER.to_string(),
+            log_format: OutputFormat::Text,
+            log_directory: None,
+            log_span_timings: false,
+            locale: None,
+            resources: PathBuf::from(DEFAULT_RESOURCES_PATH),
+        }
+    }
+}
+
+impl From<PartialSettings> for Settings {
+    /// Uses the defaults for every value which isn't set
+    fn from(settings: PartialSettings) -> Self {
+        let defaults: Self = Self::default();
+
+        Self {
+            width: settings.width.unwrap_or(defaults.width),
+            height: settings.height.unwrap_or(defaults.height),
+            vsync: settings.vsync.unwrap_or(defaults.vsync),
+            port: settings.port.unwrap_or(defaults.port),
+            log_filter: settings.log_filter.unwrap_or(defaults.log_filter),
+            log_format: settings.log_format.unwrap_or(defaults.log_format),
+            log_directory: settings.log_directory.or(defaults.log_directory),
+            log_span_timings: settings
+                .log_span_timings
+                .unwrap_or(defaults.log_span_timings),
+            locale: settings.locale.or(defaults.locale),
+            resources: settings.resources.unwrap_or(defaults.resources),
+        }
+    }
+}
+
+/// Layers the settings in effect were resolved from
+#[derive(Debug)]
+struct Store {
+    /// Config file, or why it could not be read
+    file: Result<ConfigFile, String>,
+
+    /// Settings set by the command line and environment variables
+    command_line: PartialSettings,
+
+    /// Settings in effect
+    settings: Settings,
+
+    /// Problems found while resolving the settings
+    diagnostics: Vec<SettingsDiagnostic>,
+}
+
+impl Store {
+    /// Resolves the settings in effect from the layers
+    fn resolve(&mut self) -> Vec<SettingsDiagnostic> {
+        let (from_file, diagnostics) = match &self.file {
+            Ok(file) => PartialSettings::from_file(file),
+            Err(_) => (PartialSettings::default(), Vec::new()),
+        };
+
+        self.settings = Settings::from(self.command_line.clone().or(from_file));
+        diagnostics
+    }
+}
+
+/// Reads the config file and resolves the settings, with command line flags taking precedence,
+/// then environment variables, then the config file and then the defaults
+///
+/// The config file is read from `--config` if set, otherwise from [`file::default_config_path`]
+///
+/// # Panics
+///
+/// May panic if the settings' lock is poisoned
+pub fn load_settings(args: &Args) -> Settings {
+    let path: Option<PathBuf> = args
+        .global
+        .config
+        .clone()
+        .or_else(file::default_config_path);
+    let (command_line, mut diagnostics) =
+        PartialSettings::from_args(args).validate(&SettingsSource::CommandLine);
+
+    let file: Result<ConfigFile, String> = ConfigFile::read(path.clone());
+    if let (Err(error), Some(path)) = (&file, path) {
+        diagnostics.push(SettingsDiagnostic::Unreadable {
+            path,
+            error: error.clone(),
+        });
+    }
+
+    let mut store: Store = Store {
+        file,
+        command_line,
+        settings: Settings::default(),
+        diagnostics: Vec::new(),
+    };
+    diagnostics.extend(store.resolve());
+    store.diagnostics = diagnostics;
+
+    let settings: Settings = store.settings.clone();
+    *SETTINGS.write().unwrap() = Some(store);
+
+    settings
+}
+
+/// Get the settings in effect, if they were loaded
+///
+/// # Panics
+///
+/// May panic if the settings' lock is poisoned
+#[must_use]
+pub fn get_settings() -> Option<Settings> {
+    SETTINGS
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|store| store.settings.clone())
+}
+
+/// Get the problems found while loading the settings
+///
+/// # Panics
+///
+/// May panic if the settings' lock is poisoned
+#[must_use]
+pub fn get_diagnostics() -> Vec<SettingsDiagnostic> {
+    SETTINGS
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|store| store.diagnostics.clone())
+        .unwrap_or_default()
+}
+
+/// Writes changed settings to the config file and resolves the settings again
+///
+/// Command line flags and environment variables still take precedence over the written values,
+/// values read once at startup (e.g. the port) take effect on the next start,
+/// except for the log filter which takes effect immediately
+///
+/// # Errors
+///
+/// Errors if the settings weren't loaded, a value is invalid or the config file cannot be written
+///
+/// # Panics
+///
+/// May panic if the settings' lock is poisoned
+pub fn save_settings(changes: PartialSettings) -> Result<Settings, String> {
+    let mut store = SETTINGS.write().unwrap();
+    let store: &mut Store = store
+        .as_mut()
+        .ok_or_else(|| "Settings have not been loaded".to_string())?;
+
+    // A file which could not be read might still hold the user's settings
+    let file: &mut ConfigFile = store
+        .file
+        .as_mut()
+        .map_err(|error| format!("Refusing to overwrite unreadable config file: {error}"))?;
+
+    let (changes, invalid) = changes.validate(&SettingsSource::File(file.path.clone()));
+    if let Some(diagnostic) = invalid.first() {
+        return Err(diagnostic.to_string());
+    }
+
+    let mut changed: ConfigFile = file.clone();
+    changes.write_to(&mut changed)?;
+    changed.write()?;
+    *file = changed;
+
+    let log_filter: String = store.settings.log_filter.clone();
+    store.resolve();
+
+    // Unlike most settings, the log filter can change without restarting
+    if store.settings.log_filter != log_filter {
+        if let Err(error) = crate::logging::set_log_filter(&store.settings.log_filter) {
+            warn!("Could not apply the new log filter: {error}");
+        }
+    }
+
+    Ok(store.settings.clone())
+}
+
+/// Reads an integer from the config file, reporting values of the wrong type or range
+fn read_integer<T: TryFrom<i64>>(
+    file: &ConfigFile,
+    key: &str,
+    source: &SettingsSource,
+    diagnostics: &mut Vec<SettingsDiagnostic>,
+) -> Option<T> {
+    let value: &toml::Value = file.get(key)?;
+
+    let result: Result<T, String> = value
+        .as_integer()
+        .ok_or_else(|| format!("Expected an integer, found {}", value.type_str()))
+        .and_then(|integer| T::try_from(integer).map_err(|_| format!("{integer} is out of range")));
+
+    report(result, key, source, diagnostics)
+}
+
+/// Reads a string from the config file, reporting values of the wrong type
+fn read_string(
+    file: &ConfigFile,
+    key: &str,
+    source: &SettingsSource,
+    diagnostics: &mut Vec<SettingsDiagnostic>,
+) -> Option<String> {
+    let value: &toml::Value = file.get(key)?;
+
+    let result: Result<String, String> = value
+        .as_str()
+        .map(ToString::to_string)
+        .ok_or_else(|| format!("Expected a string, found {}", value.type_str()));
+
+    report(result, key, source, diagnostics)
+}
+
+/// Reads a boolean from the config file, reporting values of the wrong type
+fn read_bool(
+    file: &ConfigFile,
+    key: &str,
+    source: &SettingsSource,
+    diagnostics: &mut Vec<SettingsDiagnostic>,
+) -> Option<bool> {
+    let value: &toml::Value = file.get(key)?;
+
+    let result: Result<bool, String> = value
+        .as_bool()
+        .ok_or_else(|| format!("Expected a boolean, found {}", value.type_str()));
+
+    report(result, key, source, diagnostics)
+}
+
+/// Reads one of the values a flag accepts (e.g. an output format) from the config file, reporting unknown values
+fn read_choice<T: clap::ValueEnum>(
+    file: &ConfigFile,
+    key: &str,
+    source: &SettingsSource,
+    diagnostics: &mut Vec<SettingsDiagnostic>,
+) -> Option<T> {
+    let name: String = read_string(file, key, source, diagnostics)?;
+
+    let result: Result<T, String> =
+        T::from_str(&name, true).map_err(|_| format!("Unknown value {name}"));
+
+    report(result, key, source, diagnostics)
+}
+
+/// Name of one of the values a flag accepts as written in the config file
+fn choice_name<T: clap::ValueEnum>(choice: T) -> String {
+    choice
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Validates a value if it's set, reporting it and leaving it unset if invalid
+fn check<T>(
+    value: Option<T>,
+    key: &str,
+    source: &SettingsSource,
+    diagnostics: &mut Vec<SettingsDiagnostic>,
+    validate: impl Fn(T) -> Result<T, String>,
+) -> Option<T> {
+    report(validate(value?), key, source, diagnostics)
+}
+
+/// Turns an invalid value into a diagnostic
+fn report<T>(
+    result: Result<T, String>,
+    key: &str,
+    source: &SettingsSource,
+    diagnostics: &mut Vec<SettingsDiagnostic>,
+) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(error) => {
+            diagnostics.push(SettingsDiagnostic::Invalid {
+                source: source.clone(),
+                key: key.to_string(),
+                error,
+            });
+
+            None
+        }
+    }
+}
+
+/// Checks a window dimension is neither zero nor larger than [`MAX_WINDOW_SIZE`]
+fn validate_window_size(size: u32) -> Result<u32, String> {
+    if (1..=MAX_WINDOW_SIZE).contains(&size) {
+        Ok(size)
+    } else {
+        Err(format!("Must be between 1 and {MAX_WINDOW_SIZE}"))
+    }
+}
+
+/// Checks the port isn't zero, which would pick a random port clients can't find
+fn validate_port(port: u16) -> Result<u16, String> {
+    if port == 0 {
+        Err("Port cannot be 0".to_string())
+    } else {
+        Ok(port)
+    }
+}
+
+/// Checks each `target=level` directive of a log filter names a known level
+fn validate_log_filter(filter: String) -> Result<String, String> {
+    let filter: &str = filter.trim();
+    if filter.is_empty() {
+        return Err("Log filter cannot be empty".to_string());
+    }
+
+    for directive in filter.split(',').filter(|directive| !directive.is_empty()) {
+        // Span fields may contain `=` too, so the level is after the last one
+        let level: Option<&str> = match directive.rsplit_once('=') {
+            Some((_, level)) => Some(level),
+            // A bare directive is either a level or a target to show every log of
+            None => LOG_LEVELS
+                .iter()
+                .find(|level| directive.eq_ignore_ascii_case(level))
+                .copied(),
+        };
+
+        if let Some(level) = level {
+            if !LOG_LEVELS
+                .iter()
+                .any(|known| level.eq_ignore_ascii_case(known))
+            {
+                return Err(format!("Unknown log level {level}"));
+            }
+        }
+    }
+
+    Ok(filter.to_string())
+}
+
+/// Checks the log directory isn't empty
+fn validate_log_directory(path: PathBuf) -> Result<PathBuf, String> {
+    if path.as_os_str().is_empty() {
+        Err("Log directory cannot be empty".to_string())
+    } else {
+        Ok(path)
+    }
+}
+
+/// Normalizes a locale to a BCP 47 style tag
+fn validate_locale(locale: String) -> Result<String, String> {
+    crate::i18n::locale::normalize(&locale).ok_or_else(|| format!("{locale} isn't a locale"))
+}
+
+/// Checks the resources path isn't empty
+fn validate_resources(path: PathBuf) -> Result<PathBuf, String> {
+    if path.as_os_str().is_empty() {
+        Err("Resources path cannot be empty".to_string())
+    } else {
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{ConfigFile, OutputFormat, PartialSettings, Settings, SettingsSource, Vsync};
+
+    #[test]
+    fn test_precedence() {
+        let file: ConfigFile = ConfigFile::parse(
+            None,
+            "
+            locale = \"pt_BR\"
+
+            [window]
+            width = 1920
+            height = 0
+            vsync = \"Mailbox\"
+
+            [server]
+            port = \"7777\"
+
+            [log]
+            filter = \"info,catgirl_engine=loud\"
+            format = \"JSON\"
+            span_timings = 1
+            ",
+        )
+        .unwrap();
+
+        let (from_file, diagnostics) = PartialSettings::from_file(&file);
+        assert_eq!(from_file.width, Some(1920));
+        assert_eq!(from_file.locale.as_deref(), Some("pt-BR"));
+        assert_eq!(from_file.height, None);
+        assert_eq!(from_file.vsync, Some(Vsync::Mailbox));
+        assert_eq!(from_file.port, None);
+        assert_eq!(from_file.log_filter, None);
+        assert_eq!(from_file.log_format, Some(OutputFormat::Json));
+        assert_eq!(from_file.log_span_timings, None);
+        assert_eq!(diagnostics.len(), 4);
+
+        let (command_line, diagnostics) = PartialSettings {
+            width: Some(800),
+            resources: Some(PathBuf::from("mods")),
+            log_filter: Some("debug".to_string()),
+            ..PartialSettings::default()
+        }
+        .validate(&SettingsSource::CommandLine);
+        assert!(diagnostics.is_empty());
+
+        let settings: Settings = Settings::from(command_line.or(from_file));
+        assert_eq!(settings.width, 800);
+        assert_eq!(settings.height, super::DEFAULT_HEIGHT);
+        assert_eq!(settings.port, crate::network::DEFAULT_PORT);
+        assert_eq!(settings.log_filter, "debug");
+        assert_eq!(settings.locale.as_deref(), Some("pt-BR"));
+        assert_eq!(settings.resources, PathBuf::from("mods"));
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs::File,
+    io::Read,
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
+};
+
+use zip::ZipArchive;
+
+use crate::vfs::{
+    memory::{tree_list, tree_metadata},
+    DirEntry, Metadata, MountSource,
+};
+
+/// Mount point serving the files within a zip archive
+#[derive(Debug)]
+pub struct ArchiveMount {
+    /// Path of the archive on the filesystem
+    path: PathBuf,
+
+    /// Open archive, locked as reading moves the file cursor
+    archive: Mutex<ZipArchive<File>>,
+
+    /// Uncompressed size of each file keyed by their path
+    files: BTreeMap<PathBuf, u64>,
+}
+
+impl ArchiveMount {
+    /// Opens and indexes a zip archive
+    ///
+    /// Files whose paths escape the mount point are skipped
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file cannot be opened or isn't a zip archive
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file: File = File::open(path)
+            .map_err(|error| format!("Could not open {}: {error}", path.display()))?;
+        let mut archive: ZipArchive<File> = ZipArchive::new(file)
+            .map_err(|error| format!("Could not read archive {}: {error}", path.display()))?;
+
+        let mut files: BTreeMap<PathBuf, u64> = BTreeMap::new();
+        for index in 0..archive.len() {
+            let Ok(entry) = archive.by_index_raw(index) else {
+                continue;
+            };
+
+            if entry.is_dir() {
+                continue;
+            }
+
+            match crate::vfs::path::normalize(Path::new(entry.name())) {
+                Ok(name) => {
+                    files.insert(name, entry.size());
+                }
+                Err(error) => warn!("Skipping file in archive {}: {error}", path.display()),
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            archive: Mutex::new(archive),
+            files,
+        })
+    }
+}
+
+impl MountSource for ArchiveMount {
+    fn read(&self, path: &Path) -> Result<Cow<'static, [u8]>, String> {
+        if !self.files.contains_key(path) {
+            return Err(format!(
+                "Could not find {} in {}",
+                path.display(),
+                self.path.display()
+            ));
+        }
+
+        // Zip archives always use `/` as the separator
+        let name: String = path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut archive = self
+            .archive
+            .lock()
+            .map_err(|_| format!("Archive {} was poisoned", self.path.display()))?;
+        let mut file = archive.by_name(&name).map_err(|error| {
+            format!("Could not find {name} in {}: {error}", self.path.display())
+        })?;
+
+        let mut bytes: Vec<u8> = Vec::new();
+        file.read_to_end(&mut bytes).map_err(|error| {
+            format!(
+                "Could not read {name} from {}: {error}",
+                self.path.display()
+            )
+        })?;
+
+        Ok(Cow::Owned(bytes))
+    }
+
+    fn metadata(&self, path: &Path) -> Option<Metadata> {
+        tree_metadata(&self.files, path, |size| *size)
+    }
+
+    fn list(&self, path: &Path) -> Option<Vec<DirEntry>> {
+        tree_list(&self.files, path)
+    }
+}
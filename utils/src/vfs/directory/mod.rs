@@ -0,0 +1,65 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use crate::vfs::{DirEntry, EntryKind, Metadata, MountSource};
+
+/// Mount point serving a directory on the filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryMount {
+    /// Directory on the filesystem
+    root: PathBuf,
+}
+
+impl DirectoryMount {
+    /// Serves the files within a directory
+    #[must_use]
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+impl MountSource for DirectoryMount {
+    fn read(&self, path: &Path) -> Result<Cow<'static, [u8]>, String> {
+        let full_path: PathBuf = self.root.join(path);
+
+        std::fs::read(&full_path)
+            .map(Cow::Owned)
+            .map_err(|error| format!("Could not read {}: {error}", full_path.display()))
+    }
+
+    fn metadata(&self, path: &Path) -> Option<Metadata> {
+        let metadata: std::fs::Metadata = std::fs::metadata(self.root.join(path)).ok()?;
+
+        Some(if metadata.is_dir() {
+            Metadata {
+                kind: EntryKind::Directory,
+                len: 0,
+            }
+        } else {
+            Metadata {
+                kind: EntryKind::File,
+                len: metadata.len(),
+            }
+        })
+    }
+
+    fn list(&self, path: &Path) -> Option<Vec<DirEntry>> {
+        let entries = std::fs::read_dir(self.root.join(path)).ok()?;
+
+        Some(
+            entries
+                .flatten()
+                .map(|entry| DirEntry {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    kind: if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                        EntryKind::Directory
+                    } else {
+                        EntryKind::File
+                    },
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Component, Path},
+};
+
+use common::resources::{EmbeddedFile, EmbeddedFiles};
+
+use crate::vfs::{DirEntry, EntryKind, Metadata, MountSource};
+
+/// Mount point serving the resources embedded in the binary
+///
+/// Uncompressed files are served without copying them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddedMount {
+    /// Index of the embedded files
+    files: EmbeddedFiles,
+}
+
+impl EmbeddedMount {
+    /// Serves the embedded resources
+    #[must_use]
+    pub fn new(files: EmbeddedFiles) -> Self {
+        Self { files }
+    }
+
+    /// Retrieves a file, whose contents may still be compressed
+    #[must_use]
+    pub fn get(&self, path: &Path) -> Option<&'static EmbeddedFile> {
+        self.files.get(&embedded_path(path))
+    }
+
+    /// Embedded files inside a directory
+    fn inside(&self, path: &Path) -> &'static [EmbeddedFile] {
+        let directory: String = embedded_path(path);
+
+        if directory.is_empty() {
+            self.files.files()
+        } else {
+            self.files.with_prefix(&format!("{directory}/"))
+        }
+    }
+}
+
+impl MountSource for EmbeddedMount {
+    fn read(&self, path: &Path) -> Result<Cow<'static, [u8]>, String> {
+        let file: &'static EmbeddedFile = self.get(path).ok_or_else(|| {
+            format!(
+                "Asset Not Found - Unable To Load Embedded Asset: {}",
+                path.display()
+            )
+        })?;
+
+        crate::resources::decompress(file)
+    }
+
+    fn metadata(&self, path: &Path) -> Option<Metadata> {
+        if let Some(file) = self.get(path) {
+            return Some(Metadata {
+                kind: EntryKind::File,
+                len: file.size,
+            });
+        }
+
+        let is_directory: bool = path.as_os_str().is_empty() || !self.inside(path).is_empty();
+        is_directory.then_some(Metadata {
+            kind: EntryKind::Directory,
+            len: 0,
+        })
+    }
+
+    fn list(&self, path: &Path) -> Option<Vec<DirEntry>> {
+        let inside: &'static [EmbeddedFile] = self.inside(path);
+        if inside.is_empty() && !path.as_os_str().is_empty() {
+            return None;
+        }
+
+        let skip: usize = path.components().count();
+        let mut entries: BTreeMap<&str, EntryKind> = BTreeMap::new();
+        for file in inside {
+            let mut parts = file.path.split('/').skip(skip);
+            let Some(name) = parts.next() else {
+                continue;
+            };
+
+            let kind: EntryKind = if parts.next().is_some() {
+                EntryKind::Directory
+            } else {
+                EntryKind::File
+            };
+            entries.insert(name, kind);
+        }
+
+        Some(
+            entries
+                .into_iter()
+                .map(|(name, kind)| DirEntry {
+                    name: name.to_string(),
+                    kind,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Joins the components of a normalised path with `/` to match the embedded paths
+fn embedded_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<&str>>()
+        .join("/")
+}
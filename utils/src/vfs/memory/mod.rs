@@ -0,0 +1,132 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    ops::Bound,
+    path::{Component, Path, PathBuf},
+    sync::RwLock,
+};
+
+use crate::vfs::{DirEntry, EntryKind, Metadata, MountSource};
+
+/// Mount point holding files in memory which can be changed while mounted
+#[derive(Debug, Default)]
+pub struct MemoryMount {
+    /// Contents of each file keyed by their path
+    files: RwLock<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryMount {
+    /// Creates an empty mount point
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a file
+    ///
+    /// # Errors
+    ///
+    /// Errors if the path escapes the mount point
+    ///
+    /// # Panics
+    ///
+    /// May panic if the files' lock is poisoned
+    pub fn insert(&self, path: &Path, contents: Vec<u8>) -> Result<(), String> {
+        let path: PathBuf = crate::vfs::path::normalize(path)?;
+        self.files.write().unwrap().insert(path, contents);
+
+        Ok(())
+    }
+
+    /// Removes a file, returning its contents if it existed
+    ///
+    /// # Panics
+    ///
+    /// May panic if the files' lock is poisoned
+    pub fn remove(&self, path: &Path) -> Option<Vec<u8>> {
+        let path: PathBuf = crate::vfs::path::normalize(path).ok()?;
+
+        self.files.write().unwrap().remove(&path)
+    }
+}
+
+impl MountSource for MemoryMount {
+    fn read(&self, path: &Path) -> Result<Cow<'static, [u8]>, String> {
+        self.files
+            .read()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .map(Cow::Owned)
+            .ok_or_else(|| format!("{} is not in memory", path.display()))
+    }
+
+    fn metadata(&self, path: &Path) -> Option<Metadata> {
+        tree_metadata(&self.files.read().unwrap(), path, |contents| {
+            contents.len() as u64
+        })
+    }
+
+    fn list(&self, path: &Path) -> Option<Vec<DirEntry>> {
+        tree_list(&self.files.read().unwrap(), path)
+    }
+}
+
+/// Describes a path within a map of files, treating every prefix of a file's path as a directory
+pub(crate) fn tree_metadata<V>(
+    files: &BTreeMap<PathBuf, V>,
+    path: &Path,
+    size: impl Fn(&V) -> u64,
+) -> Option<Metadata> {
+    if let Some(file) = files.get(path) {
+        return Some(Metadata {
+            kind: EntryKind::File,
+            len: size(file),
+        });
+    }
+
+    // Paths inside a directory sort directly after the directory itself
+    let is_directory: bool = path.as_os_str().is_empty()
+        || files
+            .range::<Path, _>((Bound::Excluded(path), Bound::Unbounded))
+            .next()
+            .is_some_and(|(file, _)| file.starts_with(path));
+
+    is_directory.then_some(Metadata {
+        kind: EntryKind::Directory,
+        len: 0,
+    })
+}
+
+/// Lists the entries directly inside a directory within a map of files
+pub(crate) fn tree_list<V>(files: &BTreeMap<PathBuf, V>, path: &Path) -> Option<Vec<DirEntry>> {
+    let mut entries: BTreeMap<String, EntryKind> = BTreeMap::new();
+
+    let inside = files
+        .range::<Path, _>((Bound::Excluded(path), Bound::Unbounded))
+        .take_while(|(file, _)| file.starts_with(path));
+    for (file, _) in inside {
+        let mut components = file.strip_prefix(path).ok()?.components();
+        let Some(Component::Normal(name)) = components.next() else {
+            continue;
+        };
+
+        let kind: EntryKind = if components.next().is_some() {
+            EntryKind::Directory
+        } else {
+            EntryKind::File
+        };
+        entries.insert(name.to_string_lossy().to_string(), kind);
+    }
+
+    if entries.is_empty() && !path.as_os_str().is_empty() {
+        return None;
+    }
+
+    Some(
+        entries
+            .into_iter()
+            .map(|(name, kind)| DirEntry { name, kind })
+            .collect(),
+    )
+}
@@ -0,0 +1,499 @@
+/// Module for normalising virtual paths
+pub mod path;
+
+/// Module for mounting directories on the filesystem
+pub mod directory;
+
+/// Module for mounting the resources embedded in the binary
+pub mod embedded;
+
+/// Module for mounting zip archives
+pub mod archive;
+
+/// Module for mounting files held in memory
+pub mod memory;
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, RwLockReadGuard},
+};
+
+pub use archive::ArchiveMount;
+pub use directory::DirectoryMount;
+pub use embedded::EmbeddedMount;
+pub use memory::MemoryMount;
+
+/// Virtual filesystem every resource is read through
+static VFS: RwLock<Vfs> = RwLock::new(Vfs::new());
+
+/// Whether an entry is a file or a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntryKind {
+    /// Entry holds bytes
+    File,
+
+    /// Entry holds other entries
+    Directory,
+}
+
+/// Describes a file or directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    /// Whether the entry is a file or a directory
+    pub kind: EntryKind,
+
+    /// Size of a file in bytes, zero for directories
+    pub len: u64,
+}
+
+/// Entry directly inside a directory
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DirEntry {
+    /// Name of the entry
+    pub name: String,
+
+    /// Whether the entry is a file or a directory
+    pub kind: EntryKind,
+}
+
+/// Layer a mount point belongs to
+///
+/// Mount points in higher layers override the ones below them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    /// Resources embedded in the binary
+    Embedded,
+
+    /// Resources the user placed on the filesystem
+    User,
+
+    /// Resources provided by mods
+    Mod,
+}
+
+/// Source of files which can be mounted into the virtual filesystem
+///
+/// Paths are normalised and relative to the mount point, with an empty path being the mount point itself
+pub trait MountSource: Debug + Send + Sync {
+    /// Reads a file, borrowing its contents if they live for the whole program
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file does not exist or cannot be read
+    fn read(&self, path: &Path) -> Result<Cow<'static, [u8]>, String>;
+
+    /// Describes a file or directory, or `None` if it does not exist
+    fn metadata(&self, path: &Path) -> Option<Metadata>;
+
+    /// Lists the entries directly inside a directory, or `None` if it isn't a directory
+    fn list(&self, path: &Path) -> Option<Vec<DirEntry>>;
+}
+
+/// Source mounted at a virtual path
+#[derive(Debug, Clone)]
+struct Mount {
+    /// Virtual path the source is mounted at
+    point: PathBuf,
+
+    /// Layer the source belongs to
+    layer: Layer,
+
+    /// Files served at the mount point
+    source: Arc<dyn MountSource>,
+}
+
+/// Combines mount points into a single tree of files
+#[derive(Debug, Clone, Default)]
+pub struct Vfs {
+    /// Mount points ordered from lowest to highest priority
+    mounts: Vec<Mount>,
+}
+
+impl Vfs {
+    /// Creates a virtual filesystem without any mount points
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { mounts: Vec::new() }
+    }
+
+    /// Mounts a source at a virtual path
+    ///
+    /// Within a layer, sources mounted later override the ones mounted before them
+    ///
+    /// # Errors
+    ///
+    /// Errors if the mount point escapes the root
+    pub fn mount(
+        &mut self,
+        point: &Path,
+        layer: Layer,
+        source: Arc<dyn MountSource>,
+    ) -> Result<(), String> {
+        let point: PathBuf = path::normalize(point)?;
+
+        // Keeps mounts sorted by layer while preserving mount order within a layer
+        let index: usize = self.mounts.partition_point(|mount| mount.layer <= layer);
+        self.mounts.insert(
+            index,
+            Mount {
+                point,
+                layer,
+                source,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Reads a file from the highest priority source which has it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the path escapes the root or no source has the file
+    pub fn read(&self, path: &Path) -> Result<Cow<'static, [u8]>, String> {
+        self.read_matching(path, |_| true)
+    }
+
+    /// Reads a file from the highest priority source in a layer which has it, ignoring the other layers
+    ///
+    /// # Errors
+    ///
+    /// Errors if the path escapes the root or no source in the layer has the file
+    pub fn read_layer(&self, layer: Layer, path: &Path) -> Result<Cow<'static, [u8]>, String> {
+        self.read_matching(path, |mount| mount.layer == layer)
+    }
+
+    /// Reads a file from the highest priority matching source which has it
+    fn read_matching(
+        &self,
+        path: &Path,
+        matches: impl Fn(&Mount) -> bool,
+    ) -> Result<Cow<'static, [u8]>, String> {
+        let path: PathBuf = path::normalize(path)?;
+
+        for (mount, relative) in self.resolve(&path).filter(|(mount, _)| matches(mount)) {
+            if mount
+                .source
+                .metadata(relative)
+                .is_some_and(|metadata| metadata.kind == EntryKind::File)
+            {
+                return mount.source.read(relative);
+            }
+        }
+
+        Err(format!(
+            "Asset Not Found - Unable To Load Asset: {}",
+            path.display()
+        ))
+    }
+
+    /// Reads a UTF-8 file from the highest priority source which has it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file cannot be read or isn't UTF-8
+    pub fn read_string(&self, path: &Path) -> Result<String, String> {
+        String::from_utf8(self.read(path)?.into_owned())
+            .map_err(|_| format!("{} is not valid UTF-8", path.display()))
+    }
+
+    /// Whether a file or directory exists
+    #[must_use]
+    pub fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// Describes a file or directory from the highest priority source which has it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the path escapes the root or doesn't exist
+    pub fn metadata(&self, path: &Path) -> Result<Metadata, String> {
+        let path: PathBuf = path::normalize(path)?;
+
+        // Directories leading up to a mount point exist even if nothing is mounted there
+        let directory: Metadata = Metadata {
+            kind: EntryKind::Directory,
+            len: 0,
+        };
+        if self
+            .mounts
+            .iter()
+            .any(|mount| mount.point.starts_with(&path))
+        {
+            return Ok(directory);
+        }
+
+        let metadata: Option<Metadata> = self
+            .resolve(&path)
+            .find_map(|(mount, relative)| mount.source.metadata(relative));
+
+        metadata.ok_or_else(|| format!("{} does not exist", path.display()))
+    }
+
+    /// Lists the entries directly inside a directory across every source
+    ///
+    /// Entries are sorted by name and an entry in a higher priority source hides the same entry below it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the path escapes the root or isn't a directory in any source
+    pub fn list(&self, path: &Path) -> Result<Vec<DirEntry>, String> {
+        self.list_matching(path, |_| true)
+    }
+
+    /// Lists the entries directly inside a directory across every source in a layer, ignoring the other layers
+    ///
+    /// # Errors
+    ///
+    /// Errors if the path escapes the root or isn't a directory in any source in the layer
+    pub fn list_layer(&self, layer: Layer, path: &Path) -> Result<Vec<DirEntry>, String> {
+        self.list_matching(path, |mount| mount.layer == layer)
+    }
+
+    /// Lists the entries directly inside a directory across every matching source
+    fn list_matching(
+        &self,
+        path: &Path,
+        matches: impl Fn(&Mount) -> bool,
+    ) -> Result<Vec<DirEntry>, String> {
+        let path: PathBuf = path::normalize(path)?;
+        let mut entries: BTreeMap<String, EntryKind> = BTreeMap::new();
+        let mut found: bool = false;
+
+        for mount in self.mounts.iter().rev().filter(|mount| matches(mount)) {
+            if let Ok(relative) = path.strip_prefix(&mount.point) {
+                let Some(listed) = mount.source.list(relative) else {
+                    continue;
+                };
+
+                found = true;
+                for entry in listed {
+                    entries.entry(entry.name).or_insert(entry.kind);
+                }
+            } else if let Ok(rest) = mount.point.strip_prefix(&path) {
+                // The mount point is deeper, so its next component shows up as a directory
+                if let Some(name) = rest.components().next() {
+                    found = true;
+                    entries
+                        .entry(name.as_os_str().to_string_lossy().to_string())
+                        .or_insert(EntryKind::Directory);
+                }
+            }
+        }
+
+        if !found {
+            return Err(format!("{} is not a directory", path.display()));
+        }
+
+        Ok(entries
+            .into_iter()
+            .map(|(name, kind)| DirEntry { name, kind })
+            .collect())
+    }
+
+    /// Lists every file under a directory across every source, in the order the sources were mounted
+    ///
+    /// Sources are walked from lowest to highest priority, so mods come after the resources they build on.
+    /// A file provided by several sources is listed once, where the first source provided it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the path escapes the root
+    pub fn walk_files(&self, path: &Path) -> Result<Vec<PathBuf>, String> {
+        let path: PathBuf = path::normalize(path)?;
+        let mut seen: BTreeSet<PathBuf> = BTreeSet::new();
+        let mut files: Vec<PathBuf> = Vec::new();
+
+        for mount in &self.mounts {
+            // Mount points deeper than the path are walked from their root
+            let start: &Path = if let Ok(relative) = path.strip_prefix(&mount.point) {
+                relative
+            } else if mount.point.starts_with(&path) {
+                Path::new("")
+            } else {
+                continue;
+            };
+
+            for file in walk(mount.source.as_ref(), start) {
+                let file: PathBuf = mount.point.join(file);
+                if seen.insert(file.clone()) {
+                    files.push(file);
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Mount points containing a path from highest to lowest priority, with the path relative to each
+    fn resolve<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = (&'a Mount, &'a Path)> {
+        self.mounts
+            .iter()
+            .rev()
+            .filter_map(move |mount| Some((mount, path.strip_prefix(&mount.point).ok()?)))
+    }
+}
+
+/// Get the virtual filesystem every resource is read through
+///
+/// # Panics
+///
+/// May panic if the virtual filesystem's lock is poisoned
+pub fn get_vfs() -> RwLockReadGuard<'static, Vfs> {
+    VFS.read().unwrap()
+}
+
+/// Mounts a source into the virtual filesystem every resource is read through
+///
+/// # Errors
+///
+/// Errors if the mount point escapes the root
+///
+/// # Panics
+///
+/// May panic if the virtual filesystem's lock is poisoned
+pub fn mount(point: &Path, layer: Layer, source: Arc<dyn MountSource>) -> Result<(), String> {
+    VFS.write().unwrap().mount(point, layer, source)
+}
+
+/// Reads a file from the virtual filesystem
+///
+/// # Errors
+///
+/// Errors if the path escapes the root or no source has the file
+pub fn read(path: &Path) -> Result<Cow<'static, [u8]>, String> {
+    get_vfs().read(path)
+}
+
+/// Reads a UTF-8 file from the virtual filesystem
+///
+/// # Errors
+///
+/// Errors if the file cannot be read or isn't UTF-8
+pub fn read_string(path: &Path) -> Result<String, String> {
+    get_vfs().read_string(path)
+}
+
+/// Whether a file or directory exists in the virtual filesystem
+#[must_use]
+pub fn exists(path: &Path) -> bool {
+    get_vfs().exists(path)
+}
+
+/// Describes a file or directory in the virtual filesystem
+///
+/// # Errors
+///
+/// Errors if the path escapes the root or doesn't exist
+pub fn metadata(path: &Path) -> Result<Metadata, String> {
+    get_vfs().metadata(path)
+}
+
+/// Lists the entries directly inside a directory in the virtual filesystem
+///
+/// # Errors
+///
+/// Errors if the path escapes the root or isn't a directory in any source
+pub fn list(path: &Path) -> Result<Vec<DirEntry>, String> {
+    get_vfs().list(path)
+}
+
+/// Lists every file inside a source, relative to the path given
+pub(crate) fn walk(source: &dyn MountSource, path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = Vec::new();
+
+    for entry in source.list(path).unwrap_or_default() {
+        let entry_path: PathBuf = path.join(&entry.name);
+
+        match entry.kind {
+            EntryKind::File => files.push(entry_path),
+            EntryKind::Directory => files.extend(walk(source, &entry_path)),
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use super::{DirEntry, EntryKind, Layer, MemoryMount, Vfs};
+
+    /// Creates an in-memory source with the given files
+    fn memory(files: &[(&str, &str)]) -> Arc<MemoryMount> {
+        let mount: MemoryMount = MemoryMount::new();
+        for (path, contents) in files {
+            mount
+                .insert(Path::new(path), contents.as_bytes().to_vec())
+                .unwrap();
+        }
+
+        Arc::new(mount)
+    }
+
+    #[test]
+    fn test_layers_override_lower_layers() {
+        let mut vfs: Vfs = Vfs::new();
+        vfs.mount(
+            Path::new("resources"),
+            Layer::Mod,
+            memory(&[("a.txt", "mod")]),
+        )
+        .unwrap();
+        vfs.mount(
+            Path::new(""),
+            Layer::Embedded,
+            memory(&[
+                ("resources/a.txt", "embedded"),
+                ("resources/b/c.txt", "embedded"),
+            ]),
+        )
+        .unwrap();
+        vfs.mount(
+            Path::new("resources"),
+            Layer::User,
+            memory(&[("a.txt", "user"), ("b/c.txt", "user")]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vfs.read_string(Path::new("resources/a.txt")),
+            Ok("mod".to_string())
+        );
+        assert_eq!(
+            vfs.read_string(Path::new("./resources/b/../b/c.txt")),
+            Ok("user".to_string())
+        );
+        assert!(vfs.read(Path::new("resources/../../a.txt")).is_err());
+
+        assert!(vfs.exists(Path::new("resources/b")));
+        assert!(!vfs.exists(Path::new("resources/missing.txt")));
+        assert_eq!(vfs.metadata(Path::new("resources/b/c.txt")).unwrap().len, 4);
+
+        assert_eq!(
+            vfs.list(Path::new("resources")),
+            Ok(vec![
+                DirEntry {
+                    name: "a.txt".to_string(),
+                    kind: EntryKind::File
+                },
+                DirEntry {
+                    name: "b".to_string(),
+                    kind: EntryKind::Directory
+                },
+            ])
+        );
+        assert_eq!(
+            vfs.list(Path::new("")),
+            Ok(vec![DirEntry {
+                name: "resources".to_string(),
+                kind: EntryKind::Directory
+            }])
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Normalises a virtual path so it can be matched against mount points
+///
+/// Removes `.` components and resolves `..` components against the components before them
+///
+/// # Errors
+///
+/// Errors if the path is absolute or a `..` component would leave the root
+pub fn normalize(path: &Path) -> Result<PathBuf, String> {
+    let mut normalized: PathBuf = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(format!("{} escapes the root", path.display()));
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!("{} is not a relative path", path.display()));
+            }
+        }
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::normalize;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(Path::new("./resources/assets/../locales/en.toml")),
+            Ok(PathBuf::from("resources/locales/en.toml"))
+        );
+        assert_eq!(normalize(Path::new("")), Ok(PathBuf::new()));
+        assert!(normalize(Path::new("resources/../../secret")).is_err());
+        assert!(normalize(Path::new("/etc/passwd")).is_err());
+    }
+}